@@ -205,7 +205,9 @@ impl fmt::Display for SynthesisError {
             write!(f, "I/O error: ")?;
             e.fmt(f)
         } else {
-            write!(f, "{}", self.to_string())
+            #[allow(deprecated)]
+            let description = self.description();
+            write!(f, "{}", description)
         }
     }
 }
@@ -39,33 +39,31 @@ use crate::worker::{
     Worker
 };
 
-// /// Generates a random common reference string for
-// /// a circuit.
-// pub fn generate_random_parameters<E, C, R>(
-//     circuit: C,
-//     rng: &mut R
-// ) -> Result<Parameters<E>, SynthesisError>
-//     where E: Engine, C: Circuit<E>, R: Rng
-// {
-//     let g1 = rng.gen();
-//     let g2 = rng.gen();
-//     let alpha = rng.gen();
-//     let beta = rng.gen();
-//     let gamma = rng.gen();
-//     let delta = rng.gen();
-//     let tau = rng.gen();
-
-//     generate_parameters::<E, C>(
-//         circuit,
-//         g1,
-//         g2,
-//         alpha,
-//         beta,
-//         gamma,
-//         delta,
-//         tau
-//     )
-// }
+/// Generates a random common reference string for
+/// a circuit.
+pub fn generate_random_parameters<E, C, R>(
+    circuit: C,
+    rng: &mut R
+) -> Result<Parameters<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>, R: Rng
+{
+    let g1 = rng.gen();
+    let g2 = rng.gen();
+    let alpha = rng.gen();
+    let beta = rng.gen();
+    let gamma = rng.gen();
+    let tau = rng.gen();
+
+    generate_parameters::<E, C>(
+        circuit,
+        g1,
+        g2,
+        alpha,
+        beta,
+        gamma,
+        tau
+    )
+}
 
 /// This is our assembly structure that we'll use to synthesize the
 /// circuit into a SAP. Square arithmetic problem is different from QAP in a form:
@@ -233,10 +231,8 @@ pub fn generate_parameters<E, C>(
     alpha: E::Fr,
     beta: E::Fr,
     gamma: E::Fr,
-    // delta: E::Fr,
     tau: E::Fr
-) -> Result<(), SynthesisError>
-// Result<Parameters<E>, SynthesisError>
+) -> Result<Parameters<E>, SynthesisError>
     where E: Engine, C: Circuit<E>
 {
     let verbose = verbose_flag();
@@ -282,9 +278,10 @@ pub fn generate_parameters<E, C>(
         }
 
         let mut lc_input_0_a: LinearCombination<E> = LinearCombination::zero();
-        lc_input_0_a = lc_input_0_a.add(input_0.clone());
-        eval_lc(lc_input_0_a, &mut assembly.at_inputs, &mut assembly.at_aux, assembly.num_constraints);
-        
+        lc_input_0_a = lc_input_0_a.add(input_0);
+        eval_lc(lc_input_0_a.clone(), &mut assembly.at_inputs, &mut assembly.at_aux, assembly.num_constraints);
+        eval_lc(lc_input_0_a, &mut assembly.ct_inputs, &mut assembly.ct_aux, assembly.num_constraints);
+
         assembly.num_constraints += 1;
     }
 
@@ -375,7 +372,7 @@ pub fn generate_parameters<E, C>(
 
     // G1^{gamma^2 * Z(t) * t^i} for 0 <= i < 2^m - 1 for 2^m domains
     let mut gamma2_z_t_g1 = vec![E::G1::zero(); domain.as_ref().len() - 1];
-    let mut z_at_tau = E::Fr::zero();
+    let z_at_tau;
 
     {
         // Compute powers of tau
@@ -387,7 +384,7 @@ pub fn generate_parameters<E, C>(
                 for (i, subdomain) in domain.chunks_mut(chunk).enumerate()
                 {
                     scope.spawn(move |_| {
-                        let mut current_power = tau.pow(&[(i*chunk) as u64]);
+                        let mut current_power = tau.pow([(i*chunk) as u64]);
 
                         for p in subdomain {
                             p.0 = current_power;
@@ -442,28 +439,32 @@ pub fn generate_parameters<E, C>(
     // G1^{2 * gamma^2 * Z(t) * A_i(t)} for 0 <= i <= num_variables
     let mut c_2_g1 = vec![E::G1::zero(); assembly.num_inputs + assembly.num_aux];
 
-    // G1^{gamma * Z(t)}
+    if z_at_tau.is_zero() {
+        return Err(SynthesisError::UnexpectedIdentity);
+    }
+    let gamma_inverse = gamma.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    let g1 = g1.into_affine();
+    let g2 = g2.into_affine();
+
     let mut gamma_zt = gamma;
     gamma_zt.mul_assign(&z_at_tau);
 
-    let gamma_z = g1.into_affine().mul(gamma.into_repr());
+    // G1^{gamma * Z(t)}
+    let gamma_z = g1.mul(gamma_zt.into_repr());
     // G2^{gamma * Z(t)}
-    let gamma_z_g2 = g2.into_affine().mul(gamma.into_repr());
+    let gamma_z_g2 = g2.mul(gamma_zt.into_repr());
 
-    let mut ab_gamma = alpha;
-    ab_gamma.add_assign(&beta);
-    ab_gamma.mul_assign(&gamma);
+    let mut ab_gamma_z = alpha;
+    ab_gamma_z.add_assign(&beta);
+    ab_gamma_z.mul_assign(&gamma_zt);
     // G1^{(alpha + beta) * gamma * Z(t)}
-    let ab_gamma_z_g1 = g1.into_affine().mul(ab_gamma.into_repr());
+    let ab_gamma_z_g1 = g1.mul(ab_gamma_z.into_repr());
 
-    let mut gamma2_z2 = gamma;
-    gamma2_z2.mul_assign(&z_at_tau);
+    let mut gamma2_z2 = gamma_zt;
     gamma2_z2.square();
     // G1^{gamma^2 * Z(t)^2}
-    let gamma2_z2_g1 = g1.into_affine().mul(gamma2_z2.into_repr());
-
-    // G^{gamma^2 * Z(t) * t^i} for 0 <= i < 2^m - 1 for 2^m domains
-    let mut gamma2_z_t = vec![E::G1::zero(); domain.as_ref().len() - 1];
+    let gamma2_z2_g1 = g1.mul(gamma2_z2.into_repr());
 
     if verbose {eprintln!("using inverse FFT to convert to intepolation coefficients...")};
     
@@ -486,6 +487,7 @@ pub fn generate_parameters<E, C>(
     // c_1_g1 = (t1 + ab*t0)*G1
     // c_2_g1 = (2*gamma*z_at_tau*t0)*G1
 
+    #[allow(clippy::too_many_arguments)]
     fn eval_stage_1<E: Engine>(
         // wNAF window tables
         g1_wnaf: &Wnaf<usize, &[E::G1], &mut Vec<i64>>,
@@ -524,7 +526,7 @@ pub fn generate_parameters<E, C>(
 
         // compute once
         let mut ab = *alpha;
-        ab.add_assign(&beta);
+        ab.add_assign(beta);
 
         let mut gamma2 = *gamma;
         gamma2.square();
@@ -568,7 +570,7 @@ pub fn generate_parameters<E, C>(
                         // Evaluate SAP polynomials at tau
                         // t0 = gamma*A_i(t)
                         let mut t0 = eval_at_tau(powers_of_tau, at);
-                        t0.mul_assign(&gamma);
+                        t0.mul_assign(gamma);
                         // t1 = gamma^2*C_t(t)
                         let mut t1 = eval_at_tau(powers_of_tau, ct);
                         t1.mul_assign(&gamma2);
@@ -589,8 +591,8 @@ pub fn generate_parameters<E, C>(
                         c_1_g1_factor.add_assign(&t1);
 
                         // (2*gamma*z_at_tau*t0) inplace
-                        t0.mul_assign(&z_at_tau);
-                        t0.mul_assign(&gamma);
+                        t0.mul_assign(z_at_tau);
+                        t0.mul_assign(gamma);
                         t0.double();
 
                         *c_1_g1 = g1_wnaf.scalar(c_1_g1_factor.into_repr());
@@ -625,7 +627,7 @@ pub fn generate_parameters<E, C>(
         &worker
     );
 
-    // Evaluate for inputs.
+    // Evaluate for auxillary variables.
     eval_stage_1(
         &g1_wnaf,
         &g2_wnaf,
@@ -643,43 +645,48 @@ pub fn generate_parameters<E, C>(
         &worker
     );
 
-    // for _ in 0..assembly.num_inputs {
-    //     c_1_g1.remove(0);
-    // }
+    // The verifier pairs the inputs part of the C_1 query with gamma in G2,
+    // so it is G1^{gamma * C_i(t) + (alpha + beta) * A_i(t)} in the key.
+    let ic = c_1_g1.drain(0..assembly.num_inputs)
+                   .map(|e| e.into_affine().mul(gamma_inverse.into_repr()).into_affine())
+                   .collect::<Vec<_>>();
+
+    // Don't allow any elements be unconstrained, so that
+    // the C_1 query is always fully dense.
+    for e in c_1_g1.iter() {
+        if e.is_zero() {
+            return Err(SynthesisError::UnconstrainedVariable);
+        }
+    }
 
-    // // Don't allow any elements be unconstrained, so that
-    // // the L query is always fully dense.
-    // for e in l.iter() {
-    //     if e.is_zero() {
-    //         return Err(SynthesisError::UnconstrainedVariable);
-    //     }
-    // }
-
-    // let g1 = g1.into_affine();
-    // let g2 = g2.into_affine();
-
-    // let vk = VerifyingKey::<E> {
-    //     alpha_g1: g1.mul(alpha).into_affine(),
-    //     beta_g1: g1.mul(beta).into_affine(),
-    //     beta_g2: g2.mul(beta).into_affine(),
-    //     gamma_g2: g2.mul(gamma).into_affine(),
-    //     delta_g1: g1.mul(delta).into_affine(),
-    //     delta_g2: g2.mul(delta).into_affine(),
-    //     ic: ic.into_iter().map(|e| e.into_affine()).collect()
-    // };
+    let vk = VerifyingKey::<E> {
+        h_g2: g2,
+        alpha_g1: g1.mul(alpha).into_affine(),
+        beta_g2: g2.mul(beta).into_affine(),
+        gamma_g1: g1.mul(gamma).into_affine(),
+        gamma_g2: g2.mul(gamma).into_affine(),
+        ic
+    };
+
+    if verbose {eprintln!("Has generated {} points", a_g1.len())};
+
+    Ok(Parameters {
+        vk,
+
+        // Filter points at infinity away from A/C_2 queries, they have the
+        // same density since both are multiples of A_i(t)
+        a_g1: Arc::new(a_g1.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
+        a_g2: Arc::new(a_g2.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
 
-    println!("Has generated {} points", a_g1.len());
+        c_1_g1: Arc::new(c_1_g1.into_iter().map(|e| e.into_affine()).collect()),
+        c_2_g1: Arc::new(c_2_g1.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
 
-    Ok(())
+        gamma_z: gamma_z.into_affine(),
+        gamma_z_g2: gamma_z_g2.into_affine(),
 
-    // Ok(Parameters {
-    //     vk: vk,
-    //     h: Arc::new(h.into_iter().map(|e| e.into_affine()).collect()),
-    //     l: Arc::new(l.into_iter().map(|e| e.into_affine()).collect()),
+        ab_gamma_z_g1: ab_gamma_z_g1.into_affine(),
+        gamma2_z2_g1: gamma2_z2_g1.into_affine(),
 
-    //     // Filter points at infinity away from A/B queries
-    //     a: Arc::new(a.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
-    //     b_g1: Arc::new(b_g1.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
-    //     b_g2: Arc::new(b_g2.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect())
-    // })
+        gamma2_z_t: Arc::new(gamma2_z_t_g1.into_iter().map(|e| e.into_affine()).collect()),
+    })
 }
@@ -17,12 +17,12 @@ use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
 mod tests;
 
 mod generator;
-// mod prover;
-// mod verifier;
+mod prover;
+mod verifier;
 
 pub use self::generator::*;
-// pub use self::prover::*;
-// pub use self::verifier::*;
+pub use self::prover::*;
+pub use self::verifier::*;
 
 #[derive(Debug, Clone)]
 pub struct Proof<E: Engine> {
@@ -90,9 +90,9 @@ impl<E: Engine> Proof<E> {
                 })?;
 
         Ok(Proof {
-            a: a,
-            b: b,
-            c: c
+            a,
+            b,
+            c
         })
     }
 }
@@ -193,11 +193,11 @@ impl<E: Engine> VerifyingKey<E> {
 
         Ok(VerifyingKey {
             h_g2: h_h2,
-            alpha_g1: alpha_g1,
-            beta_g2: beta_g2,
-            gamma_g1: gamma_g1,
-            gamma_g2: gamma_g2,
-            ic: ic
+            alpha_g1,
+            beta_g2,
+            gamma_g1,
+            gamma_g2,
+            ic
         })
     }
 }
@@ -235,149 +235,171 @@ impl<E: Engine> PartialEq for Parameters<E> {
     }
 }
 
-// impl<E: Engine> Parameters<E> {
-//     pub fn write<W: Write>(
-//         &self,
-//         mut writer: W
-//     ) -> io::Result<()>
-//     {
-//         self.vk.write(&mut writer)?;
+impl<E: Engine> Parameters<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        self.vk.write(&mut writer)?;
 
-//         writer.write_u32::<BigEndian>(self.h.len() as u32)?;
-//         for g in &self.h[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
+        writer.write_u32::<BigEndian>(self.a_g1.len() as u32)?;
+        for g in &self.a_g1[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
 
-//         writer.write_u32::<BigEndian>(self.l.len() as u32)?;
-//         for g in &self.l[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
+        writer.write_u32::<BigEndian>(self.a_g2.len() as u32)?;
+        for g in &self.a_g2[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
 
-//         writer.write_u32::<BigEndian>(self.a.len() as u32)?;
-//         for g in &self.a[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
+        writer.write_u32::<BigEndian>(self.c_1_g1.len() as u32)?;
+        for g in &self.c_1_g1[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
 
-//         writer.write_u32::<BigEndian>(self.b_g1.len() as u32)?;
-//         for g in &self.b_g1[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
+        writer.write_u32::<BigEndian>(self.c_2_g1.len() as u32)?;
+        for g in &self.c_2_g1[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
 
-//         writer.write_u32::<BigEndian>(self.b_g2.len() as u32)?;
-//         for g in &self.b_g2[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
+        writer.write_all(self.gamma_z.into_uncompressed().as_ref())?;
+        writer.write_all(self.gamma_z_g2.into_uncompressed().as_ref())?;
+        writer.write_all(self.ab_gamma_z_g1.into_uncompressed().as_ref())?;
+        writer.write_all(self.gamma2_z2_g1.into_uncompressed().as_ref())?;
 
-//         Ok(())
-//     }
+        writer.write_u32::<BigEndian>(self.gamma2_z_t.len() as u32)?;
+        for g in &self.gamma2_z_t[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
 
-//     pub fn read<R: Read>(
-//         mut reader: R,
-//         checked: bool
-//     ) -> io::Result<Self>
-//     {
-//         let read_g1 = |reader: &mut R| -> io::Result<E::G1Affine> {
-//             let mut repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
-//             reader.read_exact(repr.as_mut())?;
-
-//             if checked {
-//                 repr
-//                 .into_affine()
-//             } else {
-//                 repr
-//                 .into_affine_unchecked()
-//             }
-//             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-//             .and_then(|e| if e.is_zero() {
-//                 Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-//             } else {
-//                 Ok(e)
-//             })
-//         };
-
-//         let read_g2 = |reader: &mut R| -> io::Result<E::G2Affine> {
-//             let mut repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
-//             reader.read_exact(repr.as_mut())?;
-
-//             if checked {
-//                 repr
-//                 .into_affine()
-//             } else {
-//                 repr
-//                 .into_affine_unchecked()
-//             }
-//             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-//             .and_then(|e| if e.is_zero() {
-//                 Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-//             } else {
-//                 Ok(e)
-//             })
-//         };
-
-//         let vk = VerifyingKey::<E>::read(&mut reader)?;
-
-//         let mut h = vec![];
-//         let mut l = vec![];
-//         let mut a = vec![];
-//         let mut b_g1 = vec![];
-//         let mut b_g2 = vec![];
+        Ok(())
+    }
 
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 h.push(read_g1(&mut reader)?);
-//             }
-//         }
+    pub fn read<R: Read>(
+        mut reader: R,
+        checked: bool
+    ) -> io::Result<Self>
+    {
+        let read_g1 = |reader: &mut R| -> io::Result<E::G1Affine> {
+            let mut repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
 
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 l.push(read_g1(&mut reader)?);
-//             }
-//         }
+            if checked {
+                repr
+                .into_affine()
+            } else {
+                repr
+                .into_affine_unchecked()
+            }
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            .and_then(|e| if e.is_zero() {
+                Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
+            } else {
+                Ok(e)
+            })
+        };
+
+        let read_g2 = |reader: &mut R| -> io::Result<E::G2Affine> {
+            let mut repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
+
+            if checked {
+                repr
+                .into_affine()
+            } else {
+                repr
+                .into_affine_unchecked()
+            }
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            .and_then(|e| if e.is_zero() {
+                Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
+            } else {
+                Ok(e)
+            })
+        };
+
+        let vk = VerifyingKey::<E>::read(&mut reader)?;
+
+        let mut a_g1 = vec![];
+        let mut a_g2 = vec![];
+        let mut c_1_g1 = vec![];
+        let mut c_2_g1 = vec![];
+        let mut gamma2_z_t = vec![];
+
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                a_g1.push(read_g1(&mut reader)?);
+            }
+        }
 
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 a.push(read_g1(&mut reader)?);
-//             }
-//         }
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                a_g2.push(read_g2(&mut reader)?);
+            }
+        }
 
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 b_g1.push(read_g1(&mut reader)?);
-//             }
-//         }
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                c_1_g1.push(read_g1(&mut reader)?);
+            }
+        }
 
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 b_g2.push(read_g2(&mut reader)?);
-//             }
-//         }
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                c_2_g1.push(read_g1(&mut reader)?);
+            }
+        }
 
-//         Ok(Parameters {
-//             vk: vk,
-//             h: Arc::new(h),
-//             l: Arc::new(l),
-//             a: Arc::new(a),
-//             b_g1: Arc::new(b_g1),
-//             b_g2: Arc::new(b_g2)
-//         })
-//     }
-// }
+        let gamma_z = read_g1(&mut reader)?;
+        let gamma_z_g2 = read_g2(&mut reader)?;
+        let ab_gamma_z_g1 = read_g1(&mut reader)?;
+        let gamma2_z2_g1 = read_g1(&mut reader)?;
 
-// pub struct PreparedVerifyingKey<E: Engine> {
-//     /// Pairing result of alpha*beta
-//     alpha_g1_beta_g2: E::Fqk,
-//     /// -gamma in G2
-//     neg_gamma_g2: <E::G2Affine as CurveAffine>::Prepared,
-//     /// -delta in G2
-//     neg_delta_g2: <E::G2Affine as CurveAffine>::Prepared,
-//     /// Copy of IC from `VerifiyingKey`.
-//     ic: Vec<E::G1Affine>
-// }
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                gamma2_z_t.push(read_g1(&mut reader)?);
+            }
+        }
+
+        Ok(Parameters {
+            vk,
+            a_g1: Arc::new(a_g1),
+            a_g2: Arc::new(a_g2),
+            c_1_g1: Arc::new(c_1_g1),
+            c_2_g1: Arc::new(c_2_g1),
+            gamma_z,
+            gamma_z_g2,
+            ab_gamma_z_g1,
+            gamma2_z2_g1,
+            gamma2_z_t: Arc::new(gamma2_z_t)
+        })
+    }
+}
+
+pub struct PreparedVerifyingKey<E: Engine> {
+    /// Pairing result of alpha*beta
+    alpha_g1_beta_g2: E::Fqk,
+    /// alpha in G1, added to A
+    alpha_g1: E::G1Affine,
+    /// beta in G2, added to B
+    beta_g2: E::G2Affine,
+    /// gamma in G1
+    gamma_g1: E::G1Affine,
+    /// gamma in G2
+    gamma_g2: E::G2Affine,
+    /// -gamma in G2
+    neg_gamma_g2: <E::G2Affine as CurveAffine>::Prepared,
+    /// -H in G2
+    neg_h_g2: <E::G2Affine as CurveAffine>::Prepared,
+    /// Copy of IC from `VerifiyingKey`.
+    ic: Vec<E::G1Affine>
+}
 
 // pub trait ParameterSource<E: Engine> {
 //     type G1Builder: SourceBuilder<E::G1Affine>;
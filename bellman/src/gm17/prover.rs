@@ -0,0 +1,332 @@
+use rand::Rng;
+
+use std::sync::Arc;
+
+use crate::pairing::{
+    Engine,
+    CurveProjective,
+    CurveAffine
+};
+
+use crate::pairing::ff::{
+    PrimeField,
+    Field
+};
+
+use super::{
+    Parameters,
+    Proof
+};
+
+use crate::{
+    SynthesisError,
+    Circuit,
+    ConstraintSystem,
+    LinearCombination,
+    Variable,
+    Index
+};
+
+use crate::domain::{
+    EvaluationDomain,
+    Scalar
+};
+
+use crate::source::{
+    DensityTracker,
+    FullDensity
+};
+
+use crate::multiexp::*;
+
+use crate::worker::{
+    Worker
+};
+
+use crate::groth16::{
+    field_elements_into_representations,
+    scalars_into_representations
+};
+
+fn eval<E: Engine>(
+    lc: &LinearCombination<E>,
+    mut aux_density: Option<&mut DensityTracker>,
+    input_assignment: &[E::Fr],
+    aux_assignment: &[E::Fr]
+) -> E::Fr
+{
+    let mut acc = E::Fr::zero();
+
+    for &(index, coeff) in lc.0.iter() {
+        let mut tmp;
+
+        match index {
+            Variable(Index::Input(i)) => {
+                tmp = input_assignment[i];
+            },
+            Variable(Index::Aux(i)) => {
+                tmp = aux_assignment[i];
+                if let Some(ref mut v) = aux_density {
+                    v.inc(i);
+                }
+            }
+        }
+
+        if coeff == E::Fr::one() {
+           acc.add_assign(&tmp);
+        } else {
+           tmp.mul_assign(&coeff);
+           acc.add_assign(&tmp);
+        }
+    }
+
+    acc
+}
+
+/// Synthesizes the circuit into a SAP the same way `generate_parameters`
+/// does, assigning the extra variables the reduction introduces.
+struct ProvingAssignment<E: Engine> {
+    // Density of the A query, inputs have full density
+    a_aux_density: DensityTracker,
+
+    // Evaluations of A and C polynomials
+    a: Vec<Scalar<E>>,
+    c: Vec<Scalar<E>>,
+
+    // Assignments of variables
+    input_assignment: Vec<E::Fr>,
+    aux_assignment: Vec<E::Fr>
+}
+
+impl<E: Engine> ConstraintSystem<E> for ProvingAssignment<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(
+        &mut self,
+        _: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        self.aux_assignment.push(f()?);
+        self.a_aux_density.add_element();
+
+        Ok(Variable(Index::Aux(self.aux_assignment.len() - 1)))
+    }
+
+    fn alloc_input<F, A, AR>(
+        &mut self,
+        _: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        self.input_assignment.push(f()?);
+
+        Ok(Variable(Index::Input(self.input_assignment.len() - 1)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(
+        &mut self,
+        _: A,
+        a: LA,
+        b: LB,
+        c: LC
+    )
+        where A: FnOnce() -> AR, AR: Into<String>,
+              LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>
+    {
+        // <a,x>*<b,x> = <c,x> is reduced to
+        // <a - b,x> * <a - b,x> = y
+        // <a + b,x> * <a + b,x> = 4*<c,x> + y
+        let a = a(LinearCombination::zero());
+        let b = b(LinearCombination::zero());
+        let c = c(LinearCombination::zero());
+
+        let a_minus_b = eval(
+            &(a.clone() - &b),
+            Some(&mut self.a_aux_density),
+            &self.input_assignment,
+            &self.aux_assignment
+        );
+        let a_plus_b = eval(
+            &(a + &b),
+            Some(&mut self.a_aux_density),
+            &self.input_assignment,
+            &self.aux_assignment
+        );
+        let mut c = eval(
+            &c,
+            None,
+            &self.input_assignment,
+            &self.aux_assignment
+        );
+
+        let mut y = a_minus_b;
+        y.square();
+        self.alloc(|| "SAP reduction y", || Ok(y)).expect("must allocate SAP reduction variable");
+
+        self.a.push(Scalar(a_minus_b));
+        self.c.push(Scalar(y));
+
+        c.double();
+        c.double();
+        c.add_assign(&y);
+
+        self.a.push(Scalar(a_plus_b));
+        self.c.push(Scalar(c));
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+        where NR: Into<String>, N: FnOnce() -> NR
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self)
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+pub fn create_random_proof<E, C, R>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>, R: Rng
+{
+    let r = rng.gen();
+
+    create_proof::<E, C>(circuit, params, r)
+}
+
+pub fn create_proof<E, C>(
+    circuit: C,
+    params: &Parameters<E>,
+    r: E::Fr
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let mut prover = ProvingAssignment {
+        a_aux_density: DensityTracker::new(),
+        a: vec![],
+        c: vec![],
+        input_assignment: vec![],
+        aux_assignment: vec![]
+    };
+
+    prover.alloc_input(|| "", || Ok(E::Fr::one()))?;
+
+    circuit.synthesize(&mut prover)?;
+
+    // Input 0 is constrained as 1*1=1 and other inputs as x_i * 1 = x_i,
+    // same as in `generate_parameters`
+    prover.a.push(Scalar(E::Fr::one()));
+    prover.c.push(Scalar(E::Fr::one()));
+
+    for i in 1..prover.input_assignment.len() {
+        prover.enforce(|| "",
+            |lc| lc + Variable(Index::Input(i)),
+            |lc| lc + Variable(Index::Input(0)),
+            |lc| lc + Variable(Index::Input(i)),
+        );
+    }
+
+    let worker = Worker::new();
+
+    let vk = &params.vk;
+    if (prover.input_assignment.len()) != vk.ic.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    // H(x) = (A(x)^2 - C(x)) / Z(x)
+    let mut h = {
+        let mut a = EvaluationDomain::from_coeffs(prover.a)?;
+        let mut c = EvaluationDomain::from_coeffs(prover.c)?;
+        if a.as_ref().len() != params.gamma2_z_t.len() + 1 {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+
+        a.ifft(&worker);
+        a.coset_fft(&worker);
+        c.ifft(&worker);
+        c.coset_fft(&worker);
+
+        let a_copy = EvaluationDomain::from_coeffs(a.as_ref().to_vec())?;
+        a.mul_assign(&worker, &a_copy);
+        drop(a_copy);
+        a.sub_assign(&worker, &c);
+        drop(c);
+        a.divide_by_z_on_coset(&worker);
+        a.icoset_fft(&worker);
+        let mut a = a.into_coeffs();
+        let a_len = a.len() - 1;
+        a.truncate(a_len);
+        let a = Arc::new(scalars_into_representations::<E>(&worker, a)?);
+
+        multiexp(&worker, (params.gamma2_z_t.clone(), 0), FullDensity, a)
+    };
+
+    let input_len = prover.input_assignment.len();
+    let input_assignment = Arc::new(field_elements_into_representations::<E>(&worker, prover.input_assignment)?);
+    let aux_assignment = Arc::new(field_elements_into_representations::<E>(&worker, prover.aux_assignment)?);
+    let a_aux_density = Arc::new(prover.a_aux_density);
+
+    let mut c_1 = multiexp(&worker, (params.c_1_g1.clone(), 0), FullDensity, aux_assignment.clone());
+
+    let mut a_g1_inputs = multiexp(&worker, (params.a_g1.clone(), 0), FullDensity, input_assignment.clone());
+    let mut a_g1_aux = multiexp(&worker, (params.a_g1.clone(), input_len), a_aux_density.clone(), aux_assignment.clone());
+
+    let mut a_g2_inputs = multiexp(&worker, (params.a_g2.clone(), 0), FullDensity, input_assignment.clone());
+    let mut a_g2_aux = multiexp(&worker, (params.a_g2.clone(), input_len), a_aux_density.clone(), aux_assignment.clone());
+
+    let mut c_2_inputs = multiexp(&worker, (params.c_2_g1.clone(), 0), FullDensity, input_assignment);
+    let mut c_2_aux = multiexp(&worker, (params.c_2_g1.clone(), input_len), a_aux_density, aux_assignment);
+
+    if params.gamma_z.is_zero() || params.gamma_z_g2.is_zero() {
+        // If this element is zero, someone is trying to perform a
+        // subversion-CRS attack.
+        return Err(SynthesisError::UnexpectedIdentity);
+    }
+
+    // A = gamma * (A(t) + r * Z(t)) in G1
+    let mut g_a = params.gamma_z.mul(r);
+    g_a.add_assign(&a_g1_inputs.wait()?);
+    g_a.add_assign(&a_g1_aux.wait()?);
+
+    // B = gamma * (A(t) + r * Z(t)) in G2
+    let mut g_b = params.gamma_z_g2.mul(r);
+    g_b.add_assign(&a_g2_inputs.wait()?);
+    g_b.add_assign(&a_g2_aux.wait()?);
+
+    // C = sum of a_i * (gamma^2 * C_i(t) + (alpha + beta) * gamma * A_i(t)) for aux
+    //     + gamma^2 * Z(t) * H(t)
+    //     + r * 2 * gamma^2 * Z(t) * A(t)
+    //     + r^2 * gamma^2 * Z(t)^2
+    //     + r * (alpha + beta) * gamma * Z(t)
+    let mut g_c = c_1.wait()?;
+    g_c.add_assign(&h.wait()?);
+
+    let mut c_2 = c_2_inputs.wait()?;
+    c_2.add_assign(&c_2_aux.wait()?);
+    c_2.add_assign_mixed(&params.ab_gamma_z_g1);
+    c_2.mul_assign(r);
+    g_c.add_assign(&c_2);
+
+    let mut r2 = r;
+    r2.square();
+    g_c.add_assign(&params.gamma2_z2_g1.mul(r2));
+
+    Ok(Proof {
+        a: g_a.into_affine(),
+        b: g_b.into_affine(),
+        c: g_c.into_affine()
+    })
+}
@@ -0,0 +1,80 @@
+use crate::pairing::{
+    Engine,
+    CurveProjective,
+    CurveAffine
+};
+
+use crate::pairing::ff::PrimeField;
+
+use super::{
+    Proof,
+    VerifyingKey,
+    PreparedVerifyingKey
+};
+
+use crate::{
+    SynthesisError
+};
+
+pub fn prepare_verifying_key<E: Engine>(
+    vk: &VerifyingKey<E>
+) -> PreparedVerifyingKey<E>
+{
+    let mut gamma = vk.gamma_g2;
+    gamma.negate();
+    let mut h = vk.h_g2;
+    h.negate();
+
+    PreparedVerifyingKey {
+        alpha_g1_beta_g2: E::pairing(vk.alpha_g1, vk.beta_g2),
+        alpha_g1: vk.alpha_g1,
+        beta_g2: vk.beta_g2,
+        gamma_g1: vk.gamma_g1,
+        gamma_g2: vk.gamma_g2,
+        neg_gamma_g2: gamma.prepare(),
+        neg_h_g2: h.prepare(),
+        ic: vk.ic.clone()
+    }
+}
+
+pub fn verify_proof<E: Engine>(
+    pvk: &PreparedVerifyingKey<E>,
+    proof: &Proof<E>,
+    public_inputs: &[E::Fr]
+) -> Result<bool, SynthesisError>
+{
+    if (public_inputs.len() + 1) != pvk.ic.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    let mut acc = pvk.ic[0].into_projective();
+
+    for (i, b) in public_inputs.iter().zip(pvk.ic.iter().skip(1)) {
+        acc.add_assign(&b.mul(i.into_repr()));
+    }
+
+    // A and B must be the same SAP evaluation in both groups:
+    // e(A, gamma) = e(gamma, B)
+    if E::pairing(proof.a, pvk.gamma_g2) != E::pairing(pvk.gamma_g1, proof.b) {
+        return Ok(false);
+    }
+
+    // The main verification equation is:
+    // (A + alpha) * (B + beta) = alpha * beta + inputs * gamma + C
+    // ... which we rearrange so that it is:
+    // (A + alpha) * (B + beta) + inputs * (-gamma) + C * (-H) = alpha * beta
+    // which allows us to do a single final exponentiation.
+
+    let mut a = proof.a.into_projective();
+    a.add_assign_mixed(&pvk.alpha_g1);
+    let mut b = proof.b.into_projective();
+    b.add_assign_mixed(&pvk.beta_g2);
+
+    Ok(E::final_exponentiation(
+        &E::miller_loop([
+            (&a.into_affine().prepare(), &b.into_affine().prepare()),
+            (&acc.into_affine().prepare(), &pvk.neg_gamma_g2),
+            (&proof.c.prepare(), &pvk.neg_h_g2)
+        ].iter())
+    ).unwrap() == pvk.alpha_g1_beta_g2)
+}
@@ -574,4 +574,82 @@ mod test_with_bls12_381 {
             assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
         }
     }
+
+    #[test]
+    fn batch_verification() {
+        struct SquareCircuit<E: Engine> {
+            a: Option<E::Fr>
+        }
+
+        impl<E: Engine> Circuit<E> for SquareCircuit<E> {
+            fn synthesize<CS: ConstraintSystem<E>>(
+                self,
+                cs: &mut CS
+            ) -> Result<(), SynthesisError>
+            {
+                let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+                let c = cs.alloc_input(|| "c", || {
+                    let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+
+                    a.square();
+                    Ok(a)
+                })?;
+
+                cs.enforce(
+                    || "a*a=c",
+                    |lc| lc + a,
+                    |lc| lc + a,
+                    |lc| lc + c
+                );
+
+                Ok(())
+            }
+        }
+
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bls12, _, _>(
+            SquareCircuit { a: None },
+            rng
+        ).unwrap();
+
+        let pvk = prepare_verifying_key::<Bls12>(&params.vk);
+
+        let mut proofs = vec![];
+        let mut inputs = vec![];
+        for _ in 0..10 {
+            let a = Fr::rand(rng);
+            let mut c = a;
+            c.square();
+
+            proofs.push(create_random_proof(
+                SquareCircuit {
+                    a: Some(a)
+                },
+                &params,
+                rng
+            ).unwrap());
+            inputs.push(vec![c]);
+        }
+
+        fn batch<'a>(proofs: &'a [Proof<Bls12>], inputs: &'a [Vec<Fr>]) -> Vec<(&'a Proof<Bls12>, &'a [Fr])> {
+            proofs.iter().zip(inputs.iter()).map(|(p, i)| (p, &i[..])).collect()
+        }
+
+        assert!(verify_proofs_batch(&pvk, rng, &batch(&proofs, &inputs)).unwrap().is_empty());
+        assert!(verify_proofs_batch(&pvk, rng, &[]).unwrap().is_empty());
+
+        let mut bad_inputs = inputs.clone();
+        bad_inputs[3][0] = Fr::rand(rng);
+        bad_inputs[7][0] = Fr::rand(rng);
+        assert_eq!(verify_proofs_batch(&pvk, rng, &batch(&proofs, &bad_inputs)).unwrap(), vec![3, 7]);
+
+        let mut swapped = batch(&proofs, &inputs);
+        swapped[0].0 = &proofs[9];
+        assert_eq!(verify_proofs_batch(&pvk, rng, &swapped).unwrap(), vec![0]);
+
+        let mut malformed = inputs.clone();
+        malformed[5].push(Fr::one());
+        assert!(verify_proofs_batch(&pvk, rng, &batch(&proofs, &malformed)).is_err());
+    }
 }
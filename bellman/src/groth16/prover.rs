@@ -182,6 +182,34 @@ pub fn prepare_prover<E, C>(
 }
 
 impl<E:Engine> PreparedProver<E> {
+    /// Moves the `i`-th constraint, counting the `x * 0 = 0` ones of the
+    /// inputs which come last, to the point `position(i)` of an evaluation
+    /// domain of `domain_size` points. This allows proving with parameters
+    /// whose QAP doesn't put the constraints at the points of the domain
+    /// in the order they are synthesized, e.g. ones made with another root
+    /// of unity. `position` must not put two constraints at the same point.
+    pub fn place_constraints<F: Fn(usize) -> usize>(
+        &mut self,
+        domain_size: usize,
+        position: F
+    ) -> Result<(), SynthesisError>
+    {
+        let prover = &mut self.assignment;
+        for evaluations in [&mut prover.a, &mut prover.b, &mut prover.c] {
+            let mut placed = vec![Scalar::<E>(E::Fr::zero()); domain_size];
+            for (i, value) in evaluations.iter().enumerate() {
+                let p = position(i);
+                if p >= domain_size {
+                    return Err(SynthesisError::PolynomialDegreeTooLarge);
+                }
+                placed[p] = *value;
+            }
+            *evaluations = placed;
+        }
+
+        Ok(())
+    }
+
     pub fn create_random_proof<R, P: ParameterSource<E>>(
         self,
         params: P,
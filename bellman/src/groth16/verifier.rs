@@ -1,10 +1,12 @@
+use rand::Rng;
+
 use crate::pairing::{
     Engine,
     CurveProjective,
     CurveAffine
 };
 
-use crate::pairing::ff::{PrimeField};
+use crate::pairing::ff::{Field, PrimeField};
 
 use super::{
     Proof,
@@ -33,6 +35,20 @@ pub fn prepare_verifying_key<E: Engine>(
     }
 }
 
+fn accumulate_inputs<E: Engine>(
+    pvk: &PreparedVerifyingKey<E>,
+    public_inputs: &[E::Fr]
+) -> E::G1
+{
+    let mut acc = pvk.ic[0].into_projective();
+
+    for (i, b) in public_inputs.iter().zip(pvk.ic.iter().skip(1)) {
+        acc.add_assign(&b.mul(i.into_repr()));
+    }
+
+    acc
+}
+
 pub fn verify_proof<'a, E: Engine>(
     pvk: &'a PreparedVerifyingKey<E>,
     proof: &Proof<E>,
@@ -43,11 +59,7 @@ pub fn verify_proof<'a, E: Engine>(
         return Err(SynthesisError::MalformedVerifyingKey);
     }
 
-    let mut acc = pvk.ic[0].into_projective();
-
-    for (i, b) in public_inputs.iter().zip(pvk.ic.iter().skip(1)) {
-        acc.add_assign(&b.mul(i.into_repr()));
-    }
+    let acc = accumulate_inputs(pvk, public_inputs);
 
     // The original verification equation is:
     // A * B = alpha * beta + inputs * gamma + C * delta
@@ -65,3 +77,99 @@ pub fn verify_proof<'a, E: Engine>(
         ].iter())
     ).unwrap() == pvk.alpha_g1_beta_g2)
 }
+
+/// Verifies a batch of proofs for the same verifying key, returning the
+/// indices of the proofs which are invalid, so an empty result means that
+/// every proof verifies.
+///
+/// The verification equations are combined with random coefficients into
+/// a single multi-Miller loop and final exponentiation. If the combined
+/// check fails, the batch is split in halves until the invalid proofs are
+/// found.
+pub fn verify_proofs_batch<E: Engine, R: Rng>(
+    pvk: &PreparedVerifyingKey<E>,
+    rng: &mut R,
+    proofs: &[(&Proof<E>, &[E::Fr])]
+) -> Result<Vec<usize>, SynthesisError>
+{
+    for &(_, public_inputs) in proofs {
+        if (public_inputs.len() + 1) != pvk.ic.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+    }
+
+    let mut invalid = vec![];
+    find_invalid_proofs(pvk, rng, proofs, 0, &mut invalid);
+
+    Ok(invalid)
+}
+
+fn find_invalid_proofs<E: Engine, R: Rng>(
+    pvk: &PreparedVerifyingKey<E>,
+    rng: &mut R,
+    proofs: &[(&Proof<E>, &[E::Fr])],
+    offset: usize,
+    invalid: &mut Vec<usize>
+)
+{
+    if batch_is_valid(pvk, rng, proofs) {
+        return;
+    }
+
+    if proofs.len() == 1 {
+        invalid.push(offset);
+        return;
+    }
+
+    let mid = proofs.len() / 2;
+    find_invalid_proofs(pvk, rng, &proofs[..mid], offset, invalid);
+    find_invalid_proofs(pvk, rng, &proofs[mid..], offset + mid, invalid);
+}
+
+fn batch_is_valid<E: Engine, R: Rng>(
+    pvk: &PreparedVerifyingKey<E>,
+    rng: &mut R,
+    proofs: &[(&Proof<E>, &[E::Fr])]
+) -> bool
+{
+    if proofs.is_empty() {
+        return true;
+    }
+
+    // Each proof satisfies
+    // A * B + inputs * (-gamma) + C * (-delta) = alpha * beta
+    // so for random nonzero r_i the batch satisfies
+    // sum(r_i * A_i * B_i) + sum(r_i * inputs_i) * (-gamma) + sum(r_i * C_i) * (-delta) = sum(r_i) * alpha * beta
+    // which fails with overwhelming probability if any of the proofs is invalid.
+
+    let mut acc_inputs = E::G1::zero();
+    let mut acc_c = E::G1::zero();
+    let mut r_sum = E::Fr::zero();
+    let mut ab = Vec::with_capacity(proofs.len());
+
+    for &(proof, public_inputs) in proofs {
+        let r = loop {
+            let r: E::Fr = rng.gen();
+            if !r.is_zero() {
+                break r;
+            }
+        };
+        let r_repr = r.into_repr();
+
+        let mut inputs = accumulate_inputs(pvk, public_inputs);
+        inputs.mul_assign(r_repr);
+        acc_inputs.add_assign(&inputs);
+        acc_c.add_assign(&proof.c.mul(r_repr));
+        ab.push((proof.a.mul(r_repr).into_affine().prepare(), proof.b.prepare()));
+        r_sum.add_assign(&r);
+    }
+
+    let acc_inputs = acc_inputs.into_affine().prepare();
+    let acc_c = acc_c.into_affine().prepare();
+
+    let mut terms = ab.iter().map(|(a, b)| (a, b)).collect::<Vec<_>>();
+    terms.push((&acc_inputs, &pvk.neg_gamma_g2));
+    terms.push((&acc_c, &pvk.neg_delta_g2));
+
+    E::final_exponentiation(&E::miller_loop(terms.iter())) == Some(pvk.alpha_g1_beta_g2.pow(r_sum.into_repr()))
+}
@@ -35,6 +35,9 @@ mod worker {
 mod cs;
 pub use self::cs::*;
 
+#[cfg(feature = "test-cs")]
+pub mod test_cs;
+
 use std::str::FromStr;
 use std::env;
 
@@ -12,7 +12,7 @@ use super::helper::{Aggregate};
 
 use crate::SynthesisError;
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher};
 use crate::sonic::util::*;
 use crate::sonic::cs::{Backend, SynthesisDriver};
 use crate::{Circuit};
@@ -20,6 +20,7 @@ use crate::sonic::sonic::AdaptorCircuit;
 use crate::sonic::srs::SRS;
 use crate::sonic::sonic::Nonassigning;
 use super::helper::create_aggregate as create_aggregate_sonic_circuit;
+use super::helper::create_aggregate_on_srs_using_information_with_hasher as create_aggregate_on_srs_using_information_with_hasher_sonic_circuit;
 
 pub fn create_aggregate<E: Engine, C: Circuit<E> + Clone>(
     circuit: C,
@@ -31,3 +32,15 @@ pub fn create_aggregate<E: Engine, C: Circuit<E> + Clone>(
 
     create_aggregate_sonic_circuit::<_, _, Nonassigning>(&adapted_circuit, inputs, params)
 }
+
+/// Same as `create_aggregate`, for proofs made with a transcript over the hasher `H`
+pub fn create_aggregate_with_hasher<E: Engine, C: Circuit<E> + Clone, H: Hasher>(
+    circuit: C,
+    inputs: &[(Proof<E>, SxyAdvice<E>)],
+    params: &Parameters<E>,
+) -> Aggregate<E>
+{
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    create_aggregate_on_srs_using_information_with_hasher_sonic_circuit::<_, _, Nonassigning, H>(&adapted_circuit, inputs, &params.srs, params.vk.n, params.vk.q)
+}
@@ -9,7 +9,7 @@ use super::parameters::{Parameters};
 
 use crate::SynthesisError;
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher};
 use crate::sonic::util::*;
 use crate::sonic::cs::{Backend, SynthesisDriver};
 use crate::{Circuit};
@@ -19,6 +19,8 @@ use crate::sonic::sonic::Basic;
 use super::prover::create_advice as create_advice_sonic_circuit;
 use super::prover::create_advice_on_information_and_srs as create_advice_on_information_and_srs_sonic_circuit;
 use super::prover::create_proof_on_srs as create_proof_on_srs_sonic_circuit;
+use super::prover::create_advice_on_information_and_srs_with_hasher as create_advice_on_information_and_srs_with_hasher_sonic_circuit;
+use super::prover::create_proof_on_srs_with_hasher as create_proof_on_srs_with_hasher_sonic_circuit;
 use crate::sonic::sonic::CountN;
 
 // pub fn create_advice_on_information_and_srs<E: Engine, C: Circuit<E> + Clone, S: SynthesisDriver>(
@@ -86,6 +88,30 @@ pub fn create_proof_on_srs<E: Engine, C: Circuit<E> + Clone>(
     create_proof_on_srs_sonic_circuit::<_, _, Basic>(&adapted_circuit, srs)
 }
 
+/// Same as `create_advice`, for a proof made with a transcript over the hasher `H`
+pub fn create_advice_with_hasher<E: Engine, C: Circuit<E> + Clone, H: Hasher>(
+    circuit: C,
+    proof: &Proof<E>,
+    parameters: &Parameters<E>,
+) -> Result<SxyAdvice<E>, SynthesisError>
+{
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    create_advice_on_information_and_srs_with_hasher_sonic_circuit::<_, _, Basic, H>(&adapted_circuit, proof, &parameters.srs, parameters.vk.n)
+}
+
+/// Same as `create_proof`, deriving the challenges from a transcript over
+/// the hasher `H`
+pub fn create_proof_with_hasher<E: Engine, C: Circuit<E> + Clone, H: Hasher>(
+    circuit: C,
+    parameters: &Parameters<E>
+) -> Result<Proof<E>, SynthesisError>
+{
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    create_proof_on_srs_with_hasher_sonic_circuit::<_, _, Basic, H>(&adapted_circuit, &parameters.srs)
+}
+
 // #[test]
 // fn my_fun_circuit_test() {
 //     use crate::pairing::ff::PrimeField;
@@ -12,7 +12,7 @@ use super::helper::{Aggregate};
 
 use crate::SynthesisError;
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher};
 use crate::sonic::util::*;
 use crate::sonic::cs::{Backend, SynthesisDriver};
 use crate::{Circuit};
@@ -21,6 +21,8 @@ use crate::sonic::srs::SRS;
 use crate::sonic::sonic::Nonassigning;
 use super::verifier::verify_aggregate_on_srs as verify_aggregate_on_srs_sonic_circuit;
 use super::verifier::verify_proofs_on_srs as verify_proofs_on_srs_sonic_circuit;
+use super::verifier::verify_aggregate_on_srs_with_hasher as verify_aggregate_on_srs_with_hasher_sonic_circuit;
+use super::verifier::verify_proofs_on_srs_with_hasher as verify_proofs_on_srs_with_hasher_sonic_circuit;
 
 pub fn verify_proofs<E: Engine, C: Circuit<E> + Clone, R: Rng>(
     proofs: &[Proof<E>],
@@ -50,6 +52,35 @@ pub fn verify_aggregate<E: Engine, C: Circuit<E> + Clone, R: Rng>(
     verify_aggregate_on_srs_sonic_circuit::<_, _, Nonassigning, _>(proofs, aggregate, inputs, adapted_circuit, rng, &params.srs)
 }
 
+/// Same as `verify_proofs`, for proofs made with a transcript over the hasher `H`
+pub fn verify_proofs_with_hasher<E: Engine, C: Circuit<E> + Clone, R: Rng, H: Hasher>(
+    proofs: &[Proof<E>],
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    params: &Parameters<E>,
+) -> Result<bool, SynthesisError>
+{
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    verify_proofs_on_srs_with_hasher_sonic_circuit::<_, _, Nonassigning, _, H>(proofs, inputs, adapted_circuit, rng, &params.srs)
+}
+
+/// Same as `verify_aggregate`, for proofs and an aggregate made with
+/// a transcript over the hasher `H`
+pub fn verify_aggregate_with_hasher<E: Engine, C: Circuit<E> + Clone, R: Rng, H: Hasher>(
+    proofs: &[(Proof<E>, SxyAdvice<E>)],
+    aggregate: &Aggregate<E>,
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    params: &Parameters<E>,
+) -> Result<bool, SynthesisError> {
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    verify_aggregate_on_srs_with_hasher_sonic_circuit::<_, _, Nonassigning, _, H>(proofs, aggregate, inputs, adapted_circuit, rng, &params.srs)
+}
+
 
 // #[test]
 // fn my_fun_circuit_test() {
@@ -100,3 +131,33 @@ pub fn verify_aggregate<E: Engine, C: Circuit<E> + Clone, R: Rng>(
 //     let elapsed = start.elapsed();
 //     println!("time to verify: {:?}", elapsed);
 // }
+
+#[test]
+fn verify_proofs_with_hasher_test() {
+    use crate::pairing::ff::PrimeField;
+    use crate::pairing::bn256::{Bn256, Fr};
+    use crate::sonic::tests::circuits::MulCircuit;
+    use crate::sonic::transcript::{BlakeHasher, Keccak256Hasher};
+    use super::adapted_prover::create_proof_with_hasher;
+    use super::generator::generate_parameters_on_srs;
+    use rand::thread_rng;
+
+    let srs = SRS::<Bn256>::new(
+        32,
+        Fr::from_str("22222").unwrap(),
+        Fr::from_str("33333333").unwrap(),
+    );
+    let params = generate_parameters_on_srs(MulCircuit::<Bn256> { a: None, b: None }, &srs).unwrap();
+
+    let circuit = MulCircuit::<Bn256> {
+        a: Fr::from_str("3"),
+        b: Fr::from_str("5"),
+    };
+    let inputs = vec![vec![Fr::from_str("15").unwrap()]];
+    let proof = create_proof_with_hasher::<_, _, BlakeHasher>(circuit.clone(), &params).unwrap();
+    let proofs = vec![proof];
+
+    assert!(verify_proofs_with_hasher::<_, _, _, BlakeHasher>(&proofs, &inputs, circuit.clone(), thread_rng(), &params).unwrap());
+    assert!(!verify_proofs_with_hasher::<_, _, _, Keccak256Hasher>(&proofs, &inputs, circuit.clone(), thread_rng(), &params).unwrap());
+    assert!(!verify_proofs(&proofs, &inputs, circuit, thread_rng(), &params).unwrap());
+}
@@ -100,7 +100,7 @@ impl<E: Engine> Batch<E> {
             neg_x_n_minus_d_precomp: vk.neg_x_n_minus_d.prepare(),
 
             value: E::Fr::zero(),
-            g: E::G1Affine::one(),
+            g: vk.g,
         }
     }
 
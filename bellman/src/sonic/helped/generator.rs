@@ -371,6 +371,8 @@ pub fn generate_parameters_on_srs_and_information<E: Engine>(
     };
 
     let vk = VerifyingKey {
+        g: trimmed_srs.g_positive_x[0],
+
         alpha_x: trimmed_srs.h_positive_x_alpha[1],
 
         alpha: trimmed_srs.h_positive_x_alpha[0],
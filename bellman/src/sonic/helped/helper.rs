@@ -9,7 +9,7 @@ use super::Parameters;
 
 use crate::SynthesisError;
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher, Keccak256Hasher};
 use crate::sonic::util::*;
 use crate::sonic::cs::{Backend, SynthesisDriver};
 use crate::sonic::cs::{Circuit, Variable, Coeff};
@@ -69,11 +69,24 @@ pub fn create_aggregate_on_srs_using_information<E: Engine, C: Circuit<E>, S: Sy
     q: usize,
 ) -> Aggregate<E>
 {
-    let mut transcript = Transcript::new(&[]);
+    create_aggregate_on_srs_using_information_with_hasher::<E, C, S, Keccak256Hasher>(circuit, inputs, srs, n, q)
+}
+
+/// Same as `create_aggregate_on_srs_using_information`, for proofs made
+/// with a transcript over the hasher `H`
+pub fn create_aggregate_on_srs_using_information_with_hasher<E: Engine, C: Circuit<E>, S: SynthesisDriver, H: Hasher>(
+    circuit: &C,
+    inputs: &[(Proof<E>, SxyAdvice<E>)],
+    srs: &SRS<E>,
+    n: usize,
+    q: usize,
+) -> Aggregate<E>
+{
+    let mut transcript = Transcript::<H>::new(&[]);
     let mut y_values: Vec<E::Fr> = Vec::with_capacity(inputs.len());
     for &(ref proof, ref sxyadvice) in inputs {
         {
-            let mut transcript = Transcript::new(&[]);
+            let mut transcript = Transcript::<H>::new(&[]);
             transcript.commit_point(&proof.r);
             y_values.push(transcript.get_challenge_scalar());
         }
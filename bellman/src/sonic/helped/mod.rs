@@ -37,15 +37,20 @@ pub use self::adapted_prover::{
     create_advice,
     create_advice_on_srs,
     create_advice_on_information_and_srs, 
+    create_advice_with_hasher,
     create_proof, 
     create_proof_on_srs, 
+    create_proof_with_hasher,
 };
 
 pub use self::adapted_verifier::{
     verify_proofs,
-    verify_aggregate
+    verify_proofs_with_hasher,
+    verify_aggregate,
+    verify_aggregate_with_hasher
 };
 
 pub use self::adapted_helper::{
-    create_aggregate
+    create_aggregate,
+    create_aggregate_with_hasher
 };
\ No newline at end of file
@@ -158,6 +158,10 @@ impl<E: Engine> Proof<E> {
 
 #[derive(Clone, Debug, Eq)]
 pub struct VerifyingKey<E: Engine> {
+    // the generator of G1 the SRS is taken over, which isn't
+    // the fixed one for SRSes derived from powers of tau
+    pub g: E::G1Affine,
+
     pub alpha_x: E::G2Affine,
 
     pub alpha: E::G2Affine,
@@ -175,6 +179,7 @@ pub struct VerifyingKey<E: Engine> {
 
 impl<E: Engine> PartialEq for VerifyingKey<E> {
     fn eq(&self, other: &VerifyingKey<E>) -> bool {
+        self.g == other.g &&
         self.alpha_x == other.alpha_x &&
         self.alpha == other.alpha &&
         self.neg_h == other.neg_h &&
@@ -186,11 +191,15 @@ impl<E: Engine> PartialEq for VerifyingKey<E> {
 }
 
 impl<E: Engine> VerifyingKey<E> {
+    /// Writes the key as `g` in G1, then `alpha_x`, `alpha`, `neg_h` and
+    /// `neg_x_n_minus_d` in G2, all uncompressed, followed by the length
+    /// of `k_map`, its entries, `n` and `q` as big endian `u32`s
     pub fn write<W: Write>(
         &self,
         mut writer: W
     ) -> io::Result<()>
     {
+        writer.write_all(self.g.into_uncompressed().as_ref())?;
         writer.write_all(self.alpha_x.into_uncompressed().as_ref())?;
         writer.write_all(self.alpha.into_uncompressed().as_ref())?;
         writer.write_all(self.neg_h.into_uncompressed().as_ref())?;
@@ -206,12 +215,17 @@ impl<E: Engine> VerifyingKey<E> {
         Ok(())
     }
 
+    /// Reads a key in the layout of `write`
     pub fn read<R: Read>(
         mut reader: R
     ) -> io::Result<Self>
     {
+        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
         let mut g2_repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
 
+        reader.read_exact(g1_repr.as_mut())?;
+        let g = g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
         reader.read_exact(g2_repr.as_mut())?;
         let alpha_x = g2_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
@@ -239,6 +253,7 @@ impl<E: Engine> VerifyingKey<E> {
         let q = reader.read_u32::<BigEndian>()? as usize;
 
         Ok(VerifyingKey {
+            g: g,
             alpha_x: alpha_x,
             alpha: alpha,
             neg_h: neg_h,
@@ -264,6 +279,8 @@ impl<E: Engine> VerifyingKey<E> {
         S::synthesize(&mut preprocess, &circuit)?;
 
         Ok(Self {
+            g: srs.g_positive_x[0],
+
             alpha_x: srs.h_positive_x_alpha[1],
 
             alpha: srs.h_positive_x_alpha[0],
@@ -466,4 +483,36 @@ fn parameters_generation() {
         // assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
         // assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn verifying_key_serialization() {
+    use crate::pairing::CurveProjective;
+    use crate::pairing::bn256::{Bn256, Fr, G1Affine, G2};
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+    let vk = VerifyingKey::<Bn256> {
+        g: G1Affine::one().mul(Fr::rand(rng)).into_affine(),
+        alpha_x: G2::rand(rng).into_affine(),
+        alpha: G2::rand(rng).into_affine(),
+        neg_h: G2::rand(rng).into_affine(),
+        neg_x_n_minus_d: G2::rand(rng).into_affine(),
+        k_map: vec![1, 3],
+        n: 4,
+        q: 5
+    };
+
+    let mut bytes = vec![];
+    vk.write(&mut bytes).unwrap();
+
+    let mut expected = vk.g.into_uncompressed().as_ref().to_vec();
+    for p in &[vk.alpha_x, vk.alpha, vk.neg_h, vk.neg_x_n_minus_d] {
+        expected.extend_from_slice(p.into_uncompressed().as_ref());
+    }
+    for value in &[2, 1, 3, 4, 5] {
+        expected.write_u32::<BigEndian>(*value).unwrap();
+    }
+    assert_eq!(bytes, expected);
+    assert!(VerifyingKey::<Bn256>::read(&bytes[..]).unwrap() == vk);
+}
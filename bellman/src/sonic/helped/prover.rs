@@ -9,7 +9,7 @@ use super::parameters::{Parameters, NUM_BLINDINGS};
 
 use crate::SynthesisError;
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher, Keccak256Hasher};
 use crate::sonic::util::*;
 use crate::sonic::cs::{Backend, SynthesisDriver};
 use crate::sonic::cs::{Circuit, Variable, Coeff};
@@ -22,11 +22,23 @@ pub fn create_advice_on_information_and_srs<E: Engine, C: Circuit<E>, S: Synthes
     srs: &SRS<E>,
     n: usize
 ) -> Result<SxyAdvice<E>, SynthesisError>
+{
+    create_advice_on_information_and_srs_with_hasher::<E, C, S, Keccak256Hasher>(circuit, proof, srs, n)
+}
+
+/// Same as `create_advice_on_information_and_srs`, for a proof made
+/// with a transcript over the hasher `H`
+pub fn create_advice_on_information_and_srs_with_hasher<E: Engine, C: Circuit<E>, S: SynthesisDriver, H: Hasher>(
+    circuit: &C,
+    proof: &Proof<E>,
+    srs: &SRS<E>,
+    n: usize
+) -> Result<SxyAdvice<E>, SynthesisError>
 {
     let z: E::Fr;
     let y: E::Fr;
     {
-        let mut transcript = Transcript::new(&[]);
+        let mut transcript = Transcript::<H>::new(&[]);
         transcript.commit_point(&proof.r);
         y = transcript.get_challenge_scalar();
         transcript.commit_point(&proof.t);
@@ -145,6 +157,16 @@ pub fn create_proof_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
     circuit: &C,
     srs: &SRS<E>
 ) -> Result<Proof<E>, SynthesisError>
+{
+    create_proof_on_srs_with_hasher::<E, C, S, Keccak256Hasher>(circuit, srs)
+}
+
+/// Same as `create_proof_on_srs`, deriving the challenges from
+/// a transcript over the hasher `H`
+pub fn create_proof_on_srs_with_hasher<E: Engine, C: Circuit<E>, S: SynthesisDriver, H: Hasher>(
+    circuit: &C,
+    srs: &SRS<E>
+) -> Result<Proof<E>, SynthesisError>
 {
     let mut wires = Wires::new();
 
@@ -152,7 +174,7 @@ pub fn create_proof_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
 
     let n = wires.a.len();
 
-    let mut transcript = Transcript::new(&[]);
+    let mut transcript = Transcript::<H>::new(&[]);
 
     let rng = &mut thread_rng();
 
@@ -404,8 +426,10 @@ fn polynomial_commitment_test() {
     // make commitment to the poly
     let commitment = polynomial_commitment(2, 4, 2, &srs, poly.iter());
     let point: Fr = rng.gen();
+    // the first coefficient is the one of x^-4
     let mut tmp = point.inverse().unwrap();
     tmp.square();
+    tmp.square();
     let value = evaluate_at_consequitive_powers(&poly, tmp, point);
     // evaluate f(z)
     poly[4] = value;
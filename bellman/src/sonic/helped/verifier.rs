@@ -11,24 +11,25 @@ use super::parameters::{Parameters};
 
 use crate::SynthesisError;
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher, Keccak256Hasher};
 use crate::sonic::util::*;
 use crate::sonic::cs::{Backend, SynthesisDriver};
 use crate::sonic::cs::{Circuit, Variable, Coeff};
 use crate::sonic::srs::SRS;
 use crate::sonic::sonic::Preprocess;
 
-pub struct MultiVerifier<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> {
+/// Batch verifier of proofs whose challenges come from a transcript over `H`
+pub struct MultiVerifier<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng, H: Hasher = Keccak256Hasher> {
     circuit: C,
     pub(crate) batch: Batch<E>,
     k_map: Vec<usize>,
     n: usize,
     q: usize,
     randomness_source: R,
-    _marker: PhantomData<(E, S)>
+    _marker: PhantomData<(E, S, H)>
 }
 
-impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> MultiVerifier<E, C, S, R> {
+impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng, H: Hasher> MultiVerifier<E, C, S, R, H> {
     // This constructor consumes randomness source cause it's later used internally
     pub fn new(circuit: C, srs: &SRS<E>, rng: R) -> Result<Self, SynthesisError> {
         let mut preprocess = Preprocess::new();
@@ -52,11 +53,11 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> MultiVerifier<E, C, S
         aggregate: &Aggregate<E>,
     )
     {
-        let mut transcript = Transcript::new(&[]);
+        let mut transcript = Transcript::<H>::new(&[]);
         let mut y_values: Vec<E::Fr> = Vec::with_capacity(proofs.len());
         for &(ref proof, ref sxyadvice) in proofs {
             {
-                let mut transcript = Transcript::new(&[]);
+                let mut transcript = Transcript::<H>::new(&[]);
                 transcript.commit_point(&proof.r);
                 y_values.push(transcript.get_challenge_scalar());
             }
@@ -133,7 +134,7 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> MultiVerifier<E, C, S
         let z = z.unwrap();
 
         // We need to open up SxyAdvice.s at z using SxyAdvice.opening
-        let mut transcript = Transcript::new(&[]);
+        let mut transcript = Transcript::<H>::new(&[]);
         transcript.commit_point(&advice.opening);
         transcript.commit_point(&advice.s);
         transcript.commit_scalar(&advice.szy);
@@ -152,7 +153,7 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> MultiVerifier<E, C, S
     )
         where F: FnOnce(E::Fr, E::Fr) -> Option<E::Fr>
     {
-        let mut transcript = Transcript::new(&[]);
+        let mut transcript = Transcript::<H>::new(&[]);
 
         transcript.commit_point(&proof.r);
 
@@ -263,7 +264,19 @@ pub fn verify_proofs_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng
     rng: R,
     srs: &SRS<E>,
 ) -> Result<bool, SynthesisError> {
-    let mut verifier = MultiVerifier::<E, C, S, R>::new(circuit, srs, rng)?;
+    verify_proofs_on_srs_with_hasher::<E, C, S, R, Keccak256Hasher>(proofs, inputs, circuit, rng, srs)
+}
+
+/// Same as `verify_proofs_on_srs`, for proofs made with a transcript
+/// over the hasher `H`
+pub fn verify_proofs_on_srs_with_hasher<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng, H: Hasher>(
+    proofs: &[Proof<E>],
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    srs: &SRS<E>,
+) -> Result<bool, SynthesisError> {
+    let mut verifier = MultiVerifier::<E, C, S, R, H>::new(circuit, srs, rng)?;
     let expected_inputs_size = verifier.get_k_map().len() - 1;
     for (proof, inputs) in proofs.iter().zip(inputs.iter()) {
         if inputs.len() != expected_inputs_size {
@@ -298,7 +311,20 @@ pub fn verify_aggregate_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver, R:
     rng: R,
     srs: &SRS<E>,
 ) -> Result<bool, SynthesisError> {
-    let mut verifier = MultiVerifier::<E, C, S, R>::new(circuit, srs, rng)?;
+    verify_aggregate_on_srs_with_hasher::<E, C, S, R, Keccak256Hasher>(proofs, aggregate, inputs, circuit, rng, srs)
+}
+
+/// Same as `verify_aggregate_on_srs`, for proofs and an aggregate made
+/// with a transcript over the hasher `H`
+pub fn verify_aggregate_on_srs_with_hasher<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng, H: Hasher>(
+    proofs: &[(Proof<E>, SxyAdvice<E>)],
+    aggregate: &Aggregate<E>,
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    srs: &SRS<E>,
+) -> Result<bool, SynthesisError> {
+    let mut verifier = MultiVerifier::<E, C, S, R, H>::new(circuit, srs, rng)?;
     let expected_inputs_size = verifier.get_k_map().len() - 1;
     for ((proof, advice), inputs) in proofs.iter().zip(inputs.iter()) {
         if inputs.len() != expected_inputs_size {
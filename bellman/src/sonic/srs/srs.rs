@@ -5,6 +5,8 @@ use std::io::{self, Read, Write};
 use std::sync::Arc;
 use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
 
+// g and h are g_positive_x[0] and h_positive_x[0], which aren't the
+// fixed generators for an SRS derived from powers of tau
 #[derive(Clone, Eq)]
 pub struct SRS<E: Engine> {
     pub d: usize,
@@ -0,0 +1,31 @@
+use crate::pairing::Engine;
+use crate::pairing::ff::Field;
+
+use crate::{
+    Circuit,
+    ConstraintSystem,
+    SynthesisError
+};
+
+/// `a * b = c`, with `c` as the only public input.
+#[derive(Clone)]
+pub struct MulCircuit<E: Engine> {
+    pub a: Option<E::Fr>,
+    pub b: Option<E::Fr>,
+}
+
+impl<E: Engine> Circuit<E> for MulCircuit<E> {
+    fn synthesize<CS: ConstraintSystem<E>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+        let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+        let c = cs.alloc_input(|| "c", || {
+            let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+            a.mul_assign(&self.b.ok_or(SynthesisError::AssignmentMissing)?);
+            Ok(a)
+        })?;
+
+        cs.enforce(|| "a*b=c", |lc| lc + a, |lc| lc + b, |lc| lc + c);
+
+        Ok(())
+    }
+}
@@ -1 +1,3 @@
-mod sonics;
\ No newline at end of file
+pub(crate) mod circuits;
+mod sonics;
+mod succinct;
\ No newline at end of file
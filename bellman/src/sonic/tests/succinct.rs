@@ -0,0 +1,156 @@
+use rand::{XorShiftRng, SeedableRng};
+
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::bls12_381::{Bls12, Fr};
+
+use crate::SynthesisError;
+
+use crate::sonic::cs::{Circuit, ConstraintSystem};
+use crate::sonic::helped::{Proof, SxyAdvice};
+use crate::sonic::helped::prover::{create_advice_on_information_and_srs_with_hasher, create_proof_on_srs_with_hasher};
+use crate::sonic::sonic::{AdaptorCircuit, Permutation3};
+use crate::sonic::srs::SRS;
+use crate::sonic::transcript::{Hasher, Keccak256Hasher, BlakeHasher};
+use crate::sonic::unhelped::{
+    SuccinctAggregate,
+    SuccinctMultiVerifier,
+    SuccinctProvingKey,
+    SuccinctVerifyingKey,
+    create_aggregate_on_srs_using_key_with_hasher
+};
+
+use super::circuits::MulCircuit;
+
+fn srs() -> SRS<Bls12> {
+    SRS::<Bls12>::new(
+        100,
+        Fr::from_str("23923").unwrap(),
+        Fr::from_str("23728792").unwrap(),
+    )
+}
+
+fn circuit() -> AdaptorCircuit<MulCircuit<Bls12>> {
+    AdaptorCircuit(MulCircuit::<Bls12> {
+        a: Fr::from_str("3"),
+        b: Fr::from_str("5"),
+    })
+}
+
+fn rng() -> XorShiftRng {
+    XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654])
+}
+
+/// A proof of `3 * 5 = 15` with its advice and aggregate, made with the
+/// transcript hasher `H` over an SRS with distinct powers of x
+struct SuccinctAggregateFixture {
+    srs: SRS<Bls12>,
+    inputs: [Fr; 1],
+    pk: SuccinctProvingKey<Bls12>,
+    proofs: Vec<(Proof<Bls12>, SxyAdvice<Bls12>)>,
+    aggregate: SuccinctAggregate<Bls12>
+}
+
+impl SuccinctAggregateFixture {
+    fn new<H: Hasher>() -> Self {
+        let srs = srs();
+        let circuit = circuit();
+        let inputs = [Fr::from_str("15").unwrap()];
+
+        let pk = SuccinctProvingKey::<Bls12>::new::<_, Permutation3>(&circuit, &srs).unwrap();
+
+        let proof = create_proof_on_srs_with_hasher::<Bls12, _, Permutation3, H>(&circuit, &srs).unwrap();
+        let advice = create_advice_on_information_and_srs_with_hasher::<Bls12, _, Permutation3, H>(&circuit, &proof, &srs, pk.vk.n).unwrap();
+        let proofs = vec![(proof, advice)];
+        let aggregate = create_aggregate_on_srs_using_key_with_hasher::<Bls12, _, Permutation3, H>(&circuit, &proofs, &srs, &pk);
+
+        SuccinctAggregateFixture {
+            srs,
+            inputs,
+            pk,
+            proofs,
+            aggregate
+        }
+    }
+
+    /// A verifier from the cached key with the proofs and the aggregate
+    /// added, challenges are drawn with `H`
+    fn verifier<H: Hasher>(&self) -> SuccinctMultiVerifier<Bls12, AdaptorCircuit<MulCircuit<Bls12>>, Permutation3, XorShiftRng, H> {
+        let mut verifier = SuccinctMultiVerifier::new_from_key(circuit(), self.pk.vk.clone(), &self.srs, rng()).unwrap();
+        for (proof, advice) in &self.proofs {
+            verifier.add_proof_with_advice(proof, &self.inputs, advice);
+        }
+        verifier.add_aggregate(&self.proofs, &self.aggregate, &self.srs);
+
+        verifier
+    }
+}
+
+#[test]
+fn test_succinct_aggregate() {
+    let fixture = SuccinctAggregateFixture::new::<Keccak256Hasher>();
+
+    // over an SRS with distinct powers of x, the b commitments of the grand products only
+    // pair with h^{x^{3n + 2}}, with h^{x^{n + 1}} this valid aggregate is rejected
+    assert!(fixture.verifier::<Keccak256Hasher>().check_all());
+}
+
+#[test]
+fn test_succinct_aggregate_tampered_grand_product() {
+    let mut fixture = SuccinctAggregateFixture::new::<Keccak256Hasher>();
+    fixture.aggregate.signature.grand_product_signature.grand_product_openings[0].0.add_assign(&Fr::one());
+
+    assert!(!fixture.verifier::<Keccak256Hasher>().check_all());
+}
+
+#[test]
+fn test_succinct_aggregate_tampered_wellformedness() {
+    let mut fixture = SuccinctAggregateFixture::new::<Keccak256Hasher>();
+    let proof = &mut fixture.aggregate.signature.grand_product_signature.wellformedness_signature.proof;
+    proof.l = proof.r;
+
+    assert!(!fixture.verifier::<Keccak256Hasher>().check_all());
+}
+
+#[test]
+fn test_succinct_aggregate_with_hasher() {
+    let fixture = SuccinctAggregateFixture::new::<BlakeHasher>();
+
+    assert!(fixture.verifier::<BlakeHasher>().check_all());
+
+    // the same proofs don't pass with challenges drawn from the default transcript
+    assert!(!fixture.verifier::<Keccak256Hasher>().check_all());
+}
+
+#[test]
+fn test_succinct_verifier_from_key() {
+    // fails as soon as it is synthesized
+    struct UnsynthesizableCircuit;
+
+    impl Circuit<Bls12> for UnsynthesizableCircuit {
+        fn synthesize<CS: ConstraintSystem<Bls12>>(&self, _: &mut CS) -> Result<(), SynthesisError> {
+            Err(SynthesisError::Unsatisfiable)
+        }
+    }
+
+    let srs = srs();
+    let vk = SuccinctVerifyingKey::<Bls12>::new::<_, Permutation3>(&circuit(), &srs).unwrap();
+
+    assert!(SuccinctMultiVerifier::<Bls12, _, Permutation3, _>::new(UnsynthesizableCircuit, &srs, rng()).is_err());
+
+    // the shape is taken from the key, so the circuit is not synthesized
+    let verifier = SuccinctMultiVerifier::<Bls12, _, Permutation3, _>::new_from_key(UnsynthesizableCircuit, vk.clone(), &srs, rng()).unwrap();
+    assert_eq!(verifier.get_n(), vk.n);
+    assert_eq!(verifier.get_q(), vk.q);
+    assert_eq!(verifier.get_k_map(), vk.k_map);
+
+    // the grand product arguments need x^{3n + 2}
+    let small_srs = SRS::<Bls12>::new(
+        3*vk.n + 1,
+        Fr::from_str("23923").unwrap(),
+        Fr::from_str("23728792").unwrap(),
+    );
+    match SuccinctMultiVerifier::<Bls12, _, Permutation3, _>::new_from_key(circuit(), vk, &small_srs, rng()) {
+        Err(SynthesisError::PolynomialDegreeTooLarge) => {},
+        _ => panic!("SRS without x^(3n + 2) must be rejected")
+    }
+}
@@ -1,7 +1,7 @@
 extern crate tiny_keccak;
 extern crate blake2_rfc;
 
-use self::tiny_keccak::Keccak;
+use self::tiny_keccak::{Keccak, Hasher as KeccakHasher};
 use self::blake2_rfc::blake2s::{Blake2s, blake2s};
 
 pub trait Hasher {
@@ -48,7 +48,7 @@ pub struct Keccak256Hasher {
 
 impl Hasher for Keccak256Hasher {
     fn new(personalization: &[u8]) -> Self {
-        let mut h = Keccak::new_keccak256();
+        let mut h = Keccak::v256();
         h.update(personalization);
 
         Self {
@@ -63,7 +63,7 @@ impl Hasher for Keccak256Hasher {
     fn finalize(&mut self) -> Vec<u8> {
         use std::mem;
 
-        let new_h = Keccak::new_keccak256();
+        let new_h = Keccak::v256();
         let h = std::mem::replace(&mut self.h, new_h);
 
         let mut res: [u8; 32] = [0; 32];
@@ -4,14 +4,32 @@ use std::io;
 
 mod hasher;
 
-use self::hasher::{Hasher, Keccak256Hasher, BlakeHasher};
-
+pub use self::hasher::{Hasher, Keccak256Hasher, BlakeHasher};
+
+/// Transcript the provers and verifiers derive their challenges from. It hashes
+/// with Keccak-256 unless another hasher is given, which is what a Solidity
+/// verifier can replay with `keccak256`.
+///
+/// Over BN254 the state is a `bytes32` that evolves as
+///
+/// ```text
+/// state = keccak256(personalization)
+/// commit_point(p):  state = keccak256(abi.encodePacked(state, "point", uint256(p.x), uint256(p.y)))
+/// commit_scalar(s): state = keccak256(abi.encodePacked(state, "scalar", uint256(s)))
+/// ```
+///
+/// G2 points are packed as `x.c1, x.c0, y.c1, y.c0`. The point at infinity is
+/// not packed as `(0, 0)`: it is 64 zero bytes with `0x40` set in the first one.
+/// A challenge is `uint256(keccak256(abi.encodePacked(state, uint32(nonce))))`
+/// for the first nonce that gives a value below the scalar field modulus;
+/// nonces start from 0 after every commitment and from the last accepted
+/// nonce plus one for repeated challenges.
 #[derive(Clone)]
-pub struct Transcript {
-    transcriptor: RollingHashTranscript<Keccak256Hasher>
+pub struct Transcript<H: Hasher = Keccak256Hasher> {
+    transcriptor: RollingHashTranscript<H>
 }
 
-impl Transcript {
+impl<H: Hasher> Transcript<H> {
     pub fn new(personalization: &[u8]) -> Self {
         Self {
             transcriptor: RollingHashTranscript::new(personalization)
@@ -19,7 +37,7 @@ impl Transcript {
     }
 }
 
-impl TranscriptProtocol for Transcript {
+impl<H: Hasher> TranscriptProtocol for Transcript<H> {
     fn commit_point<G: CurveAffine>(&mut self, point: &G) {
         self.transcriptor.commit_point(point);
     }
@@ -131,4 +149,33 @@ impl<H:Hasher> TranscriptProtocol for RollingHashTranscript<H> {
 
 //         Ok(buf.len())
 //     }
-// }
\ No newline at end of file
+// }
+
+#[test]
+fn keccak_transcript_matches_abi_encode_packed() {
+    use crate::pairing::bn256::{Fr, G1Affine};
+
+    fn state(transcript: &Transcript<Keccak256Hasher>) -> String {
+        transcript.transcriptor.buffer.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    let mut transcript = Transcript::<Keccak256Hasher>::new(&[]);
+    // keccak256("")
+    assert_eq!(state(&transcript), "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+
+    // keccak256(abi.encodePacked(state, "point", uint256(1), uint256(2)))
+    transcript.commit_point(&G1Affine::one());
+    assert_eq!(state(&transcript), "6f6f3f745a33980d73763356912271f508ef6a4129bfedfb12b29877a02f706a");
+
+    // keccak256(abi.encodePacked(state, "scalar", uint256(5)))
+    transcript.commit_scalar(&Fr::from_str("5").unwrap());
+    assert_eq!(state(&transcript), "f38824226d266b0bf9939f128ee1ba17d900cbf49d4b59f013eac1e8bd81393e");
+
+    // the hash for nonce 0 is above the modulus, so the first challenge is taken at nonce 1
+    let challenge: Fr = transcript.get_challenge_scalar();
+    assert_eq!(challenge, Fr::from_str("13536231823114301588506051711982562783576966041609143383975627534357304566207").unwrap());
+
+    // the next one continues from nonce 2 and is accepted at nonce 13
+    let challenge: Fr = transcript.get_challenge_scalar();
+    assert_eq!(challenge, Fr::from_str("4833814233160404143715051630135585055137033921697591999084645160105334943618").unwrap());
+}
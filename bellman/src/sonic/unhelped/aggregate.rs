@@ -9,7 +9,7 @@ use crate::sonic::helped::Parameters;
 
 use crate::SynthesisError;
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher, Keccak256Hasher};
 use crate::sonic::util::*;
 use crate::sonic::cs::{Backend, SynthesisDriver};
 use crate::sonic::cs::{Circuit, Variable, Coeff};
@@ -21,6 +21,7 @@ use super::permutation_structure::create_permutation_structure;
 use super::permutation_argument::PermutationArgument;
 use super::permutation_argument::SignatureOfCorrectComputation;
 use super::permutation_argument::SpecializedSRS;
+use super::parameters::SuccinctProvingKey;
 
 #[derive(Clone)]
 pub struct SuccinctAggregate<E: Engine> {
@@ -71,6 +72,20 @@ pub fn create_aggregate_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
 }
 
 pub fn create_aggregate_on_srs_using_information<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
+    circuit: &C,
+    inputs: &[(Proof<E>, SxyAdvice<E>)],
+    srs: &SRS<E>,
+    specialized_srs: &SpecializedSRS<E>,
+    n: usize,
+    q: usize,
+) -> SuccinctAggregate<E>
+{
+    create_aggregate_on_srs_using_information_with_hasher::<E, C, S, Keccak256Hasher>(circuit, inputs, srs, specialized_srs, n, q)
+}
+
+/// Same as `create_aggregate_on_srs_using_information`, for proofs made with
+/// a transcript over the hasher `H`
+pub fn create_aggregate_on_srs_using_information_with_hasher<E: Engine, C: Circuit<E>, S: SynthesisDriver, H: Hasher>(
     circuit: &C,
     inputs: &[(Proof<E>, SxyAdvice<E>)],
     srs: &SRS<E>,
@@ -78,15 +93,51 @@ pub fn create_aggregate_on_srs_using_information<E: Engine, C: Circuit<E>, S: Sy
     n: usize,
     q: usize,
 ) -> SuccinctAggregate<E>
+{
+    create_aggregate_on_srs_using_information_and_key::<E, C, S, H>(circuit, inputs, srs, n, q, None)
+}
+
+/// Create an aggregate using a cached proving key, so the structure of
+/// permutations in s1 is not synthesized again
+pub fn create_aggregate_on_srs_using_key<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
+    circuit: &C,
+    inputs: &[(Proof<E>, SxyAdvice<E>)],
+    srs: &SRS<E>,
+    pk: &SuccinctProvingKey<E>,
+) -> SuccinctAggregate<E>
+{
+    create_aggregate_on_srs_using_key_with_hasher::<E, C, S, Keccak256Hasher>(circuit, inputs, srs, pk)
+}
+
+/// Same as `create_aggregate_on_srs_using_key`, for proofs made with
+/// a transcript over the hasher `H`
+pub fn create_aggregate_on_srs_using_key_with_hasher<E: Engine, C: Circuit<E>, S: SynthesisDriver, H: Hasher>(
+    circuit: &C,
+    inputs: &[(Proof<E>, SxyAdvice<E>)],
+    srs: &SRS<E>,
+    pk: &SuccinctProvingKey<E>,
+) -> SuccinctAggregate<E>
+{
+    create_aggregate_on_srs_using_information_and_key::<E, C, S, H>(circuit, inputs, srs, pk.vk.n, pk.vk.q, Some(pk))
+}
+
+fn create_aggregate_on_srs_using_information_and_key<E: Engine, C: Circuit<E>, S: SynthesisDriver, H: Hasher>(
+    circuit: &C,
+    inputs: &[(Proof<E>, SxyAdvice<E>)],
+    srs: &SRS<E>,
+    n: usize,
+    q: usize,
+    pk: Option<&SuccinctProvingKey<E>>,
+) -> SuccinctAggregate<E>
 {
     use std::time::Instant;
     let start = Instant::now();
     // take few proofs that are to be evaluated at some y_i and make an aggregate from them
-    let mut transcript = Transcript::new(&[]);
+    let mut transcript = Transcript::<H>::new(&[]);
     let mut y_values: Vec<E::Fr> = Vec::with_capacity(inputs.len());
     for &(ref proof, ref sxyadvice) in inputs {
         {
-            let mut transcript = Transcript::new(&[]);
+            let mut transcript = Transcript::<H>::new(&[]);
             transcript.commit_point(&proof.r);
             y_values.push(transcript.get_challenge_scalar());
         }
@@ -140,8 +191,8 @@ pub fn create_aggregate_on_srs_using_information<E: Engine, C: Circuit<E>, S: Sy
 
     // Commit!
 
-    // TODO: Precompute!
     // this will internally synthesize a circuit and structure of permutations
+    // unless they are cached in the proving key
 
     let start = Instant::now();
 
@@ -151,13 +202,15 @@ pub fn create_aggregate_on_srs_using_information<E: Engine, C: Circuit<E>, S: Sy
     println!("S2 proof taken {:?}", start.elapsed());
     let start = Instant::now();
 
-    let permutation_structure = create_permutation_structure(circuit);
-    let (non_permuted_coeffs, permutations) = permutation_structure.create_permutation_vectors();
+    let (non_permuted_coeffs, permutations) = match pk {
+        Some(pk) => (pk.non_permuted_coeffs.clone(), pk.permutations.clone()),
+        None => create_permutation_structure(circuit).create_permutation_vectors()
+    };
 
     println!("Permutation vectors synthesis taken {:?}", start.elapsed());
     let start = Instant::now();
 
-    let signature = PermutationArgument::make_signature(
+    let signature = PermutationArgument::make_signature_with_hasher::<H>(
         non_permuted_coeffs, 
         permutations, 
         w, 
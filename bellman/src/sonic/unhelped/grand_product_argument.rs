@@ -39,8 +39,8 @@ pub struct GrandProductSignature<E: Engine> {
 }
 
 impl<E: Engine> GrandProductArgument<E> {
-    pub fn create_signature(
-        transcript: &mut Transcript,
+    pub fn create_signature<T: TranscriptProtocol>(
+        transcript: &mut T,
         grand_products: Vec<(Vec<E::Fr>, Vec<E::Fr>)>, 
         y: E::Fr,
         z: E::Fr,
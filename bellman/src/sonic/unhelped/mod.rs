@@ -10,8 +10,10 @@ mod permutation_argument;
 mod verifier;
 pub mod permutation_structure;
 mod aggregate;
+mod parameters;
 
 pub use self::wellformed_argument::{WellformednessArgument, WellformednessProof};
-pub use self::permutation_argument::{PermutationArgument, PermutationProof, PermutationArgumentProof};
+pub use self::permutation_argument::{PermutationArgument, PermutationProof, PermutationArgumentProof, SpecializedSRS};
 pub use self::verifier::SuccinctMultiVerifier;
-pub use self::aggregate::*;
\ No newline at end of file
+pub use self::aggregate::*;
+pub use self::parameters::{SuccinctProvingKey, SuccinctVerifyingKey};
\ No newline at end of file
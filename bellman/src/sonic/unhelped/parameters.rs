@@ -0,0 +1,299 @@
+use crate::pairing::ff::{
+    Field,
+    PrimeField,
+    PrimeFieldRepr
+};
+
+use crate::pairing::{
+    Engine,
+    CurveAffine,
+    EncodedPoint
+};
+
+use crate::{
+    SynthesisError
+};
+
+use std::io::{self, Read, Write};
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
+
+use crate::sonic::cs::{Circuit, SynthesisDriver};
+use crate::sonic::srs::SRS;
+use crate::sonic::sonic::Preprocess;
+
+use super::permutation_structure::create_permutation_structure;
+use super::permutation_argument::{PermutationArgument, SpecializedSRS};
+
+/// Verifying key for the succinct mode. Captures everything the verifier
+/// derives from the circuit structure, so checking proofs for a fixed
+/// circuit doesn't require synthesizing permutations of S again
+#[derive(Clone)]
+pub struct SuccinctVerifyingKey<E: Engine> {
+    pub k_map: Vec<usize>,
+
+    pub n: usize,
+
+    pub q: usize,
+
+    // commitments to the coefficients and permutations of s1
+    pub s1_special_reference: SpecializedSRS<E>,
+
+    // commitment to s2, that only depends on n
+    pub s2_special_reference: E::G1Affine
+}
+
+impl<E: Engine> PartialEq for SuccinctVerifyingKey<E> {
+    fn eq(&self, other: &SuccinctVerifyingKey<E>) -> bool {
+        self.k_map == other.k_map &&
+        self.n == other.n &&
+        self.q == other.q &&
+        self.s1_special_reference == other.s1_special_reference &&
+        self.s2_special_reference == other.s2_special_reference
+    }
+}
+
+impl<E: Engine> SuccinctVerifyingKey<E> {
+    pub fn new<C: Circuit<E>, S: SynthesisDriver>(circuit: &C, srs: &SRS<E>) -> Result<Self, SynthesisError> {
+        Ok(SuccinctProvingKey::new::<C, S>(circuit, srs)?.vk)
+    }
+
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_u32::<BigEndian>(self.k_map.len() as u32)?;
+        for k in &self.k_map {
+            writer.write_u32::<BigEndian>(*k as u32)?;
+        }
+        writer.write_u32::<BigEndian>(self.n as u32)?;
+        writer.write_u32::<BigEndian>(self.q as u32)?;
+
+        self.s1_special_reference.write(&mut writer)?;
+        writer.write_all(self.s2_special_reference.into_uncompressed().as_ref())?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let k_map_len = reader.read_u32::<BigEndian>()? as usize;
+
+        let mut k_map = vec![];
+
+        for _ in 0..k_map_len {
+            let k = reader.read_u32::<BigEndian>()? as usize;
+
+            k_map.push(k);
+        }
+
+        let n = reader.read_u32::<BigEndian>()? as usize;
+
+        let q = reader.read_u32::<BigEndian>()? as usize;
+
+        let s1_special_reference = SpecializedSRS::<E>::read(&mut reader)?;
+
+        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
+        reader.read_exact(g1_repr.as_mut())?;
+        let s2_special_reference = g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        Ok(SuccinctVerifyingKey {
+            k_map: k_map,
+            n: n,
+            q: q,
+            s1_special_reference: s1_special_reference,
+            s2_special_reference: s2_special_reference
+        })
+    }
+}
+
+/// Proving key for the succinct mode. Along with the verifying key it keeps
+/// the permutation vectors of s1, which the helper needs for every aggregate
+#[derive(Clone)]
+pub struct SuccinctProvingKey<E: Engine> {
+    pub vk: SuccinctVerifyingKey<E>,
+
+    pub non_permuted_coeffs: Vec<Vec<E::Fr>>,
+
+    pub permutations: Vec<Vec<usize>>
+}
+
+impl<E: Engine> PartialEq for SuccinctProvingKey<E> {
+    fn eq(&self, other: &SuccinctProvingKey<E>) -> bool {
+        self.vk == other.vk &&
+        self.non_permuted_coeffs == other.non_permuted_coeffs &&
+        self.permutations == other.permutations
+    }
+}
+
+impl<E: Engine> SuccinctProvingKey<E> {
+    pub fn new<C: Circuit<E>, S: SynthesisDriver>(circuit: &C, srs: &SRS<E>) -> Result<Self, SynthesisError> {
+        let mut preprocess = Preprocess::new();
+
+        S::synthesize(&mut preprocess, circuit)?;
+
+        let permutation_structure = create_permutation_structure(circuit);
+        let s2_special_reference = permutation_structure.calculate_s2_commitment_value(&srs);
+        let (non_permuted_coeffs, permutations) = permutation_structure.create_permutation_vectors();
+        let s1_special_reference = PermutationArgument::make_specialized_srs(
+            &non_permuted_coeffs,
+            &permutations,
+            &srs
+        );
+
+        Ok(SuccinctProvingKey {
+            vk: SuccinctVerifyingKey {
+                k_map: preprocess.k_map,
+                n: preprocess.n,
+                q: preprocess.q,
+                s1_special_reference: s1_special_reference,
+                s2_special_reference: s2_special_reference
+            },
+            non_permuted_coeffs: non_permuted_coeffs,
+            permutations: permutations
+        })
+    }
+
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        if self.non_permuted_coeffs.len() != self.permutations.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "number of coefficient vectors and permutations differ"));
+        }
+
+        self.vk.write(&mut writer)?;
+
+        writer.write_u32::<BigEndian>(self.permutations.len() as u32)?;
+        for (coeffs, permutation) in self.non_permuted_coeffs.iter().zip(self.permutations.iter()) {
+            if coeffs.len() != permutation.len() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "coefficients and permutation have different lengths"));
+            }
+
+            writer.write_u32::<BigEndian>(coeffs.len() as u32)?;
+            for coeff in coeffs {
+                coeff.into_repr().write_be(&mut writer)?;
+            }
+            for sigma in permutation {
+                writer.write_u32::<BigEndian>(*sigma as u32)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let vk = SuccinctVerifyingKey::<E>::read(&mut reader)?;
+
+        let num_permutations = reader.read_u32::<BigEndian>()? as usize;
+
+        let mut non_permuted_coeffs = vec![];
+        let mut permutations = vec![];
+
+        let mut fr_repr = E::Fr::zero().into_repr();
+
+        for _ in 0..num_permutations {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+
+            // `len` isn't trusted, so the vectors grow as elements are read
+            let mut coeffs = vec![];
+            for _ in 0..len {
+                fr_repr.read_be(&mut reader)?;
+                let coeff = E::Fr::from_repr(fr_repr).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+                coeffs.push(coeff);
+            }
+
+            let mut permutation = vec![];
+            for _ in 0..len {
+                let sigma = reader.read_u32::<BigEndian>()? as usize;
+
+                permutation.push(sigma);
+            }
+
+            non_permuted_coeffs.push(coeffs);
+            permutations.push(permutation);
+        }
+
+        Ok(SuccinctProvingKey {
+            vk: vk,
+            non_permuted_coeffs: non_permuted_coeffs,
+            permutations: permutations
+        })
+    }
+}
+
+#[test]
+fn succinct_keys_test() {
+    use crate::pairing::bls12_381::{Bls12, Fr};
+    use crate::sonic::sonic::{AdaptorCircuit, Permutation3};
+    use crate::sonic::tests::circuits::MulCircuit;
+    use crate::sonic::helped::prover::{create_advice_on_information_and_srs, create_proof_on_srs};
+    use super::{SuccinctMultiVerifier, create_aggregate_on_srs_using_key};
+    use rand::{XorShiftRng, SeedableRng};
+
+    let srs = SRS::<Bls12>::new(
+        100,
+        Fr::from_str("23923").unwrap(),
+        Fr::from_str("23728792").unwrap(),
+    );
+
+    let circuit = AdaptorCircuit(MulCircuit::<Bls12> {
+        a: Fr::from_str("3"),
+        b: Fr::from_str("5"),
+    });
+    let inputs = [Fr::from_str("15").unwrap()];
+
+    let pk = SuccinctProvingKey::<Bls12>::new::<_, Permutation3>(&circuit, &srs).unwrap();
+    assert!(pk.vk == SuccinctVerifyingKey::<Bls12>::new::<_, Permutation3>(&circuit, &srs).unwrap());
+
+    let mut v = vec![];
+    pk.write(&mut v).unwrap();
+    let pk = SuccinctProvingKey::<Bls12>::read(&v[..]).unwrap();
+
+    let mut bad_pk = pk.clone();
+    bad_pk.permutations.pop();
+    match bad_pk.write(&mut vec![]) {
+        Err(ref e) if e.kind() == io::ErrorKind::InvalidData => {},
+        _ => panic!("key with a missing permutation must not be written")
+    }
+
+    let mut bad_pk = pk.clone();
+    bad_pk.permutations[0].pop();
+    match bad_pk.write(&mut vec![]) {
+        Err(ref e) if e.kind() == io::ErrorKind::InvalidData => {},
+        _ => panic!("key with a short permutation must not be written")
+    }
+
+    let mut v = vec![];
+    pk.vk.write(&mut v).unwrap();
+    let vk = SuccinctVerifyingKey::<Bls12>::read(&v[..]).unwrap();
+    assert!(vk == pk.vk);
+
+    // a truncated key declaring a huge permutation
+    v.write_u32::<BigEndian>(1).unwrap();
+    v.write_u32::<BigEndian>(u32::max_value()).unwrap();
+    match SuccinctProvingKey::<Bls12>::read(&v[..]) {
+        Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {},
+        _ => panic!("truncated key must not be read")
+    }
+
+    let proof = create_proof_on_srs::<Bls12, _, Permutation3>(&circuit, &srs).unwrap();
+    let advice = create_advice_on_information_and_srs::<Bls12, _, Permutation3>(&circuit, &proof, &srs, pk.vk.n).unwrap();
+    let proofs = vec![(proof, advice)];
+    let aggregate = create_aggregate_on_srs_using_key::<Bls12, _, Permutation3>(&circuit, &proofs, &srs, &pk);
+
+    let rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+    let mut verifier = SuccinctMultiVerifier::<Bls12, _, Permutation3, _>::new_from_key(circuit, vk, &srs, rng).unwrap();
+    for (proof, advice) in &proofs {
+        verifier.add_proof_with_advice(proof, &inputs, advice);
+    }
+    verifier.add_aggregate(&proofs, &aggregate, &srs);
+    assert!(verifier.check_all());
+}
@@ -3,15 +3,17 @@
 /// for some fixed permutation `perm`
 
 use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr, ScalarEngine};
-use crate::pairing::{Engine, CurveProjective, CurveAffine};
+use crate::pairing::{Engine, CurveProjective, CurveAffine, EncodedPoint};
 use std::marker::PhantomData;
+use std::io::{self, Read, Write};
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
 
 use crate::sonic::srs::SRS;
 use crate::sonic::util::*;
 use super::wellformed_argument::{WellformednessArgument, WellformednessProof};
 use super::grand_product_argument::{GrandProductArgument, GrandProductSignature};
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher, Keccak256Hasher};
 
 #[derive(Clone)]
 pub struct SpecializedSRS<E: Engine> {
@@ -22,6 +24,75 @@ pub struct SpecializedSRS<E: Engine> {
     n: usize
 }
 
+impl<E: Engine> PartialEq for SpecializedSRS<E> {
+    fn eq(&self, other: &SpecializedSRS<E>) -> bool {
+        self.p_1 == other.p_1 &&
+        self.p_2 == other.p_2 &&
+        self.p_3 == other.p_3 &&
+        self.p_4 == other.p_4 &&
+        self.n == other.n
+    }
+}
+
+impl<E: Engine> SpecializedSRS<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        assert_eq!(self.p_2.len(), self.p_4.len());
+
+        writer.write_all(self.p_1.into_uncompressed().as_ref())?;
+        writer.write_all(self.p_3.into_uncompressed().as_ref())?;
+
+        writer.write_u32::<BigEndian>(self.p_2.len() as u32)?;
+        for (p_2, p_4) in self.p_2.iter().zip(self.p_4.iter()) {
+            writer.write_all(p_2.into_uncompressed().as_ref())?;
+            writer.write_all(p_4.into_uncompressed().as_ref())?;
+        }
+
+        writer.write_u32::<BigEndian>(self.n as u32)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
+
+        reader.read_exact(g1_repr.as_mut())?;
+        let p_1 = g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        reader.read_exact(g1_repr.as_mut())?;
+        let p_3 = g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let num_permutations = reader.read_u32::<BigEndian>()? as usize;
+
+        let mut p_2 = vec![];
+        let mut p_4 = vec![];
+
+        for _ in 0..num_permutations {
+            reader.read_exact(g1_repr.as_mut())?;
+            p_2.push(g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?);
+
+            reader.read_exact(g1_repr.as_mut())?;
+            p_4.push(g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?);
+        }
+
+        let n = reader.read_u32::<BigEndian>()? as usize;
+
+        Ok(SpecializedSRS {
+            p_1: p_1,
+            p_2: p_2,
+            p_3: p_3,
+            p_4: p_4,
+            n: n
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct PermutationArgument<E: Engine> {
     non_permuted_coefficients: Vec<Vec<E::Fr>>,
@@ -590,10 +661,21 @@ impl<E: Engine> PermutationArgument<E> {
         y: E::Fr, 
         z: E::Fr,
         srs: &SRS<E>,
+    ) -> SignatureOfCorrectComputation<E> {
+        Self::make_signature_with_hasher::<Keccak256Hasher>(coefficients, permutations, y, z, srs)
+    }
+
+    /// Same as `make_signature`, deriving the challenges from a transcript over the hasher `H`
+    pub fn make_signature_with_hasher<H: Hasher>(
+        coefficients: Vec<Vec<E::Fr>>, 
+        permutations: Vec<Vec<usize>>,
+        y: E::Fr, 
+        z: E::Fr,
+        srs: &SRS<E>,
     ) -> SignatureOfCorrectComputation<E> {
         let mut argument = PermutationArgument::new(coefficients, permutations);
         let commitments = argument.commit(y, &srs);
-        let mut transcript = Transcript::new(&[]);
+        let mut transcript = Transcript::<H>::new(&[]);
 
         let mut s_commitments = vec![];
         let mut s_prime_commitments = vec![];
@@ -638,8 +720,8 @@ impl<E: Engine> PermutationArgument<E> {
     }
 
     // Argument a permutation argument. Current implementation consumes, cause extra arguments are required
-    pub fn make_argument_with_transcript(self, 
-        transcript: &mut Transcript,
+    pub fn make_argument_with_transcript<T: TranscriptProtocol>(self, 
+        transcript: &mut T,
         y: E::Fr, 
         z: E::Fr, 
         srs: &SRS<E>
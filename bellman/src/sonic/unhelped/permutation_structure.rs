@@ -9,7 +9,7 @@ use crate::sonic::helped::Parameters;
 
 use crate::SynthesisError;
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher, Keccak256Hasher};
 use crate::sonic::util::*;
 use crate::sonic::cs::{Backend, SynthesisDriver, ConstraintSystem};
 use crate::sonic::cs::{Circuit, Variable, Coeff};
@@ -449,6 +449,11 @@ impl<E: Engine> PermutationStructure<E> {
     }
 
     pub fn make_signature(&self, y: E::Fr, z: E::Fr, srs: &SRS<E>) -> SignatureOfCorrectComputation<E> {
+        self.make_signature_with_hasher::<Keccak256Hasher>(y, z, srs)
+    }
+
+    /// Same as `make_signature`, deriving the challenges from a transcript over the hasher `H`
+    pub fn make_signature_with_hasher<H: Hasher>(&self, y: E::Fr, z: E::Fr, srs: &SRS<E>) -> SignatureOfCorrectComputation<E> {
         let (non_permuted_coeffs, permutations) = self.create_permutation_vectors();
 
         let mut s_contrib = E::Fr::zero();
@@ -481,7 +486,7 @@ impl<E: Engine> PermutationStructure<E> {
         //     &srs
         // );
 
-        let signature = PermutationArgument::make_signature(
+        let signature = PermutationArgument::make_signature_with_hasher::<H>(
             non_permuted_coeffs,
             permutations,
             y,
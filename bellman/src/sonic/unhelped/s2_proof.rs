@@ -87,12 +87,14 @@ impl<E: Engine> S2Eval<E> {
         h_prep.negate();
         let h_prep = h_prep.prepare();
 
+        let g = srs.g_positive_x[0];
+
         let mut minus_xy = x;
         minus_xy.mul_assign(&y);
         minus_xy.negate();
 
         let mut h_alpha_term = proof.c_opening.mul(minus_xy.into_repr());
-        let g_in_c = E::G1Affine::one().mul(proof.c_value);
+        let g_in_c = g.mul(proof.c_value);
         h_alpha_term.add_assign(&g_in_c);
 
         let h_alpha_term = h_alpha_term.into_affine();
@@ -114,7 +116,7 @@ impl<E: Engine> S2Eval<E> {
         minus_x_y_inv.negate();
 
         let mut h_alpha_term = proof.d_opening.mul(minus_x_y_inv.into_repr());
-        let g_in_d = E::G1Affine::one().mul(proof.d_value);
+        let g_in_d = g.mul(proof.d_value);
         h_alpha_term.add_assign(&g_in_d);
 
         let h_alpha_term = h_alpha_term.into_affine();
@@ -11,16 +11,15 @@ use crate::sonic::helped::parameters::{Parameters};
 
 use crate::SynthesisError;
 
-use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol, Hasher, Keccak256Hasher};
 use crate::sonic::util::*;
 use crate::sonic::cs::{Backend, SynthesisDriver};
 use crate::sonic::cs::{Circuit, Variable, Coeff};
 use crate::sonic::srs::SRS;
-use crate::sonic::sonic::Preprocess;
 
 use super::s2_proof::{S2Proof, S2Eval};
 use super::aggregate::SuccinctAggregate;
-use super::permutation_structure::create_permutation_structure;
+use super::parameters::SuccinctVerifyingKey;
 use super::permutation_argument::{
     PermutationArgumentProof, 
     PermutationProof, 
@@ -28,7 +27,7 @@ use super::permutation_argument::{
     SpecializedSRS
 };
 
-pub struct SuccinctMultiVerifier<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> {
+pub struct SuccinctMultiVerifier<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng, H: Hasher = Keccak256Hasher> {
     circuit: C,
     s1_special_reference: SpecializedSRS<E>,
     s2_special_reference: E::G1Affine,
@@ -37,34 +36,41 @@ pub struct SuccinctMultiVerifier<E: Engine, C: Circuit<E>, S: SynthesisDriver, R
     n: usize,
     q: usize,
     randomness_source: R,
-    _marker: PhantomData<(E, S)>
+    // cleared when a pairing check of an aggregate fails
+    aggregates_valid: bool,
+    _marker: PhantomData<(E, S, H)>
 }
 
-impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier<E, C, S, R> {
+impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng, H: Hasher> SuccinctMultiVerifier<E, C, S, R, H> {
     // This constructor consumes randomness source cause it's later used internally
     pub fn new(circuit: C, srs: &SRS<E>, rng: R) -> Result<Self, SynthesisError> {
-        let (n, q, k_map) = {
-            let mut preprocess = Preprocess::new();
-            S::synthesize(&mut preprocess, &circuit)?;
+        let vk = SuccinctVerifyingKey::new::<C, S>(&circuit, srs)?;
 
-            (preprocess.n, preprocess.q, preprocess.k_map)
-        };
-
-        // also calculate special reference for s1
+        Self::new_from_key(circuit, vk, srs, rng)
+    }
 
-        let permutation_structure = create_permutation_structure(&circuit);
-        let s2_special_reference = permutation_structure.calculate_s2_commitment_value(&srs);
-        let s1_special_reference = permutation_structure.create_permutation_special_reference(&srs);
+    /// Same as `new`, but takes `n`, `q`, `k_map` and the special references
+    /// for s1 and s2 from a cached verifying key, so S is not synthesized.
+    /// The key is what defines the circuit for succinct verification, so it
+    /// must come from a trusted setup of this circuit. `circuit` is only
+    /// synthesized to evaluate s(z, y) for proofs added without advice.
+    /// Aggregates open the permutations of s1 up to x^{3n + 2}, so an SRS
+    /// of a smaller degree gives `PolynomialDegreeTooLarge`
+    pub fn new_from_key(circuit: C, vk: SuccinctVerifyingKey<E>, srs: &SRS<E>, rng: R) -> Result<Self, SynthesisError> {
+        if 3*vk.n + 2 > srs.d {
+            return Err(SynthesisError::PolynomialDegreeTooLarge);
+        }
 
         Ok(SuccinctMultiVerifier {
             circuit,
-            s1_special_reference,
-            s2_special_reference,
-            batch: Batch::new(srs, n),
-            k_map: k_map,
-            n: n,
-            q: q,
+            s1_special_reference: vk.s1_special_reference,
+            s2_special_reference: vk.s2_special_reference,
+            batch: Batch::new(srs, vk.n),
+            k_map: vk.k_map,
+            n: vk.n,
+            q: vk.q,
             randomness_source: rng,
+            aggregates_valid: true,
             _marker: PhantomData
         })
     }
@@ -76,11 +82,13 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
         srs: &SRS<E>
     )
     {
-        let mut transcript = Transcript::new(&[]);
+        assert!(3*self.n + 2 <= srs.d, "SRS must contain x^(3n + 2) for the grand product arguments");
+
+        let mut transcript = Transcript::<H>::new(&[]);
         let mut y_values: Vec<E::Fr> = Vec::with_capacity(proofs.len());
         for &(ref proof, ref sxyadvice) in proofs {
             {
-                let mut transcript = Transcript::new(&[]);
+                let mut transcript = Transcript::<H>::new(&[]);
                 transcript.commit_point(&proof.r);
                 y_values.push(transcript.get_challenge_scalar());
             }
@@ -157,7 +165,7 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
             // verify commitments for s' and s
 
             {
-                let mut transcript = Transcript::new(&[]);
+                let mut transcript = Transcript::<H>::new(&[]);
 
                 // let s_commitments = &aggregate.signature.s_commitments;
                 // let s_prime_commitments = &aggregate.signature.s_prime_commitments;
@@ -292,15 +300,20 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
                 // Now perform an actual check
                 {
                     let randomness: Vec<E::Fr> = (0..aggregate.signature.s_commitments.len()).map(|_| self.randomness_source.gen()).collect();
-                    // e(Dj,hαx)e(D−yz,hα) = e(Aj,h)e(Bj,hxn+1)e(g−aj ,hα)
+                    // e(Dj,hαx)e(D−yz,hα) = e(Aj,h)e(Bj,hx3n+2)e(g−aj ,hα)
 
                     let g = srs.g_positive_x[0];
                     let h_alpha_x_precomp = srs.h_positive_x_alpha[1].prepare();
                     let h_alpha_precomp = srs.h_positive_x_alpha[0].prepare();
 
-                    let mut h_x_n_plus_one_precomp = srs.h_positive_x[self.n+1];
-                    h_x_n_plus_one_precomp.negate();
-                    let h_x_n_plus_one_precomp = h_x_n_plus_one_precomp.prepare();
+                    // grand products run over the 3n + 1 coefficients of the permutations, not over
+                    // the n gates, so b is shifted by x^{3n + 2}. This is the x^{n + 1} of
+                    // `GrandProductArgument::verify_ab_commitment` for products of length 3n + 1,
+                    // the same length the checks below use.
+                    // The degree of the SRS is checked in `new_from_key`
+                    let mut h_x_3n_plus_2_precomp = srs.h_positive_x[3*self.n + 1 + 1];
+                    h_x_3n_plus_2_precomp.negate();
+                    let h_x_3n_plus_2_precomp = h_x_3n_plus_2_precomp.prepare();
 
                     let mut h_prep = srs.h_positive_x[0];
                     h_prep.negate();
@@ -345,18 +358,18 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
                     let openings_zy = openings.mul(yz_neg.into_repr()).into_affine().prepare();
                     let openings = openings.prepare();
 
-                    // e(Dj,hαx)e(D−yz,hα) = e(Aj,h)e(Bj,hxn+1)e(g−aj ,hα)
+                    // e(Dj,hαx)e(D−yz,hα) = e(Aj,h)e(Bj,hx3n+2)e(g−aj ,hα)
 
                     let valid = E::final_exponentiation(&E::miller_loop(&[
                             (&openings, &h_alpha_x_precomp),
                             (&openings_zy, &h_alpha_precomp),
                             (&a, &h_prep),
-                            (&b, &h_x_n_plus_one_precomp),
+                            (&b, &h_x_3n_plus_2_precomp),
                             (&value, &h_alpha_precomp)
                         ])).unwrap() == E::Fqk::one();
 
                     // TODO
-                    assert!(valid, "grand product arguments must be valid for individual commitments");
+                    self.aggregates_valid &= valid;
 
                 }
 
@@ -517,7 +530,7 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
                             (&r_r2.prepare(), &alpha_x_n_minus_d_precomp)
                         ])).unwrap() == E::Fqk::one();
 
-                    assert!(valid, "wellformedness argument must be valid");
+                    self.aggregates_valid &= valid;
                 }
 
             }
@@ -581,7 +594,7 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
         let z = z.unwrap();
 
         // We need to open up SxyAdvice.s at z using SxyAdvice.opening
-        let mut transcript = Transcript::new(&[]);
+        let mut transcript = Transcript::<H>::new(&[]);
         transcript.commit_point(&advice.opening);
         transcript.commit_point(&advice.s);
         transcript.commit_scalar(&advice.szy);
@@ -600,7 +613,7 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
     )
         where F: FnOnce(E::Fr, E::Fr) -> Option<E::Fr>
     {
-        let mut transcript = Transcript::new(&[]);
+        let mut transcript = Transcript::<H>::new(&[]);
 
         transcript.commit_point(&proof.r);
 
@@ -686,7 +699,7 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
     }
 
     pub fn check_all(self) -> bool {
-        self.batch.check_all()
+        self.aggregates_valid && self.batch.check_all()
     }
 }
 
@@ -758,4 +771,3 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
 
 //     Ok(verifier.check_all())
 // }
-
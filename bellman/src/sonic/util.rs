@@ -145,8 +145,7 @@ pub fn polynomial_commitment_opening<
         ).into_affine()
     }
 
-extern crate crossbeam;
-use self::crossbeam::channel::{unbounded};
+use crossbeam_channel::unbounded;
 
 pub fn evaluate_at_consequitive_powers<'a, F: Field> (
     coeffs: &[F],
@@ -337,7 +336,6 @@ where
     use crate::multicore::Worker;
     use crate::multiexp::multiexp;
     use crate::source::FullDensity;
-    use futures::Future;
     use std::sync::Arc;
 
     let s: Vec<<G::Scalar as PrimeField>::Repr> = s.into_iter().map(|e| e.into_repr()).collect::<Vec<_>>();
@@ -0,0 +1,545 @@
+//! A constraint system which checks the satisfiability of a circuit and keeps
+//! the names of its variables and constraints, to help debugging circuits.
+//! Available with the `test-cs` feature.
+
+use crate::pairing::Engine;
+use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
+
+use crate::{
+    ConstraintSystem,
+    Index,
+    LinearCombination,
+    SynthesisError,
+    Variable
+};
+
+use std::collections::{BTreeMap, HashMap};
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use byteorder::{BigEndian, ByteOrder};
+use blake2_rfc::blake2s::Blake2s;
+
+/// `A`, `B` and `C` of a constraint `A * B = C` and its path.
+type NamedConstraint<E> = (
+    LinearCombination<E>,
+    LinearCombination<E>,
+    LinearCombination<E>,
+    String
+);
+
+#[derive(Debug)]
+enum NamedObject {
+    Constraint,
+    Var(Variable),
+    Namespace
+}
+
+/// Constraint system for testing purposes. Variables, constraints and namespaces
+/// are given paths made of the namespaces they were created in and their annotations,
+/// e.g. `hash/round 3/xor`, which must be unique.
+pub struct TestConstraintSystem<E: Engine> {
+    named_objects: HashMap<String, NamedObject>,
+    current_namespace: Vec<String>,
+    constraints: Vec<NamedConstraint<E>>,
+    inputs: Vec<(E::Fr, String)>,
+    aux: Vec<(E::Fr, String)>
+}
+
+#[derive(Clone, Copy)]
+struct OrderedVariable(Variable);
+
+impl Eq for OrderedVariable {}
+impl PartialEq for OrderedVariable {
+    fn eq(&self, other: &OrderedVariable) -> bool {
+        match (self.0.get_unchecked(), other.0.get_unchecked()) {
+            (Index::Input(ref a), Index::Input(ref b)) => a == b,
+            (Index::Aux(ref a), Index::Aux(ref b)) => a == b,
+            _ => false
+        }
+    }
+}
+impl PartialOrd for OrderedVariable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for OrderedVariable {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0.get_unchecked(), other.0.get_unchecked()) {
+            (Index::Input(ref a), Index::Input(ref b)) => a.cmp(b),
+            (Index::Aux(ref a), Index::Aux(ref b)) => a.cmp(b),
+            (Index::Input(_), Index::Aux(_)) => Ordering::Less,
+            (Index::Aux(_), Index::Input(_)) => Ordering::Greater
+        }
+    }
+}
+
+/// Merges the terms of a linear combination by variable and drops the zero ones.
+fn proc_lc<E: Engine>(terms: &[(Variable, E::Fr)]) -> BTreeMap<OrderedVariable, E::Fr> {
+    let mut map = BTreeMap::new();
+    for &(var, coeff) in terms {
+        map.entry(OrderedVariable(var))
+            .or_insert_with(E::Fr::zero)
+            .add_assign(&coeff);
+    }
+
+    map.retain(|_, coeff| !coeff.is_zero());
+
+    map
+}
+
+fn hash_lc<E: Engine>(terms: &[(Variable, E::Fr)], h: &mut Blake2s) {
+    let map = proc_lc::<E>(terms);
+
+    let mut buf = [0u8; 9 + 32];
+    BigEndian::write_u64(&mut buf[0..8], map.len() as u64);
+    h.update(&buf[0..8]);
+
+    for (var, coeff) in map {
+        match var.0.get_unchecked() {
+            Index::Input(i) => {
+                buf[0] = b'I';
+                BigEndian::write_u64(&mut buf[1..9], i as u64);
+            },
+            Index::Aux(i) => {
+                buf[0] = b'A';
+                BigEndian::write_u64(&mut buf[1..9], i as u64);
+            }
+        }
+
+        coeff.into_repr().write_be(&mut buf[9..]).unwrap();
+
+        h.update(&buf);
+    }
+}
+
+fn eval_lc<E: Engine>(
+    terms: &[(Variable, E::Fr)],
+    inputs: &[(E::Fr, String)],
+    aux: &[(E::Fr, String)]
+) -> E::Fr
+{
+    let mut acc = E::Fr::zero();
+
+    for &(var, ref coeff) in terms {
+        let mut tmp = match var.get_unchecked() {
+            Index::Input(index) => inputs[index].0,
+            Index::Aux(index) => aux[index].0
+        };
+
+        tmp.mul_assign(coeff);
+        acc.add_assign(&tmp);
+    }
+
+    acc
+}
+
+impl<E: Engine> Default for TestConstraintSystem<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Engine> TestConstraintSystem<E> {
+    pub fn new() -> TestConstraintSystem<E> {
+        let mut map = HashMap::new();
+        map.insert("ONE".into(), NamedObject::Var(TestConstraintSystem::<E>::one()));
+
+        TestConstraintSystem {
+            named_objects: map,
+            current_namespace: vec![],
+            constraints: vec![],
+            inputs: vec![(E::Fr::one(), "ONE".into())],
+            aux: vec![]
+        }
+    }
+
+    /// Lists the constraints with the paths of the variables they involve.
+    pub fn pretty_print(&self) -> String {
+        let mut s = String::new();
+
+        let negone = {
+            let mut tmp = E::Fr::one();
+            tmp.negate();
+            tmp
+        };
+
+        let powers_of_two = (0..E::Fr::NUM_BITS).map(|i| {
+            E::Fr::from_str("2").unwrap().pow([i as u64])
+        }).collect::<Vec<_>>();
+
+        let pp = |s: &mut String, lc: &LinearCombination<E>| {
+            write!(s, "(").unwrap();
+            let mut is_first = true;
+            for (var, coeff) in proc_lc::<E>(lc.as_ref()) {
+                if coeff == negone {
+                    write!(s, " - ").unwrap();
+                } else if !is_first {
+                    write!(s, " + ").unwrap();
+                }
+                is_first = false;
+
+                if coeff != E::Fr::one() && coeff != negone {
+                    for (i, x) in powers_of_two.iter().enumerate() {
+                        if x == &coeff {
+                            write!(s, "2^{} . ", i).unwrap();
+                            break;
+                        }
+                    }
+
+                    write!(s, "{} . ", coeff).unwrap();
+                }
+
+                match var.0.get_unchecked() {
+                    Index::Input(i) => {
+                        write!(s, "`{}`", &self.inputs[i].1).unwrap();
+                    },
+                    Index::Aux(i) => {
+                        write!(s, "`{}`", &self.aux[i].1).unwrap();
+                    }
+                }
+            }
+            if is_first {
+                // Nothing was visited, print 0.
+                write!(s, "0").unwrap();
+            }
+            write!(s, ")").unwrap();
+        };
+
+        for (a, b, c, name) in &self.constraints {
+            writeln!(s).unwrap();
+
+            write!(s, "{}: ", name).unwrap();
+            pp(&mut s, a);
+            write!(s, " * ").unwrap();
+            pp(&mut s, b);
+            write!(s, " = ").unwrap();
+            pp(&mut s, c);
+        }
+
+        writeln!(s).unwrap();
+
+        s
+    }
+
+    /// BLAKE2s hash of the constraint system, in hex. It only depends on the number of
+    /// variables and on the constraints, not on the names or the assignment, so it
+    /// changes whenever the shape of the circuit does.
+    pub fn hash(&self) -> String {
+        let mut h = Blake2s::new(32);
+        {
+            let mut buf = [0u8; 24];
+
+            BigEndian::write_u64(&mut buf[0..8], self.inputs.len() as u64);
+            BigEndian::write_u64(&mut buf[8..16], self.aux.len() as u64);
+            BigEndian::write_u64(&mut buf[16..24], self.constraints.len() as u64);
+            h.update(&buf);
+        }
+
+        for constraint in &self.constraints {
+            hash_lc::<E>(constraint.0.as_ref(), &mut h);
+            hash_lc::<E>(constraint.1.as_ref(), &mut h);
+            hash_lc::<E>(constraint.2.as_ref(), &mut h);
+        }
+
+        let mut s = String::new();
+        for b in h.finalize().as_bytes() {
+            write!(s, "{:02x}", b).unwrap();
+        }
+
+        s
+    }
+
+    /// Path of the first constraint which isn't satisfied by the assignment, if any.
+    pub fn which_is_unsatisfied(&self) -> Option<&str> {
+        for (a, b, c, path) in &self.constraints {
+            let mut a = eval_lc::<E>(a.as_ref(), &self.inputs, &self.aux);
+            let b = eval_lc::<E>(b.as_ref(), &self.inputs, &self.aux);
+            let c = eval_lc::<E>(c.as_ref(), &self.inputs, &self.aux);
+
+            a.mul_assign(&b);
+
+            if a != c {
+                return Some(path)
+            }
+        }
+
+        None
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.which_is_unsatisfied().is_none()
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Number of constraints created in every namespace, including its sub-namespaces.
+    /// Constraints outside of any namespace are counted under the empty path.
+    pub fn constraints_per_namespace(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+
+        for (_, _, _, path) in &self.constraints {
+            let mut namespace = path.as_str();
+            loop {
+                namespace = match namespace.rfind('/') {
+                    Some(i) => &namespace[..i],
+                    None => ""
+                };
+                *counts.entry(namespace.to_string()).or_insert(0) += 1;
+                if namespace.is_empty() {
+                    break;
+                }
+            }
+        }
+
+        counts
+    }
+
+    /// Changes the value of the variable at `path`, e.g. to check that a circuit
+    /// rejects a wrong witness.
+    pub fn set(&mut self, path: &str, to: E::Fr) {
+        match self.named_objects.get(path) {
+            Some(NamedObject::Var(v)) => {
+                match v.get_unchecked() {
+                    Index::Input(index) => self.inputs[index].0 = to,
+                    Index::Aux(index) => self.aux[index].0 = to
+                }
+            }
+            Some(e) => panic!("tried to set path `{}` to value, but `{:?}` already exists there.", path, e),
+            _ => panic!("no variable exists at path: {}", path)
+        }
+    }
+
+    /// Checks that the public inputs, besides `ONE`, are `expected`.
+    pub fn verify(&self, expected: &[E::Fr]) -> bool {
+        assert_eq!(expected.len() + 1, self.inputs.len());
+
+        for (a, b) in self.inputs.iter().skip(1).zip(expected.iter()) {
+            if &a.0 != b {
+                return false
+            }
+        }
+
+        true
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn get_input(&mut self, index: usize, path: &str) -> E::Fr {
+        let (assignment, name) = self.inputs[index].clone();
+
+        assert_eq!(path, name);
+
+        assignment
+    }
+
+    pub fn get(&mut self, path: &str) -> E::Fr {
+        match self.named_objects.get(path) {
+            Some(NamedObject::Var(v)) => {
+                match v.get_unchecked() {
+                    Index::Input(index) => self.inputs[index].0,
+                    Index::Aux(index) => self.aux[index].0
+                }
+            }
+            Some(e) => panic!("tried to get value of path `{}`, but `{:?}` exists there (not a variable)", path, e),
+            _ => panic!("no variable exists at path: {}", path)
+        }
+    }
+
+    fn set_named_obj(&mut self, path: String, to: NamedObject) {
+        if self.named_objects.contains_key(&path) {
+            panic!("tried to create object at existing path: {}", path);
+        }
+
+        self.named_objects.insert(path, to);
+    }
+}
+
+fn compute_path(ns: &[String], this: String) -> String {
+    if this.chars().any(|a| a == '/') {
+        panic!("'/' is not allowed in names");
+    }
+
+    let mut name = String::new();
+
+    let mut needs_separation = false;
+    for ns in ns.iter().chain(Some(&this)) {
+        if needs_separation {
+            name += "/";
+        }
+
+        name += ns;
+        needs_separation = true;
+    }
+
+    name
+}
+
+impl<E: Engine> ConstraintSystem<E> for TestConstraintSystem<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(
+        &mut self,
+        annotation: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        let index = self.aux.len();
+        let path = compute_path(&self.current_namespace, annotation().into());
+        self.aux.push((f()?, path.clone()));
+        let var = Variable::new_unchecked(Index::Aux(index));
+        self.set_named_obj(path, NamedObject::Var(var));
+
+        Ok(var)
+    }
+
+    fn alloc_input<F, A, AR>(
+        &mut self,
+        annotation: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        let index = self.inputs.len();
+        let path = compute_path(&self.current_namespace, annotation().into());
+        self.inputs.push((f()?, path.clone()));
+        let var = Variable::new_unchecked(Index::Input(index));
+        self.set_named_obj(path, NamedObject::Var(var));
+
+        Ok(var)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(
+        &mut self,
+        annotation: A,
+        a: LA,
+        b: LB,
+        c: LC
+    )
+        where A: FnOnce() -> AR, AR: Into<String>,
+              LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>
+    {
+        let path = compute_path(&self.current_namespace, annotation().into());
+        self.set_named_obj(path.clone(), NamedObject::Constraint);
+
+        let a = a(LinearCombination::zero());
+        let b = b(LinearCombination::zero());
+        let c = c(LinearCombination::zero());
+
+        self.constraints.push((a, b, c, path));
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+        where NR: Into<String>, N: FnOnce() -> NR
+    {
+        let name = name_fn().into();
+        let path = compute_path(&self.current_namespace, name.clone());
+        self.set_named_obj(path, NamedObject::Namespace);
+        self.current_namespace.push(name);
+    }
+
+    fn pop_namespace(&mut self)
+    {
+        assert!(self.current_namespace.pop().is_some());
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pairing::bn256::{Bn256, Fr};
+
+    #[test]
+    fn test_cs() {
+        let mut cs = TestConstraintSystem::<Bn256>::new();
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 0);
+        let a = cs.namespace(|| "a").alloc(|| "var", || Ok(Fr::from_str("10").unwrap())).unwrap();
+        let b = cs.namespace(|| "b").alloc(|| "var", || Ok(Fr::from_str("4").unwrap())).unwrap();
+        let c = cs.alloc(|| "product", || Ok(Fr::from_str("40").unwrap())).unwrap();
+
+        cs.enforce(
+            || "mult",
+            |lc| lc + a,
+            |lc| lc + b,
+            |lc| lc + c
+        );
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 1);
+
+        cs.set("a/var", Fr::from_str("4").unwrap());
+
+        let one = TestConstraintSystem::<Bn256>::one();
+        cs.enforce(
+            || "eq",
+            |lc| lc + a,
+            |lc| lc + one,
+            |lc| lc + b
+        );
+
+        assert!(!cs.is_satisfied());
+        assert!(cs.which_is_unsatisfied() == Some("mult"));
+
+        assert!(cs.get("product") == Fr::from_str("40").unwrap());
+
+        cs.set("product", Fr::from_str("16").unwrap());
+        assert!(cs.is_satisfied());
+
+        {
+            let mut cs = cs.namespace(|| "test1");
+            let mut cs = cs.namespace(|| "test2");
+            cs.alloc(|| "hehe", || Ok(Fr::one())).unwrap();
+        }
+
+        assert!(cs.get("test1/test2/hehe") == Fr::one());
+    }
+
+    #[test]
+    fn test_unsatisfied_path_and_namespace_counts() {
+        let mut cs = TestConstraintSystem::<Bn256>::new();
+        let x = cs.alloc_input(|| "x", || Ok(Fr::from_str("3").unwrap())).unwrap();
+
+        {
+            let mut cs = cs.namespace(|| "square");
+            let y = cs.alloc(|| "y", || Ok(Fr::from_str("9").unwrap())).unwrap();
+            cs.enforce(|| "y = x * x", |lc| lc + x, |lc| lc + x, |lc| lc + y);
+
+            let mut cs = cs.namespace(|| "cube");
+            let z = cs.alloc(|| "z", || Ok(Fr::from_str("27").unwrap())).unwrap();
+            cs.enforce(|| "z = y * x", |lc| lc + y, |lc| lc + x, |lc| lc + z);
+        }
+        cs.enforce(|| "x = x", |lc| lc + x, |lc| lc + TestConstraintSystem::<Bn256>::one(), |lc| lc + x);
+
+        assert!(cs.is_satisfied());
+        assert!(cs.verify(&[Fr::from_str("3").unwrap()]));
+        assert_eq!(cs.num_inputs(), 2);
+
+        let counts = cs.constraints_per_namespace();
+        assert_eq!(counts[""], 3);
+        assert_eq!(counts["square"], 2);
+        assert_eq!(counts["square/cube"], 1);
+
+        // The hash doesn't depend on the assignment
+        let hash = cs.hash();
+        cs.set("square/cube/z", Fr::from_str("28").unwrap());
+        assert_eq!(cs.which_is_unsatisfied(), Some("square/cube/z = y * x"));
+        assert_eq!(cs.hash(), hash);
+
+        // but does depend on the constraints
+        cs.enforce(|| "another", |lc| lc, |lc| lc, |lc| lc);
+        assert!(cs.hash() != hash);
+    }
+}
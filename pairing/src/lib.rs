@@ -318,7 +318,8 @@ impl fmt::Display for GroupDecodingError {
             GroupDecodingError::CoordinateDecodingError(description, ref err) => {
                 write!(f, "{} decoding error: {}", description, err)
             }
-            _ => write!(f, "{}", self.to_string()),
+            #[allow(deprecated)]
+            _ => write!(f, "{}", self.description()),
         }
     }
 }
@@ -2,3 +2,17 @@ pub mod curve;
 pub mod engine;
 pub mod field;
 pub mod repr;
+
+#[test]
+fn test_group_decoding_error_display() {
+    use crate::GroupDecodingError;
+
+    assert_eq!(
+        GroupDecodingError::NotOnCurve.to_string(),
+        "coordinate(s) do not lie on the curve"
+    );
+    assert_eq!(
+        GroupDecodingError::UnexpectedInformation.to_string(),
+        "encoding has unexpected information"
+    );
+}
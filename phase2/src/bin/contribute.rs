@@ -11,8 +11,9 @@ use itertools::Itertools;
 
 use std::fs::File;
 use std::fs::OpenOptions;
+use std::io::{BufReader, BufWriter, Write};
 
-use fawkes_crypto_phase2::parameters::MPCParameters;
+use fawkes_crypto_phase2::streaming::{contribute, DEFAULT_BATCH_SIZE};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -29,8 +30,6 @@ fn main() {
     let entropy = &args[3];
     let print_progress = args.len() == 6 && args[4] == "-v";
 
-    let disallow_points_at_infinity = false;
-
     if print_progress {
         println!("starting");
     }
@@ -71,7 +70,10 @@ fn main() {
                             .read(true)
                             .open(in_params_filename)
                             .expect("unable to open.");
-    let mut params = MPCParameters::read(reader, disallow_points_at_infinity, true).expect("unable to read params");
+    let reader = BufReader::new(reader);
+
+    let writer = File::create(out_params_filename).expect("unable to create output file");
+    let mut writer = BufWriter::new(writer);
 
     println!("Contributing to {}...", in_params_filename);
     let mut progress_update_interval: u32 = 0;
@@ -81,12 +83,13 @@ fn main() {
             progress_update_interval = parsed.unwrap();
         }
     }
-    let hash = params.contribute(&mut rng, &progress_update_interval);
-    println!("Contribution hash: 0x{:02x}", hash.iter().format(""));
 
+    // H and L queries are processed in batches and written to the
+    // output file directly, so that large parameters fit in memory
     println!("Writing parameters to {}.", out_params_filename);
-    let mut f = File::create(out_params_filename).unwrap();
-    params.write(&mut f).expect("failed to write updated parameters");
+    let hash = contribute(reader, &mut writer, &mut rng, DEFAULT_BATCH_SIZE, &progress_update_interval).expect("failed to contribute");
+    writer.flush().expect("failed to write updated parameters");
+    println!("Contribution hash: 0x{:02x}", hash.iter().format(""));
     if print_progress {
         println!("wrote");
     }
@@ -17,7 +17,7 @@ fn main() {
     let vk_filename = &args[2];
     let pk_filename = &args[3];
     println!("Exporting {}...", params_filename);
-    let params = load_params_file(params_filename);
+    let params = load_params_file(params_filename).expect("unable to read params");
     proving_key_json_file(&params, pk_filename).unwrap();
     verification_key_json_file(&params, vk_filename).unwrap();
     println!("Created {} and {}.", pk_filename, vk_filename);
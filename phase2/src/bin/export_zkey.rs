@@ -0,0 +1,34 @@
+extern crate fawkes_crypto_phase2;
+extern crate bellman_ce;
+extern crate exitcode;
+
+use std::fs::OpenOptions;
+
+use bellman_ce::pairing::bn256::Bn256;
+use fawkes_crypto_phase2::parameters::MPCParameters;
+use fawkes_crypto_phase2::circom_circuit::circuit_from_file;
+use fawkes_crypto_phase2::zkey::write_zkey_file;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 4 {
+        println!("Usage: \n<in_circuit.json|in_circuit.r1cs> <in_params.params> <out_circuit.zkey>");
+        std::process::exit(exitcode::USAGE);
+    }
+    let circuit_filename = &args[1];
+    let params_filename = &args[2];
+    let zkey_filename = &args[3];
+
+    let disallow_points_at_infinity = false;
+
+    println!("Exporting {}...", params_filename);
+    let reader = OpenOptions::new()
+                            .read(true)
+                            .open(params_filename)
+                            .expect("unable to open.");
+    let params = MPCParameters::read(reader, disallow_points_at_infinity, true).expect("unable to read params");
+    let circuit = circuit_from_file::<Bn256>(circuit_filename).expect("unable to read circuit");
+
+    write_zkey_file(&params, &circuit, zkey_filename).expect("unable to write zkey");
+    println!("Created {}.", zkey_filename);
+}
@@ -1,20 +1,65 @@
 extern crate fawkes_crypto_phase2;
 extern crate exitcode;
 
-use fawkes_crypto_phase2::circom_circuit::{
-    load_params_file,
-    create_verifier_sol_file
+use fawkes_crypto_phase2::circom_circuit::load_verifying_key_file;
+use fawkes_crypto_phase2::solidity::VerifierVariant;
+use fawkes_crypto_phase2::verifier::{
+    VerifierGenerator,
+    SolidityVerifier,
+    RustVerifier,
+    EthsnarksVerifier
 };
 
+fn usage() -> ! {
+    println!("Usage: \n<params|vk.json> <out_contract.sol> [--pragma <version>] [--variant legacy|assembly|yul] [--batch]");
+    println!("<params|vk.json> <out_crate_dir> --target rust [--crate-name <name>]");
+    println!("<params|vk.json> <out_vk.json> --target ethsnarks");
+    println!("<params> is a phase2 parameters or .zkey file, <vk.json> a verifying key exported by export_keys or snarkjs");
+    std::process::exit(exitcode::USAGE);
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() != 3 {
-        println!("Usage: \n<params> <out_contract.sol>");
-        std::process::exit(exitcode::USAGE);
+    if args.len() < 3 {
+        usage();
     }
-    let params_filename = &args[1];
+    let key_filename = &args[1];
     let verifier_filename = &args[2];
-    let params = load_params_file(params_filename);
-    create_verifier_sol_file(&params, verifier_filename).unwrap();
+
+    let mut target = String::from("solidity");
+    let mut solidity = SolidityVerifier::default();
+    let mut rust = RustVerifier::default();
+    let mut flags = args[3..].iter();
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--target" => target = flags.next().cloned().unwrap_or_else(|| usage()),
+            "--pragma" => solidity.options.pragma = Some(flags.next().cloned().unwrap_or_else(|| usage())),
+            "--variant" => solidity.options.variant = match flags.next().map(|v| v.as_str()) {
+                Some("legacy") => VerifierVariant::Legacy,
+                Some("assembly") => VerifierVariant::Assembly,
+                Some("yul") => VerifierVariant::Yul,
+                _ => usage(),
+            },
+            "--batch" => solidity.options.batch = true,
+            "--crate-name" => rust.crate_name = flags.next().cloned().unwrap_or_else(|| usage()),
+            _ => usage(),
+        }
+    }
+    let generator: Box<dyn VerifierGenerator> = match target.as_str() {
+        "solidity" => Box::new(solidity),
+        "rust" => Box::new(rust),
+        "ethsnarks" => Box::new(EthsnarksVerifier),
+        _ => usage(),
+    };
+
+    let vk = load_verifying_key_file(key_filename).expect("unable to read the verifying key");
+    let verifier = match generator.generate(&vk) {
+        Ok(verifier) => verifier,
+        Err(e) => {
+            println!("Unable to generate the verifier: {}", e);
+            std::process::exit(exitcode::USAGE);
+        }
+    };
+    verifier.write(verifier_filename).unwrap();
     println!("Created {}", verifier_filename);
-}
\ No newline at end of file
+}
@@ -0,0 +1,41 @@
+extern crate fawkes_crypto_phase2;
+extern crate exitcode;
+extern crate itertools;
+
+use itertools::Itertools;
+
+use std::fs::OpenOptions;
+use std::io::BufReader;
+
+use fawkes_crypto_phase2::parameters::contribution_hash;
+use fawkes_crypto_phase2::streaming::read_header;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 2 {
+        println!("Usage: \n<in_params.params>");
+        std::process::exit(exitcode::USAGE);
+    }
+    let params_filename = &args[1];
+
+    let reader = OpenOptions::new()
+                            .read(true)
+                            .open(params_filename)
+                            .expect("unable to open params");
+    // Only the shape and the transcript are printed, so the queries are
+    // skipped instead of read.
+    let header = read_header(BufReader::new(reader)).expect("unable to read params");
+
+    println!("Parameters {}", params_filename);
+    println!("IC: {} ({} public inputs)", header.vk.ic.len(), header.vk.ic.len() - 1);
+    println!("H: {}", header.h_len);
+    println!("L: {}", header.l_len);
+    println!("A: {}", header.a_len);
+    println!("B G1: {}", header.b_g1_len);
+    println!("B G2: {}", header.b_g2_len);
+    println!("cs_hash: 0x{:02x}", header.cs_hash.iter().format(""));
+    println!("Contributions: {}", header.contributions.len());
+    for (i, pubkey) in header.contributions.iter().enumerate() {
+        println!("  {}: 0x{:02x}", i + 1, contribution_hash(pubkey).iter().format(""));
+    }
+}
@@ -4,25 +4,33 @@ extern crate exitcode;
 
 use std::fs::File;
 use fawkes_crypto_phase2::parameters::MPCParameters;
-use fawkes_crypto_phase2::circom_circuit::circuit_from_json_file;
+use fawkes_crypto_phase2::circom_circuit::circuit_from_file;
+use fawkes_crypto_phase2::phase1::phase1_source;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() != 4 {
-        println!("Usage: \n<in_circuit.json> <out_params.params> <path/to/phase1radix>");
+        println!("Usage: \n<in_circuit.json|in_circuit.r1cs> <out_params.params> <path/to/phase1radix|powers.ptau|challenge|response>");
         std::process::exit(exitcode::USAGE);
     }
     let circuit_filename = &args[1];
     let params_filename = &args[2];
-    let radix_directory = &args[3];
+    let phase1_path = &args[3];
 
     let should_filter_points_at_infinity = false;
 
     // Import the circuit and create the initial parameters using phase 1
     println!("Creating initial parameters for {}...", circuit_filename);
     let params = {
-        let c = circuit_from_json_file(&circuit_filename);
-        MPCParameters::new(c, should_filter_points_at_infinity, radix_directory).unwrap()
+        let c = circuit_from_file(&circuit_filename).expect("unable to read circuit");
+        let mut phase1 = phase1_source(phase1_path).expect("unable to open phase 1 parameters");
+        match MPCParameters::new_with_source(c, should_filter_points_at_infinity, &mut *phase1) {
+            Ok(params) => params,
+            Err(e) => {
+                println!("Unable to create parameters: {}", e);
+                std::process::exit(exitcode::DATAERR);
+            }
+        }
     };
 
     println!("Writing initial parameters to {}.", params_filename);
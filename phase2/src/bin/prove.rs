@@ -8,20 +8,20 @@ extern crate itertools;
 
 use std::fs;
 use bellman_ce::pairing::bn256::Bn256;
+use fawkes_crypto_phase2::zkey::prove_with_layout;
 use fawkes_crypto_phase2::circom_circuit::{
-    load_params_file,
-    prove,
+    load_proving_params_file,
     verify,
     create_rng,
     proof_to_json_file,
-    circuit_from_json_file,
-    witness_from_json_file
+    circuit_from_file,
+    witness_from_file
 };
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() != 6 {
-        println!("Usage: \n<circuit.json> <witness.json> <params> <proof.json> <public.json>");
+    if (args.len() != 6 && args.len() != 7) || (args.len() == 7 && args[6] != "-c") {
+        println!("Usage: \n<circuit.json|circuit.r1cs> <witness.json|witness.wtns> <params> <proof.json> <public.json> [-c]");
         std::process::exit(exitcode::USAGE);
     }
     let circuit_filename = &args[1];
@@ -29,14 +29,24 @@ fn main() {
     let params_filename = &args[3];
     let proof_filename = &args[4];
     let public_filename = &args[5];
+    let check_witness = args.len() == 7;
 
     let rng = create_rng();
-    let params = load_params_file(params_filename);
-    let mut circuit = circuit_from_json_file(circuit_filename);
-    circuit.witness =  Some(witness_from_json_file::<Bn256>(witness_filename));
+    let (params, layout) = load_proving_params_file(params_filename).expect("unable to read params");
+    let mut circuit = circuit_from_file(circuit_filename).expect("unable to read circuit");
+    let witness = witness_from_file::<Bn256>(witness_filename).expect("unable to read witness");
+    circuit.set_witness(witness).expect("witness doesn't match circuit");
+
+    if check_witness {
+        println!("Checking witness...");
+        if let Err(e) = circuit.check_witness() {
+            println!("Witness is invalid: {}", e);
+            std::process::exit(exitcode::DATAERR);
+        }
+    }
 
     println!("Proving...");
-    let proof = prove(circuit.clone(), &params, rng).unwrap();
+    let proof = prove_with_layout(circuit.clone(), &params, layout, rng).unwrap();
 
     println!("Verifying proof");
     let correct = verify(&circuit, &params, &proof).unwrap();
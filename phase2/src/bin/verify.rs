@@ -0,0 +1,2 @@
+// `verify vk.json proof.json public.json`, the same binary as verify_proof
+include!("verify_proof.rs");
@@ -2,40 +2,55 @@ extern crate fawkes_crypto_phase2;
 extern crate exitcode;
 
 use std::fs::OpenOptions;
+use std::io::BufReader;
 
 use fawkes_crypto_phase2::parameters::*;
-use fawkes_crypto_phase2::circom_circuit::circuit_from_json_file;
+use fawkes_crypto_phase2::circom_circuit::circuit_from_file;
+use fawkes_crypto_phase2::phase1::phase1_source;
+use fawkes_crypto_phase2::streaming::{self, DEFAULT_BATCH_SIZE};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() != 5 {
-        println!("Usage: \n<in_circuit.json> <in_old_params.params> <in_new_params.params> <path/to/phase1radix>");
+        println!("Usage: \n<in_circuit.json|in_circuit.r1cs> <in_old_params.params> <in_new_params.params> <path/to/phase1radix|powers.ptau|challenge|response>");
         std::process::exit(exitcode::USAGE);
     }
     let circuit_filename = &args[1];
     let old_params_filename = &args[2];
     let new_params_filename = &args[3];
-    let radix_directory = &args[4];
-
-    let disallow_points_at_infinity = false;
+    let phase1_path = &args[4];
 
     let old_reader = OpenOptions::new()
                                 .read(true)
                                 .open(old_params_filename)
                                 .expect("unable to open old params");
-    let old_params = MPCParameters::read(old_reader, disallow_points_at_infinity, true).expect("unable to read old params");
-
     let new_reader = OpenOptions::new()
                                 .read(true)
                                 .open(new_params_filename)
                                 .expect("unable to open new params");
-    let new_params = MPCParameters::read(new_reader, disallow_points_at_infinity, true).expect("unable to read new params");
 
     println!("Checking contribution {}...", new_params_filename);
-    let contribution = verify_contribution(&old_params, &new_params).expect("should verify");
-
+    let contribution = match streaming::verify_contribution(BufReader::new(old_reader), BufReader::new(&new_reader), DEFAULT_BATCH_SIZE) {
+        Ok(contribution) => contribution,
+        Err(e) => {
+            println!("Contribution {} is invalid: {}", new_params_filename, e);
+            std::process::exit(exitcode::DATAERR);
+        }
+    };
+
+    let circuit = circuit_from_file(&circuit_filename).expect("unable to read circuit");
+    let mut phase1 = phase1_source(phase1_path).expect("unable to open phase 1 parameters");
     let should_filter_points_at_infinity = false;
-    let verification_result = new_params.verify(circuit_from_json_file(&circuit_filename), should_filter_points_at_infinity, radix_directory).unwrap();
-    assert!(contains_contribution(&verification_result, &contribution));
+    let verification_result = match streaming::verify(BufReader::new(&new_reader), circuit, should_filter_points_at_infinity, &mut *phase1, DEFAULT_BATCH_SIZE) {
+        Ok(result) => result,
+        Err(e) => {
+            println!("Parameters {} are invalid: {}", new_params_filename, e);
+            std::process::exit(exitcode::DATAERR);
+        }
+    };
+    if !contains_contribution(&verification_result, &contribution) {
+        println!("Contribution hash of {} is missing from its transcript", new_params_filename);
+        std::process::exit(exitcode::DATAERR);
+    }
     println!("Contribution {} verified.", new_params_filename);
 }
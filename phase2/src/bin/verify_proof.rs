@@ -0,0 +1,37 @@
+extern crate fawkes_crypto_phase2;
+extern crate exitcode;
+
+use fawkes_crypto_phase2::circom_circuit::{
+    load_verifying_key_file,
+    proof_from_json_file,
+    public_inputs_from_json_file,
+    verify_with_key
+};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 4 {
+        println!("Usage: \n<in_params.params|in_params.zkey|vk.json> <proof.json> <public.json>");
+        std::process::exit(exitcode::USAGE);
+    }
+    let key_filename = &args[1];
+    let proof_filename = &args[2];
+    let public_filename = &args[3];
+
+    let vk = load_verifying_key_file(key_filename).expect("unable to read verifying key");
+    let proof = proof_from_json_file(proof_filename).expect("unable to read proof");
+    let inputs = public_inputs_from_json_file(public_filename).expect("unable to read public inputs");
+
+    println!("Verifying proof");
+    match verify_with_key(&vk, &proof, &inputs) {
+        Ok(true) => println!("Proof is valid"),
+        Ok(false) => {
+            eprintln!("Proof is invalid");
+            std::process::exit(exitcode::DATAERR);
+        },
+        Err(e) => {
+            eprintln!("Unable to verify proof: {}", e);
+            std::process::exit(exitcode::DATAERR);
+        }
+    }
+}
@@ -0,0 +1,450 @@
+extern crate bellman_ce;
+
+use std::fmt;
+use std::error::Error;
+
+use bellman_ce::{
+    groth16::Proof,
+    pairing::{
+        EncodedPoint,
+        CurveAffine,
+        GroupDecodingError,
+        ff::{
+            PrimeField,
+            PrimeFieldRepr,
+        },
+        bn256::{
+            Bn256,
+            Fq,
+            Fr,
+            G1Affine,
+            G1Uncompressed,
+            G2Affine,
+            G2Uncompressed,
+        }
+    }
+};
+
+use tiny_keccak::{Hasher, Keccak};
+
+use solidity::VerifierVariant;
+
+/// Errors that might occur while decoding the calldata of a call to a
+/// verifier.
+#[derive(Debug)]
+pub enum CalldataError {
+    /// A string which isn't hexadecimal.
+    InvalidHex(hex::FromHexError),
+    /// Calldata which isn't the ABI encoding of a call to a verifier.
+    InvalidCalldata(&'static str),
+    /// A word which isn't an element of the field it encodes.
+    InvalidFieldElement(String),
+    /// A curve point which doesn't decode to a valid point of the group.
+    InvalidPoint(GroupDecodingError),
+}
+
+impl fmt::Display for CalldataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CalldataError::InvalidHex(ref e) => write!(f, "invalid hex: {}", e),
+            CalldataError::InvalidCalldata(reason) => write!(f, "invalid calldata: {}", reason),
+            CalldataError::InvalidFieldElement(ref s) => write!(f, "invalid field element: {:?}", s),
+            CalldataError::InvalidPoint(ref e) => write!(f, "invalid curve point: {}", e),
+        }
+    }
+}
+
+impl Error for CalldataError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            CalldataError::InvalidHex(ref e) => Some(e),
+            CalldataError::InvalidPoint(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<hex::FromHexError> for CalldataError {
+    fn from(err: hex::FromHexError) -> CalldataError {
+        CalldataError::InvalidHex(err)
+    }
+}
+
+/// The size of a proof in calldata, 8 words of 32 bytes.
+pub const PROOF_SIZE: usize = 256;
+
+/// The signature of the `verifyProof` function of a variant, or `None` for
+/// the Yul verifier which has no function selector.
+pub fn verify_proof_signature(variant: VerifierVariant, num_inputs: usize) -> Option<String> {
+    match variant {
+        VerifierVariant::Legacy => Some(format!("verifyProof(bytes,uint256[{}])", num_inputs)),
+        VerifierVariant::Assembly => Some(String::from("verifyProof(uint256[],uint256[])")),
+        VerifierVariant::Yul => None,
+    }
+}
+
+/// The signature of the `verifyProofs` function of batch verifiers, which
+/// only the assembly variant has.
+pub const VERIFY_PROOFS_SIGNATURE: &str = "verifyProofs(uint256[],uint256[])";
+
+fn selector(signature: &str) -> [u8; 4] {
+    let mut hash = [0u8; 32];
+    let mut keccak = Keccak::v256();
+    keccak.update(signature.as_bytes());
+    keccak.finalize(&mut hash);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// The function selector of `verifyProof`, the first 4 bytes of the
+/// Keccak-256 hash of its signature.
+pub fn verify_proof_selector(variant: VerifierVariant, num_inputs: usize) -> Option<[u8; 4]> {
+    verify_proof_signature(variant, num_inputs).map(|signature| selector(&signature))
+}
+
+fn push_repr<R: PrimeFieldRepr>(output: &mut Vec<u8>, repr: R) {
+    repr.write_be(output).expect("writing to a vector never fails");
+}
+
+fn push_usize(output: &mut Vec<u8>, value: usize) {
+    output.extend_from_slice(&[0u8; 24]);
+    output.extend_from_slice(&(value as u64).to_be_bytes());
+}
+
+fn push_g1(output: &mut Vec<u8>, p: &G1Affine) {
+    if p.is_zero() {
+        output.extend_from_slice(&[0u8; 64]);
+    } else {
+        push_repr(output, p.get_x().into_repr());
+        push_repr(output, p.get_y().into_repr());
+    }
+}
+
+fn push_g2(output: &mut Vec<u8>, p: &G2Affine) {
+    if p.is_zero() {
+        output.extend_from_slice(&[0u8; 128]);
+    } else {
+        let (x, y) = (p.get_x(), p.get_y());
+        for f in [x.c1, x.c0, y.c1, y.c0].iter() {
+            push_repr(output, f.into_repr());
+        }
+    }
+}
+
+/// Encodes a proof as the words `[A.X, A.Y, B.X.c1, B.X.c0, B.Y.c1, B.Y.c0,
+/// C.X, C.Y]` the verifiers take, with the points at infinity as zeroes.
+pub fn encode_proof(proof: &Proof<Bn256>) -> Vec<u8> {
+    let mut output = Vec::with_capacity(PROOF_SIZE);
+    push_g1(&mut output, &proof.a);
+    push_g2(&mut output, &proof.b);
+    push_g1(&mut output, &proof.c);
+    output
+}
+
+/// Encodes public inputs as 32-byte big-endian words.
+pub fn encode_inputs(inputs: &[Fr]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(inputs.len() * 32);
+    for input in inputs {
+        push_repr(&mut output, input.into_repr());
+    }
+    output
+}
+
+/// Encodes the calldata of a call to the `verifyProof` function of a
+/// verifier variant.
+pub fn encode_verify_proof(proof: &Proof<Bn256>, inputs: &[Fr], variant: VerifierVariant) -> Vec<u8> {
+    let mut output = vec![];
+    if let Some(selector) = verify_proof_selector(variant, inputs.len()) {
+        output.extend_from_slice(&selector);
+    }
+    match variant {
+        VerifierVariant::Legacy => {
+            // (bytes proof, uint256[n] input): the offset of the proof, the
+            // inputs inline, then the length and contents of the proof.
+            push_usize(&mut output, (inputs.len() + 1) * 32);
+            output.extend(encode_inputs(inputs));
+            push_usize(&mut output, PROOF_SIZE);
+            output.extend(encode_proof(proof));
+        },
+        VerifierVariant::Assembly => {
+            // (uint256[] proof, uint256[] input): the offsets of both arrays,
+            // then the length and contents of each.
+            push_usize(&mut output, 2 * 32);
+            push_usize(&mut output, 2 * 32 + 32 + PROOF_SIZE);
+            push_usize(&mut output, PROOF_SIZE / 32);
+            output.extend(encode_proof(proof));
+            push_usize(&mut output, inputs.len());
+            output.extend(encode_inputs(inputs));
+        },
+        VerifierVariant::Yul => {
+            output.extend(encode_proof(proof));
+            output.extend(encode_inputs(inputs));
+        },
+    }
+    output
+}
+
+/// Encodes the calldata of a call to the `verifyProofs` function of a
+/// batch verifier, with the proofs and the inputs of each proof
+/// concatenated into two arrays.
+pub fn encode_verify_proofs(proofs: &[(Proof<Bn256>, Vec<Fr>)]) -> Vec<u8> {
+    let num_inputs: usize = proofs.iter().map(|(_, inputs)| inputs.len()).sum();
+    let mut output = selector(VERIFY_PROOFS_SIGNATURE).to_vec();
+    push_usize(&mut output, 2 * 32);
+    push_usize(&mut output, 2 * 32 + 32 + proofs.len() * PROOF_SIZE);
+    push_usize(&mut output, proofs.len() * PROOF_SIZE / 32);
+    for (proof, _) in proofs {
+        output.extend(encode_proof(proof));
+    }
+    push_usize(&mut output, num_inputs);
+    for (_, inputs) in proofs {
+        output.extend(encode_inputs(inputs));
+    }
+    output
+}
+
+/// Encodes the calldata of a call to `verifyProof` as a `0x` prefixed hex
+/// string.
+pub fn encode_verify_proof_hex(proof: &Proof<Bn256>, inputs: &[Fr], variant: VerifierVariant) -> String {
+    format!("0x{}", hex::encode(encode_verify_proof(proof, inputs, variant)))
+}
+
+fn slice(data: &[u8], start: usize, len: usize) -> Result<&[u8], CalldataError> {
+    start.checked_add(len)
+        .and_then(|end| data.get(start..end))
+        .ok_or(CalldataError::InvalidCalldata("calldata is too short"))
+}
+
+fn read_usize(data: &[u8], start: usize) -> Result<usize, CalldataError> {
+    let word = slice(data, start, 32)?;
+    if word[..24].iter().any(|b| *b != 0) {
+        return Err(CalldataError::InvalidCalldata("offset or length is too large"));
+    }
+    let mut value = [0u8; 8];
+    value.copy_from_slice(&word[24..]);
+    Ok(u64::from_be_bytes(value) as usize)
+}
+
+fn read_repr<F: PrimeField>(word: &[u8]) -> Result<F, CalldataError> {
+    let mut repr = F::Repr::default();
+    repr.read_be(word).expect("words are 32 bytes long");
+    F::from_repr(repr).map_err(|_| CalldataError::InvalidFieldElement(repr.to_string()))
+}
+
+/// Decodes a proof encoded by `encode_proof`, checking that its points are
+/// on the curve and in the right subgroup.
+pub fn decode_proof(data: &[u8]) -> Result<Proof<Bn256>, CalldataError> {
+    if data.len() != PROOF_SIZE {
+        return Err(CalldataError::InvalidCalldata("a proof is 256 bytes long"));
+    }
+    for word in data.chunks(32) {
+        read_repr::<Fq>(word)?;
+    }
+
+    let g1 = |bytes: &[u8]| -> Result<G1Affine, CalldataError> {
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(G1Affine::zero());
+        }
+        let mut encoded = G1Uncompressed::empty();
+        encoded.as_mut().copy_from_slice(bytes);
+        encoded.into_affine().map_err(CalldataError::InvalidPoint)
+    };
+    let g2 = |bytes: &[u8]| -> Result<G2Affine, CalldataError> {
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(G2Affine::zero());
+        }
+        let mut encoded = G2Uncompressed::empty();
+        encoded.as_mut().copy_from_slice(bytes);
+        encoded.into_affine().map_err(CalldataError::InvalidPoint)
+    };
+
+    Ok(Proof {
+        a: g1(&data[0..64])?,
+        b: g2(&data[64..192])?,
+        c: g1(&data[192..256])?,
+    })
+}
+
+/// Decodes public inputs encoded by `encode_inputs`, checking that they
+/// are elements of the scalar field.
+pub fn decode_inputs(data: &[u8]) -> Result<Vec<Fr>, CalldataError> {
+    if data.len() % 32 != 0 {
+        return Err(CalldataError::InvalidCalldata("inputs are 32 bytes long each"));
+    }
+    data.chunks(32).map(read_repr::<Fr>).collect()
+}
+
+/// Decodes the proof and the public inputs from the calldata of a call
+/// to the `verifyProof` function of a verifier variant.
+pub fn decode_verify_proof(calldata: &[u8], variant: VerifierVariant) -> Result<(Proof<Bn256>, Vec<Fr>), CalldataError> {
+    let (proof, inputs) = match variant {
+        VerifierVariant::Legacy => {
+            let data = slice(calldata, 4, calldata.len().saturating_sub(4))?;
+            let offset = read_usize(data, 0)?;
+            if offset < 32 || offset % 32 != 0 {
+                return Err(CalldataError::InvalidCalldata("invalid offset of the proof"));
+            }
+            if read_usize(data, offset)? != PROOF_SIZE {
+                return Err(CalldataError::InvalidCalldata("a proof is 256 bytes long"));
+            }
+            (slice(data, offset + 32, PROOF_SIZE)?, slice(data, 32, offset - 32)?)
+        },
+        VerifierVariant::Assembly => {
+            let data = slice(calldata, 4, calldata.len().saturating_sub(4))?;
+            let proof_offset = read_usize(data, 0)?;
+            let inputs_offset = read_usize(data, 32)?;
+            if read_usize(data, proof_offset)? != PROOF_SIZE / 32 {
+                return Err(CalldataError::InvalidCalldata("a proof is 8 words long"));
+            }
+            let num_inputs = read_usize(data, inputs_offset)?;
+            let inputs_size = num_inputs.checked_mul(32).ok_or(CalldataError::InvalidCalldata("too many inputs"))?;
+            (slice(data, proof_offset + 32, PROOF_SIZE)?, slice(data, inputs_offset + 32, inputs_size)?)
+        },
+        VerifierVariant::Yul => {
+            if calldata.len() < PROOF_SIZE {
+                return Err(CalldataError::InvalidCalldata("calldata is too short"));
+            }
+            (&calldata[..PROOF_SIZE], &calldata[PROOF_SIZE..])
+        },
+    };
+
+    let inputs = decode_inputs(inputs)?;
+    if let Some(selector) = verify_proof_selector(variant, inputs.len()) {
+        if calldata[..4] != selector[..] {
+            return Err(CalldataError::InvalidCalldata("not a call to verifyProof"));
+        }
+    }
+    Ok((decode_proof(proof)?, inputs))
+}
+
+/// Decodes the calldata of a call to `verifyProof` from a hex string,
+/// with or without the `0x` prefix.
+pub fn decode_verify_proof_hex(calldata: &str, variant: VerifierVariant) -> Result<(Proof<Bn256>, Vec<Fr>), CalldataError> {
+    let calldata = calldata.trim();
+    let calldata = calldata.strip_prefix("0x").unwrap_or(calldata);
+    decode_verify_proof(&hex::decode(calldata)?, variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bellman_ce::pairing::{CurveProjective, bn256::{G1, G2}};
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    fn proof_and_inputs(num_inputs: usize) -> (Proof<Bn256>, Vec<Fr>) {
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let proof = Proof {
+            a: G1::rand(rng).into_affine(),
+            b: G2::rand(rng).into_affine(),
+            c: G1::rand(rng).into_affine(),
+        };
+        (proof, (0..num_inputs).map(|_| Fr::rand(rng)).collect())
+    }
+
+    #[test]
+    fn test_selectors() {
+        // keccak256 of the signatures, computed independently
+        assert_eq!(verify_proof_selector(VerifierVariant::Legacy, 1), Some([0x8d, 0x8f, 0x8a, 0x5c]));
+        assert_eq!(verify_proof_selector(VerifierVariant::Legacy, 2), Some([0xe1, 0xff, 0x50, 0x82]));
+        assert_eq!(verify_proof_selector(VerifierVariant::Assembly, 2), Some([0x72, 0x1e, 0xa4, 0xac]));
+        assert_eq!(verify_proof_selector(VerifierVariant::Yul, 2), None);
+
+        let (proof, inputs) = proof_and_inputs(2);
+        assert_eq!(encode_verify_proof(&proof, &inputs, VerifierVariant::Legacy)[..4], [0xe1, 0xff, 0x50, 0x82]);
+        assert_eq!(encode_verify_proof(&proof, &inputs, VerifierVariant::Assembly)[..4], [0x72, 0x1e, 0xa4, 0xac]);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for &variant in &[VerifierVariant::Legacy, VerifierVariant::Assembly, VerifierVariant::Yul] {
+            for &num_inputs in &[0, 1, 3] {
+                let (proof, inputs) = proof_and_inputs(num_inputs);
+                let calldata = encode_verify_proof(&proof, &inputs, variant);
+                let expected_len = match variant {
+                    VerifierVariant::Legacy => 4 + 32 + 32 * num_inputs + 32 + PROOF_SIZE,
+                    VerifierVariant::Assembly => 4 + 2 * 32 + 32 + PROOF_SIZE + 32 + 32 * num_inputs,
+                    VerifierVariant::Yul => PROOF_SIZE + 32 * num_inputs,
+                };
+                assert_eq!(calldata.len(), expected_len);
+
+                let (decoded_proof, decoded_inputs) = decode_verify_proof(&calldata, variant).unwrap();
+                assert!(decoded_proof == proof);
+                assert_eq!(decoded_inputs, inputs);
+
+                let hex = encode_verify_proof_hex(&proof, &inputs, variant);
+                assert!(hex.starts_with("0x"));
+                let (decoded_proof, decoded_inputs) = decode_verify_proof_hex(&hex, variant).unwrap();
+                assert!(decoded_proof == proof);
+                assert_eq!(decoded_inputs, inputs);
+                assert!(decode_verify_proof_hex(&hex[2..], variant).unwrap().0 == proof);
+            }
+        }
+
+        let zero = Proof { a: G1Affine::zero(), b: G2Affine::zero(), c: G1Affine::zero() };
+        let encoded = encode_proof(&zero);
+        assert_eq!(encoded, vec![0u8; PROOF_SIZE]);
+        assert!(decode_proof(&encoded).unwrap() == zero);
+    }
+
+    #[test]
+    fn test_encode_verify_proofs() {
+        let (proof, inputs) = proof_and_inputs(2);
+        let calldata = encode_verify_proofs(&[(proof.clone(), inputs.clone())]);
+        // for one proof the arguments are laid out as those of the assembly `verifyProof`
+        assert_eq!(calldata[4..], encode_verify_proof(&proof, &inputs, VerifierVariant::Assembly)[4..]);
+        assert_ne!(calldata[..4], verify_proof_selector(VerifierVariant::Assembly, 2).unwrap());
+
+        let (other_proof, other_inputs) = proof_and_inputs(3);
+        let calldata = encode_verify_proofs(&[(proof.clone(), inputs.clone()), (other_proof.clone(), other_inputs[..2].to_vec())]);
+        assert_eq!(calldata.len(), 4 + 2 * 32 + 32 + 2 * PROOF_SIZE + 32 + 4 * 32);
+        let data = &calldata[4..];
+        assert_eq!(read_usize(data, 32).unwrap(), 2 * 32 + 32 + 2 * PROOF_SIZE);
+        assert_eq!(read_usize(data, 64).unwrap(), 2 * PROOF_SIZE / 32);
+        assert!(decode_proof(&data[96 + PROOF_SIZE..96 + 2 * PROOF_SIZE]).unwrap() == other_proof);
+        assert_eq!(read_usize(data, 96 + 2 * PROOF_SIZE).unwrap(), 4);
+        assert_eq!(decode_inputs(&data[128 + 2 * PROOF_SIZE..]).unwrap(), [inputs, other_inputs[..2].to_vec()].concat());
+    }
+
+    #[test]
+    fn test_invalid_calldata() {
+        let (proof, inputs) = proof_and_inputs(2);
+
+        let mut calldata = encode_verify_proof(&proof, &inputs, VerifierVariant::Legacy);
+        calldata[0] ^= 1;
+        match decode_verify_proof(&calldata, VerifierVariant::Legacy) {
+            Err(CalldataError::InvalidCalldata(_)) => {},
+            _ => panic!("wrong selector accepted"),
+        }
+
+        for &variant in &[VerifierVariant::Legacy, VerifierVariant::Assembly, VerifierVariant::Yul] {
+            let calldata = encode_verify_proof(&proof, &inputs, variant);
+            for &len in &[0, 3, 100, calldata.len() - 1] {
+                match decode_verify_proof(&calldata[..len], variant) {
+                    Err(CalldataError::InvalidCalldata(_)) => {},
+                    _ => panic!("calldata truncated to {} bytes accepted by the {} verifier", len, variant),
+                }
+            }
+        }
+
+        // An input equal to the modulus of the scalar field.
+        let mut calldata = encode_verify_proof(&proof, &inputs, VerifierVariant::Yul);
+        let mut modulus = vec![];
+        Fr::char().write_be(&mut modulus).unwrap();
+        calldata[PROOF_SIZE..PROOF_SIZE + 32].copy_from_slice(&modulus);
+        match decode_verify_proof(&calldata, VerifierVariant::Yul) {
+            Err(CalldataError::InvalidFieldElement(_)) => {},
+            _ => panic!("input out of the field accepted"),
+        }
+
+        // A = (1, 1) isn't on the curve.
+        let mut calldata = encode_proof(&proof);
+        for b in calldata[..64].iter_mut() {
+            *b = 0;
+        }
+        calldata[31] = 1;
+        calldata[63] = 1;
+        match decode_proof(&calldata) {
+            Err(CalldataError::InvalidPoint(_)) => {},
+            _ => panic!("point off the curve accepted"),
+        }
+    }
+}
@@ -2,15 +2,21 @@ extern crate bellman_ce;
 extern crate rand;
 
 use std::str;
+use std::fmt;
 use std::fs;
 use std::fs::{OpenOptions, File};
-use std::io::{Read, Write};
+use std::error::Error;
+use std::io::{self, Read, Write, Seek, SeekFrom, BufReader};
+use std::cmp;
 use std::collections::BTreeMap;
 use std::iter::repeat;
 use std::sync::Arc;
 use itertools::Itertools;
+use byteorder::{LittleEndian, ReadBytesExt};
 use rand::{Rng, OsRng};
 use parameters::MPCParameters;
+use zkey::{read_zkey_file, ConstraintLayout, ZkeyError};
+use solidity::{generate_verifier, SolidityOptions};
 
 use bellman_ce::{
     Circuit,
@@ -22,6 +28,7 @@ use bellman_ce::{
     groth16::{
         Parameters,
         Proof,
+        VerifyingKey,
         prepare_verifying_key,
         create_random_proof,
         verify_proof,
@@ -29,11 +36,23 @@ use bellman_ce::{
     pairing::{
         Engine,
         CurveAffine,
+        CurveProjective,
+        EncodedPoint,
+        GroupDecodingError,
         ff::{
+            Field,
             PrimeField,
+            PrimeFieldRepr,
         },
         bn256::{
             Bn256,
+            Fq,
+            Fq2,
+            Fr,
+            G1Affine,
+            G1Uncompressed,
+            G2Affine,
+            G2Uncompressed,
         }
     }
 };
@@ -45,9 +64,201 @@ use crate::utils::{
     pairing_to_vec,
 };
 
+/// Errors that might occur while loading circuits and witnesses, or while
+/// checking a witness against its circuit.
+#[derive(Debug)]
+pub enum CircomError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    /// A `.r1cs` or `.wtns` file with an invalid header.
+    InvalidFile(BinaryFileError),
+    /// A string which isn't a decimal element of the scalar field.
+    InvalidFieldElement(String),
+    /// A constraint refers to a variable index which isn't a number.
+    InvalidIndex(String),
+    /// A constraint refers to a variable the circuit doesn't have.
+    IndexOutOfRange { index: usize, num_variables: usize },
+    /// The witness doesn't assign every variable of the circuit.
+    WitnessLengthMismatch { expected: usize, actual: usize },
+    /// The circuit has no witness to check.
+    MissingWitness,
+    /// The witness doesn't assign 1 to the constant variable 0, with the
+    /// decimal value it assigns instead.
+    InvalidConstantWire(String),
+    /// The witness doesn't satisfy a constraint, with the decimal values
+    /// of both sides of `A * B = C`.
+    UnsatisfiedConstraint { index: usize, ab: String, c: String },
+}
+
+impl fmt::Display for CircomError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CircomError::IoError(ref e) => write!(f, "I/O error: {}", e),
+            CircomError::JsonError(ref e) => write!(f, "JSON error: {}", e),
+            CircomError::InvalidFile(ref e) => write!(f, "{}", e),
+            CircomError::InvalidFieldElement(ref s) => write!(f, "invalid field element: {:?}", s),
+            CircomError::InvalidIndex(ref s) => write!(f, "invalid variable index: {:?}", s),
+            CircomError::IndexOutOfRange { index, num_variables } =>
+                write!(f, "variable index {} is out of range, circuit has {} variables", index, num_variables),
+            CircomError::WitnessLengthMismatch { expected, actual } =>
+                write!(f, "witness has {} elements, circuit expects {}", actual, expected),
+            CircomError::MissingWitness => write!(f, "circuit has no witness"),
+            CircomError::InvalidConstantWire(ref value) =>
+                write!(f, "witness assigns {} to the constant variable 0, expected 1", value),
+            CircomError::UnsatisfiedConstraint { index, ref ab, ref c } =>
+                write!(f, "constraint {} is not satisfied: A * B = {}, C = {}", index, ab, c),
+        }
+    }
+}
+
+impl Error for CircomError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            CircomError::IoError(ref e) => Some(e),
+            CircomError::JsonError(ref e) => Some(e),
+            CircomError::InvalidFile(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CircomError {
+    fn from(err: io::Error) -> CircomError {
+        CircomError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for CircomError {
+    fn from(err: serde_json::Error) -> CircomError {
+        CircomError::JsonError(err)
+    }
+}
+
+impl From<BinaryFileError> for CircomError {
+    fn from(err: BinaryFileError) -> CircomError {
+        match err {
+            BinaryFileError::IoError(e) => CircomError::IoError(e),
+            e => CircomError::InvalidFile(e),
+        }
+    }
+}
+
+/// Errors that might occur while reading the header of an iden3 binary
+/// file (`.r1cs`, `.wtns`, `.zkey`).
+#[derive(Debug)]
+pub enum BinaryFileError {
+    IoError(io::Error),
+    /// The file doesn't start with the expected magic bytes.
+    InvalidMagic { expected: &'static str },
+    /// The file has a format version we don't support.
+    UnsupportedVersion(u32),
+    /// The file lacks a required section.
+    MissingSection(u32),
+    /// The file uses field elements of a different size.
+    FieldSizeMismatch { expected: usize, actual: usize },
+    /// The file is defined over a different prime field.
+    PrimeMismatch,
+}
+
+impl fmt::Display for BinaryFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BinaryFileError::IoError(ref e) => write!(f, "I/O error: {}", e),
+            BinaryFileError::InvalidMagic { expected } => write!(f, "not a .{} file", expected),
+            BinaryFileError::UnsupportedVersion(v) => write!(f, "unsupported file version {}", v),
+            BinaryFileError::MissingSection(t) => write!(f, "missing section of type {}", t),
+            BinaryFileError::FieldSizeMismatch { expected, actual } =>
+                write!(f, "field elements are {} bytes long, expected {}", actual, expected),
+            BinaryFileError::PrimeMismatch => write!(f, "file is defined over a different prime field"),
+        }
+    }
+}
+
+impl Error for BinaryFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            BinaryFileError::IoError(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BinaryFileError {
+    fn from(err: io::Error) -> BinaryFileError {
+        BinaryFileError::IoError(err)
+    }
+}
+
+/// Errors that might occur while reading or writing proofs and keys as
+/// JSON, or while verifying a proof with a key read that way.
+#[derive(Debug)]
+pub enum KeyJsonError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    /// The parameters a verifying key was to be taken from couldn't be
+    /// loaded.
+    InvalidParams(ZkeyError),
+    /// A string which isn't a decimal element of the base field.
+    InvalidFieldElement(String),
+    /// A JSON curve point with the wrong number of coordinates.
+    MalformedPoint(&'static str),
+    /// A curve point which doesn't decode to a valid point of the group.
+    InvalidPoint(GroupDecodingError),
+    /// A verifying key and the public inputs it's used with disagree on
+    /// their number.
+    PublicInputsMismatch { expected: usize, actual: usize },
+}
+
+impl fmt::Display for KeyJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KeyJsonError::IoError(ref e) => write!(f, "I/O error: {}", e),
+            KeyJsonError::JsonError(ref e) => write!(f, "JSON error: {}", e),
+            KeyJsonError::InvalidParams(ref e) => write!(f, "invalid parameters: {}", e),
+            KeyJsonError::InvalidFieldElement(ref s) => write!(f, "invalid field element: {:?}", s),
+            KeyJsonError::MalformedPoint(reason) => write!(f, "malformed curve point: {}", reason),
+            KeyJsonError::InvalidPoint(ref e) => write!(f, "invalid curve point: {}", e),
+            KeyJsonError::PublicInputsMismatch { expected, actual } =>
+                write!(f, "got {} public inputs, verifying key expects {}", actual, expected),
+        }
+    }
+}
+
+impl Error for KeyJsonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            KeyJsonError::IoError(ref e) => Some(e),
+            KeyJsonError::JsonError(ref e) => Some(e),
+            KeyJsonError::InvalidParams(ref e) => Some(e),
+            KeyJsonError::InvalidPoint(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for KeyJsonError {
+    fn from(err: io::Error) -> KeyJsonError {
+        KeyJsonError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for KeyJsonError {
+    fn from(err: serde_json::Error) -> KeyJsonError {
+        KeyJsonError::JsonError(err)
+    }
+}
+
+impl From<ZkeyError> for KeyJsonError {
+    fn from(err: ZkeyError) -> KeyJsonError {
+        KeyJsonError::InvalidParams(err)
+    }
+}
+
+type LinearCombinationJson = BTreeMap<String, String>;
+
 #[derive(Serialize, Deserialize)]
 struct CircuitJson {
-    pub constraints: Vec<Vec<BTreeMap<String, String>>>,
+    pub constraints: Vec<(LinearCombinationJson, LinearCombinationJson, LinearCombinationJson)>,
     #[serde(rename = "nPubInputs")]
     pub num_inputs: usize,
     #[serde(rename = "nOutputs")]
@@ -58,6 +269,7 @@ struct CircuitJson {
 
 #[derive(Serialize, Deserialize)]
 struct ProofJson {
+    #[serde(default)]
     pub protocol: String,
     pub pi_a: Vec<String>,
     pub pi_b: Vec<Vec<String>>,
@@ -84,18 +296,23 @@ struct ProvingKeyJson {
     // Todo: add json fields: nPublic, nVars, polsA, polsB, polsC, protocol: groth
 }
 
+// Newer snarkjs versions spell alpha out, and may leave out the fields
+// which can be derived from the others.
 #[derive(Serialize, Deserialize)]
 struct VerifyingKeyJson {
     #[serde(rename = "IC")]
     pub ic: Vec<Vec<String>>,
+    #[serde(alias = "vk_alpha_1")]
     pub vk_alfa_1: Vec<String>,
     pub vk_beta_2: Vec<Vec<String>>,
     pub vk_gamma_2: Vec<Vec<String>>,
     pub vk_delta_2: Vec<Vec<String>>,
+    #[serde(default, alias = "vk_alphabeta_12")]
     pub vk_alfabeta_12: Vec<Vec<Vec<String>>>,
+    #[serde(default)]
     pub protocol: String,
     #[serde(rename = "nPublic")]
-    pub inputs_count: usize,
+    pub inputs_count: Option<usize>,
 }
 
 #[derive(Clone)]
@@ -112,10 +329,58 @@ pub struct CircomCircuit<E: Engine> {
 }
 
 impl<'a, E: Engine> CircomCircuit<E> {
+    /// Sets the witness, checking that it assigns every variable.
+    pub fn set_witness(&mut self, witness: Vec<E::Fr>) -> Result<(), CircomError> {
+        let expected = self.num_inputs + self.num_aux;
+        if witness.len() != expected {
+            return Err(CircomError::WitnessLengthMismatch { expected, actual: witness.len() });
+        }
+        self.witness = Some(witness);
+        Ok(())
+    }
+
+    /// Checks that the witness assigns every variable and satisfies every
+    /// constraint, which is much cheaper than finding out from a proof
+    /// that doesn't verify.
+    pub fn check_witness(&self) -> Result<(), CircomError> {
+        let witness = self.witness.as_ref().ok_or(CircomError::MissingWitness)?;
+        let expected = self.num_inputs + self.num_aux;
+        if witness.len() != expected {
+            return Err(CircomError::WitnessLengthMismatch { expected, actual: witness.len() });
+        }
+        // Constraints refer to constants through variable 0, bellman's
+        // prover always assigns it 1 regardless of the witness
+        if witness[0] != E::Fr::one() {
+            return Err(CircomError::InvalidConstantWire(repr_to_big(witness[0].into_repr())));
+        }
+
+        let eval_lc = |lc: &[(usize, E::Fr)]| {
+            lc.iter().fold(E::Fr::zero(), |mut acc, (index, coeff)| {
+                let mut term = witness[*index];
+                term.mul_assign(coeff);
+                acc.add_assign(&term);
+                acc
+            })
+        };
+        for (index, (a, b, c)) in self.constraints.iter().enumerate() {
+            let mut ab = eval_lc(a);
+            ab.mul_assign(&eval_lc(b));
+            let c = eval_lc(c);
+            if ab != c {
+                return Err(CircomError::UnsatisfiedConstraint {
+                    index,
+                    ab: repr_to_big(ab.into_repr()),
+                    c: repr_to_big(c.into_repr()),
+                });
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_public_inputs(&self) -> Option<Vec<E::Fr>> {
-        return match self.witness.clone() {
+        return match self.witness {
             None => None,
-            Some(w) => Some(w[1..self.num_inputs].to_vec()),
+            Some(ref w) => w.get(1..self.num_inputs).map(|inputs| inputs.to_vec()),
         }
     }
 
@@ -142,20 +407,20 @@ impl<'a, E: Engine> Circuit<E> for CircomCircuit<E> {
         for i in 1..self.num_inputs {
             cs.alloc_input(|| format!("variable {}", i),
                            || {
-                Ok(match witness {
-                    None => E::Fr::from_str("1").unwrap(),
-                    Some(w) => w[i],
-                })
+                match witness {
+                    None => Ok(E::Fr::from_str("1").unwrap()),
+                    Some(w) => w.get(i).cloned().ok_or(SynthesisError::AssignmentMissing),
+                }
             })?;
         }
 
         for i in 0..self.num_aux {
             cs.alloc(|| format!("aux {}", i),
                            || {
-                Ok(match witness {
-                    None => E::Fr::from_str("1").unwrap(),
-                    Some(w) => w[i + self.num_inputs],
-                })
+                match witness {
+                    None => Ok(E::Fr::from_str("1").unwrap()),
+                    Some(w) => w.get(i + self.num_inputs).cloned().ok_or(SynthesisError::AssignmentMissing),
+                }
             })?;
         }
 
@@ -200,40 +465,7 @@ pub fn verify<E: Engine>(circuit: &CircomCircuit<E>, params: &Parameters<E>, pro
 }
 
 pub fn create_verifier_sol(params: &Parameters<Bn256>) -> String {
-    // TODO: use a simple template engine
-    let bytes = include_bytes!("verifier_groth.sol");
-    let template = String::from_utf8_lossy(bytes);
-
-    let p1_to_str = |p: &<Bn256 as Engine>::G1Affine| {
-        let x = repr_to_big(p.get_x().into_repr());
-        let y = repr_to_big(p.get_y().into_repr());
-        return format!("uint256({}), uint256({})", x, y)
-    };
-    let p2_to_str = |p: &<Bn256 as Engine>::G2Affine| {
-        let x = p.get_x();
-        let y = p.get_y();
-        let x_c0 = repr_to_big(x.c0.into_repr());
-        let x_c1 = repr_to_big(x.c1.into_repr());
-        let y_c0 = repr_to_big(y.c0.into_repr());
-        let y_c1 = repr_to_big(y.c1.into_repr());
-        format!("[uint256({}), uint256({})], [uint256({}), uint256({})]", x_c1, x_c0, y_c1, y_c0)
-    };
-
-    let template = template.replace("<%vk_alfa1%>", &*p1_to_str(&params.vk.alpha_g1));
-    let template = template.replace("<%vk_beta2%>", &*p2_to_str(&params.vk.beta_g2));
-    let template = template.replace("<%vk_gamma2%>", &*p2_to_str(&params.vk.gamma_g2));
-    let template = template.replace("<%vk_delta2%>", &*p2_to_str(&params.vk.delta_g2));
-
-    let template = template.replace("<%vk_ic_length%>", &*params.vk.ic.len().to_string());
-    let template = template.replace("<%vk_input_length%>", &*(params.vk.ic.len() - 1).to_string());
-
-    let mut vi = String::from("");
-    for i in 0..params.vk.ic.len() {
-        vi = format!("{}{}vk.IC[{}] = Pairing.G1Point({});\n", vi, if vi.len() == 0 { "" } else { "        " }, i, &*p1_to_str(&params.vk.ic[i]));
-    }
-    let template = template.replace("<%vk_ic_pts%>", &*vi);
-
-    return template;
+    return generate_verifier(&params.vk, &SolidityOptions::default()).expect("the default verifier options are valid");
 }
 
 pub fn create_verifier_sol_file(params: &Parameters<Bn256>, filename: &str) -> std::io::Result<()> {
@@ -249,23 +481,54 @@ pub fn proof_to_json(proof: &Proof<Bn256>) -> Result<String, serde_json::error::
     });
 }
 
-pub fn proof_to_json_file(proof: &Proof<Bn256>, filename: &str) -> std::io::Result<()> {
-    let str = proof_to_json(proof).unwrap(); // TODO: proper error handling
-    return fs::write(filename, str.as_bytes());
+pub fn proof_to_json_file(proof: &Proof<Bn256>, filename: &str) -> Result<(), KeyJsonError> {
+    let str = proof_to_json(proof)?;
+    fs::write(filename, str.as_bytes())?;
+    Ok(())
 }
 
-pub fn load_params_file(filename: &str) -> Parameters<Bn256> {
+pub fn proof_from_json_file(filename: &str) -> Result<Proof<Bn256>, KeyJsonError> {
     let reader = OpenOptions::new()
         .read(true)
-        .open(filename)
-        .expect("unable to open.");
-    return load_params(reader);
+        .open(filename)?;
+    proof_from_json(reader)
+}
+
+/// Reads a proof written by `proof_to_json` or by snarkjs.
+pub fn proof_from_json<R: Read>(reader: R) -> Result<Proof<Bn256>, KeyJsonError> {
+    let proof: ProofJson = serde_json::from_reader(reader)?;
+    Ok(Proof {
+        a: p1_from_vec(&proof.pi_a)?,
+        b: p2_from_vec(&proof.pi_b)?,
+        c: p1_from_vec(&proof.pi_c)?,
+    })
 }
 
-pub fn load_params<R: Read>(reader: R) -> Parameters<Bn256> {
+/// Loads parameters from either a snarkjs `.zkey` file or a phase2
+/// parameters file, depending on the file extension. Proofs must be made
+/// with the layout of their constraints, see `load_proving_params_file`.
+/// Phase2 parameters files only fail with `ZkeyError::IoError`.
+pub fn load_params_file(filename: &str) -> Result<Parameters<Bn256>, ZkeyError> {
+    load_proving_params_file(filename).map(|(params, _)| params)
+}
+
+/// Like `load_params_file`, along with the layout of the constraints to
+/// prove with, see `zkey::prove_with_layout`. Only `.zkey` files may use
+/// another layout than bellman's.
+pub fn load_proving_params_file(filename: &str) -> Result<(Parameters<Bn256>, ConstraintLayout), ZkeyError> {
+    if filename.ends_with(".zkey") {
+        return read_zkey_file(filename);
+    }
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(filename)?;
+    Ok((load_params(reader)?, ConstraintLayout::Bellman))
+}
+
+pub fn load_params<R: Read>(reader: R) -> io::Result<Parameters<Bn256>> {
     let should_filter_points_at_infinity = false;
-    let params = MPCParameters::read(reader, should_filter_points_at_infinity, true).expect("unable to read params");
-    return params.get_params().clone();
+    let params = MPCParameters::read(reader, should_filter_points_at_infinity, true)?;
+    return Ok(params.get_params().clone());
 }
 
 pub fn filter_params<E: Engine>(params: &mut Parameters<E>) {
@@ -292,9 +555,10 @@ pub fn proving_key_json(params: &Parameters<Bn256>) -> Result<String, serde_json
     return serde_json::to_string(&proving_key);
 }
 
-pub fn proving_key_json_file(params: &Parameters<Bn256>, filename: &str) -> std::io::Result<()> {
-    let str = proving_key_json(params).unwrap(); // TODO: proper error handling
-    return fs::write(filename, str.as_bytes());
+pub fn proving_key_json_file(params: &Parameters<Bn256>, filename: &str) -> Result<(), KeyJsonError> {
+    let str = proving_key_json(params)?;
+    fs::write(filename, str.as_bytes())?;
+    Ok(())
 }
 
 pub fn verification_key_json(params: &Parameters<Bn256>) -> Result<String, serde_json::error::Error> {
@@ -305,61 +569,830 @@ pub fn verification_key_json(params: &Parameters<Bn256>) -> Result<String, serde
         vk_gamma_2: p2_to_vec(&params.vk.gamma_g2),
         vk_delta_2: p2_to_vec(&params.vk.delta_g2),
         vk_alfabeta_12: pairing_to_vec(&Bn256::pairing(params.vk.alpha_g1, params.vk.beta_g2)),
-        inputs_count: params.vk.ic.len() - 1,
+        inputs_count: Some(params.vk.ic.len() - 1),
         protocol: String::from("groth"),
     };
     return serde_json::to_string(&verification_key);
 }
 
-pub fn verification_key_json_file(params: &Parameters<Bn256>, filename: &str) -> std::io::Result<()> {
-    let str = verification_key_json(params).unwrap(); // TODO: proper error handling
-    return fs::write(filename, str.as_bytes());
+pub fn verification_key_json_file(params: &Parameters<Bn256>, filename: &str) -> Result<(), KeyJsonError> {
+    let str = verification_key_json(params)?;
+    fs::write(filename, str.as_bytes())?;
+    Ok(())
 }
 
-pub fn witness_from_json_file<E: Engine>(filename: &str) -> Vec<E::Fr> {
+pub fn verification_key_from_json_file(filename: &str) -> Result<VerifyingKey<Bn256>, KeyJsonError> {
     let reader = OpenOptions::new()
         .read(true)
-        .open(filename)
-        .expect("unable to open.");
+        .open(filename)?;
+    verification_key_from_json(reader)
+}
+
+/// Reads a verifying key written by `verification_key_json` or by snarkjs.
+///
+/// The JSON has no `beta_g1` and `delta_g1`, so they are left as the
+/// point at infinity. The key is only good for verifying proofs, with
+/// `verify_with_key` or `prepare_verifying_key`, and for generating
+/// verifiers. It can't stand in for the key of `Parameters`: proving
+/// with it fails with `SynthesisError::UnexpectedIdentity`.
+pub fn verification_key_from_json<R: Read>(reader: R) -> Result<VerifyingKey<Bn256>, KeyJsonError> {
+    let vk: VerifyingKeyJson = serde_json::from_reader(reader)?;
+    let ic = vk.ic.iter().map(|p| p1_from_vec(p)).collect::<Result<Vec<_>, _>>()?;
+    if ic.is_empty() {
+        return Err(KeyJsonError::MalformedPoint("verifying key has no IC points"));
+    }
+    if let Some(inputs_count) = vk.inputs_count {
+        if inputs_count + 1 != ic.len() {
+            return Err(KeyJsonError::PublicInputsMismatch { expected: ic.len() - 1, actual: inputs_count });
+        }
+    }
+    Ok(VerifyingKey {
+        alpha_g1: p1_from_vec(&vk.vk_alfa_1)?,
+        beta_g1: G1Affine::zero(),
+        beta_g2: p2_from_vec(&vk.vk_beta_2)?,
+        gamma_g2: p2_from_vec(&vk.vk_gamma_2)?,
+        delta_g1: G1Affine::zero(),
+        delta_g2: p2_from_vec(&vk.vk_delta_2)?,
+        ic,
+    })
+}
+
+/// Loads a verifying key from either a JSON file, see
+/// `verification_key_from_json`, or from parameters, see `load_params_file`.
+pub fn load_verifying_key_file(filename: &str) -> Result<VerifyingKey<Bn256>, KeyJsonError> {
+    if filename.ends_with(".json") {
+        return verification_key_from_json_file(filename);
+    }
+    Ok(load_params_file(filename)?.vk)
+}
+
+/// Verifies a proof against a verifying key, checking first that the
+/// number of public inputs matches the key.
+pub fn verify_with_key(vk: &VerifyingKey<Bn256>, proof: &Proof<Bn256>, inputs: &[Fr]) -> Result<bool, KeyJsonError> {
+    if inputs.len() + 1 != vk.ic.len() {
+        return Err(KeyJsonError::PublicInputsMismatch { expected: vk.ic.len() - 1, actual: inputs.len() });
+    }
+    Ok(verify_proof(&prepare_verifying_key(vk), proof, inputs).expect("the number of inputs matches the verifying key"))
+}
+
+fn field_element_from_str<E: Engine>(s: &str) -> Result<E::Fr, CircomError> {
+    E::Fr::from_str(s).ok_or_else(|| CircomError::InvalidFieldElement(s.to_string()))
+}
+
+fn fq_from_str(s: &str) -> Result<Fq, KeyJsonError> {
+    Fq::from_str(s).ok_or_else(|| KeyJsonError::InvalidFieldElement(s.to_string()))
+}
+
+fn fq2_from_vec(v: &[String]) -> Result<Fq2, KeyJsonError> {
+    if v.len() != 2 {
+        return Err(KeyJsonError::MalformedPoint("Fq2 elements have 2 coefficients"));
+    }
+    Ok(Fq2 { c0: fq_from_str(&v[0])?, c1: fq_from_str(&v[1])? })
+}
+
+/// Converts Jacobian coordinates, which is what snarkjs's projective
+/// `[x, y, z]` are, to affine ones. `z` must not be zero.
+fn from_jacobian<F: Field>(mut x: F, mut y: F, z: F) -> (F, F) {
+    let z_inv = z.inverse().expect("z is not zero");
+    let mut z_inv2 = z_inv;
+    z_inv2.square();
+    let mut z_inv3 = z_inv2;
+    z_inv3.mul_assign(&z_inv);
+    x.mul_assign(&z_inv2);
+    y.mul_assign(&z_inv3);
+    (x, y)
+}
+
+/// Reads a G1 point from the decimal `[x, y]` or `[x, y, z]` of
+/// `p1_to_vec` and snarkjs, checking that it's on the curve.
+fn p1_from_vec(v: &[String]) -> Result<G1Affine, KeyJsonError> {
+    let (x, y, z) = match v.len() {
+        2 => (fq_from_str(&v[0])?, fq_from_str(&v[1])?, Fq::one()),
+        3 => (fq_from_str(&v[0])?, fq_from_str(&v[1])?, fq_from_str(&v[2])?),
+        _ => return Err(KeyJsonError::MalformedPoint("G1 points have 2 or 3 coordinates")),
+    };
+    if z.is_zero() || (x.is_zero() && y.is_zero()) {
+        return Ok(G1Affine::zero());
+    }
+    let (x, y) = from_jacobian(x, y, z);
+    let mut encoded = G1Uncompressed::empty();
+    {
+        let mut writer = encoded.as_mut();
+        x.into_repr().write_be(&mut writer)?;
+        y.into_repr().write_be(&mut writer)?;
+    }
+    encoded.into_affine().map_err(KeyJsonError::InvalidPoint)
+}
+
+/// Reads a G2 point from the decimal `[x, y]` or `[x, y, z]` of
+/// `p2_to_vec` and snarkjs, with `Fq2` elements as `[c0, c1]`.
+fn p2_from_vec(v: &[Vec<String>]) -> Result<G2Affine, KeyJsonError> {
+    let (x, y, z) = match v.len() {
+        2 => (fq2_from_vec(&v[0])?, fq2_from_vec(&v[1])?, Fq2::one()),
+        3 => (fq2_from_vec(&v[0])?, fq2_from_vec(&v[1])?, fq2_from_vec(&v[2])?),
+        _ => return Err(KeyJsonError::MalformedPoint("G2 points have 2 or 3 coordinates")),
+    };
+    if z.is_zero() || (x.is_zero() && y.is_zero()) {
+        return Ok(G2Affine::zero());
+    }
+    let (x, y) = from_jacobian(x, y, z);
+    let mut encoded = G2Uncompressed::empty();
+    {
+        let mut writer = encoded.as_mut();
+        for f in [x.c1, x.c0, y.c1, y.c0].iter() {
+            f.into_repr().write_be(&mut writer)?;
+        }
+    }
+    let point = encoded.into_affine().map_err(KeyJsonError::InvalidPoint)?;
+    check_g2_subgroup(point).map_err(KeyJsonError::InvalidPoint)
+}
+
+/// G2 of BN254 has a cofactor, so decoding only ensures a point is on the
+/// curve. Points read from untrusted files are also checked to be in the
+/// subgroup of order r.
+pub(crate) fn check_g2_subgroup(point: G2Affine) -> Result<G2Affine, GroupDecodingError> {
+    if !point.mul(Fr::char()).is_zero() {
+        return Err(GroupDecodingError::NotInSubgroup);
+    }
+    Ok(point)
+}
+
+pub fn witness_from_json_file<E: Engine>(filename: &str) -> Result<Vec<E::Fr>, CircomError> {
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(filename)?;
     return witness_from_json::<E, File>(reader);
 }
 
-pub fn witness_from_json<E: Engine, R: Read>(reader: R) -> Vec<E::Fr>{
-    let witness: Vec<String> = serde_json::from_reader(reader).unwrap();
-    return witness.into_iter().map(|x| E::Fr::from_str(&x).unwrap()).collect::<Vec<E::Fr>>();
+pub fn witness_from_json<E: Engine, R: Read>(reader: R) -> Result<Vec<E::Fr>, CircomError> {
+    let witness: Vec<String> = serde_json::from_reader(reader)?;
+    return witness.iter().map(|x| field_element_from_str::<E>(x)).collect();
+}
+
+/// Reads public inputs, which are a JSON array of decimal strings like a
+/// witness.
+pub fn public_inputs_from_json_file(filename: &str) -> Result<Vec<Fr>, CircomError> {
+    witness_from_json_file::<Bn256>(filename)
 }
 
-pub fn circuit_from_json_file<E: Engine>(filename: &str) -> CircomCircuit::<E> {
+pub fn circuit_from_json_file<E: Engine>(filename: &str) -> Result<CircomCircuit::<E>, CircomError> {
     let reader = OpenOptions::new()
         .read(true)
-        .open(filename)
-        .expect("unable to open.");
+        .open(filename)?;
     return circuit_from_json(reader);
 }
 
-pub fn circuit_from_json<E: Engine, R: Read>(reader: R) -> CircomCircuit::<E> {
-    let circuit_json: CircuitJson = serde_json::from_reader(reader).unwrap();
+pub fn circuit_from_json<E: Engine, R: Read>(reader: R) -> Result<CircomCircuit::<E>, CircomError> {
+    let circuit_json: CircuitJson = serde_json::from_reader(reader)?;
 
     let num_inputs = circuit_json.num_inputs + circuit_json.num_outputs + 1;
-    let num_aux = circuit_json.num_variables - num_inputs;
+    let num_variables = circuit_json.num_variables;
+    if num_inputs > num_variables {
+        return Err(CircomError::IndexOutOfRange { index: num_inputs - 1, num_variables });
+    }
+    let num_aux = num_variables - num_inputs;
 
-    let convert_constraint = |lc: &BTreeMap<String, String>| {
-        lc.iter().map(|(index, coeff)| (index.parse().unwrap(), E::Fr::from_str(coeff).unwrap())).collect_vec()
+    let convert_constraint = |lc: &LinearCombinationJson| -> Result<Vec<(usize, E::Fr)>, CircomError> {
+        lc.iter().map(|(index, coeff)| {
+            let index: usize = index.parse().map_err(|_| CircomError::InvalidIndex(index.clone()))?;
+            if index >= num_variables {
+                return Err(CircomError::IndexOutOfRange { index, num_variables });
+            }
+            Ok((index, field_element_from_str::<E>(coeff)?))
+        }).collect()
     };
 
     let constraints = circuit_json.constraints.iter().map(
-        |c| (convert_constraint(&c[0]), convert_constraint(&c[1]), convert_constraint(&c[2]))
-    ).collect_vec();
+        |c| Ok((convert_constraint(&c.0)?, convert_constraint(&c.1)?, convert_constraint(&c.2)?))
+    ).collect::<Result<Vec<_>, CircomError>>()?;
 
-    return CircomCircuit {
+    return Ok(CircomCircuit {
         num_inputs: num_inputs,
         num_aux: num_aux,
         num_constraints: circuit_json.num_variables,
         witness: None,
         constraints: constraints,
+    });
+}
+
+/// Reads the header and section table of an iden3 binary file
+/// (`.r1cs`, `.wtns`), returning the offset and size of each section
+/// by its type.
+pub(crate) fn read_sections<R: Read + Seek>(
+    reader: &mut R,
+    magic: &'static str,
+    max_version: u32,
+) -> Result<BTreeMap<u32, (u64, u64)>, BinaryFileError> {
+    let mut file_magic = [0u8; 4];
+    reader.read_exact(&mut file_magic)?;
+    if &file_magic[..] != magic.as_bytes() {
+        return Err(BinaryFileError::InvalidMagic { expected: magic });
+    }
+
+    let version = reader.read_u32::<LittleEndian>()?;
+    if version == 0 || version > max_version {
+        return Err(BinaryFileError::UnsupportedVersion(version));
+    }
+
+    let num_sections = reader.read_u32::<LittleEndian>()?;
+    let mut sections = BTreeMap::new();
+    for _ in 0..num_sections {
+        let section_type = reader.read_u32::<LittleEndian>()?;
+        let size = reader.read_u64::<LittleEndian>()?;
+        let offset = reader.seek(SeekFrom::Current(0))?;
+        sections.entry(section_type).or_insert((offset, size));
+        reader.seek(SeekFrom::Current(size as i64))?;
+    }
+
+    Ok(sections)
+}
+
+/// Moves the reader to the start of a section, returning its size.
+pub(crate) fn seek_section<R: Read + Seek>(
+    reader: &mut R,
+    sections: &BTreeMap<u32, (u64, u64)>,
+    section_type: u32,
+) -> Result<u64, BinaryFileError> {
+    let &(offset, size) = sections.get(&section_type).ok_or(BinaryFileError::MissingSection(section_type))?;
+    reader.seek(SeekFrom::Start(offset))?;
+    Ok(size)
+}
+
+/// Capacity to reserve for `count` elements of at least `element_size`
+/// bytes each, read from a section of `section_size` bytes. Counts come
+/// from the file, so they can't be trusted to allocate up front.
+fn capacity_for(count: usize, section_size: u64, element_size: usize) -> usize {
+    cmp::min(count as u64, section_size / element_size as u64) as usize
+}
+
+/// Reads the field element size and the prime of a binary file header,
+/// checking that they describe the field `F`.
+pub(crate) fn read_field_header<F: PrimeField, R: Read>(reader: &mut R) -> Result<(), BinaryFileError> {
+    let expected = F::char().as_ref().len() * 8;
+    let field_size = reader.read_u32::<LittleEndian>()? as usize;
+    if field_size != expected {
+        return Err(BinaryFileError::FieldSizeMismatch { expected, actual: field_size });
+    }
+
+    let mut prime = F::Repr::default();
+    prime.read_le(reader)?;
+    if prime != F::char() {
+        return Err(BinaryFileError::PrimeMismatch);
+    }
+
+    Ok(())
+}
+
+/// Reads a field element in little-endian canonical form.
+fn read_field_element<E: Engine, R: Read>(reader: &mut R) -> Result<E::Fr, CircomError> {
+    let mut repr = <E::Fr as PrimeField>::Repr::default();
+    repr.read_le(reader)?;
+    E::Fr::from_repr(repr).map_err(|_| CircomError::InvalidFieldElement(repr.to_string()))
+}
+
+pub fn witness_from_wtns_file<E: Engine>(filename: &str) -> Result<Vec<E::Fr>, CircomError> {
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(filename)?;
+    return witness_from_wtns::<E, _>(BufReader::new(reader));
+}
+
+/// Reads a witness in the binary `.wtns` format produced by circom 2
+/// witness calculators and snarkjs.
+pub fn witness_from_wtns<E: Engine, R: Read + Seek>(mut reader: R) -> Result<Vec<E::Fr>, CircomError> {
+    let sections = read_sections(&mut reader, "wtns", 2)?;
+
+    seek_section(&mut reader, &sections, 1)?;
+    read_field_header::<E::Fr, _>(&mut reader)?;
+    let num_witness = reader.read_u32::<LittleEndian>()? as usize;
+
+    let section_size = seek_section(&mut reader, &sections, 2)?;
+    let element_size = <E::Fr as PrimeField>::Repr::default().as_ref().len() * 8;
+    let mut witness = Vec::with_capacity(capacity_for(num_witness, section_size, element_size));
+    for _ in 0..num_witness {
+        witness.push(read_field_element::<E, _>(&mut reader)?);
+    }
+
+    return Ok(witness);
+}
+
+pub fn circuit_from_r1cs_file<E: Engine>(filename: &str) -> Result<CircomCircuit::<E>, CircomError> {
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(filename)?;
+    return circuit_from_r1cs(BufReader::new(reader));
+}
+
+/// Reads a circuit in the binary `.r1cs` format produced by circom 2.
+pub fn circuit_from_r1cs<E: Engine, R: Read + Seek>(mut reader: R) -> Result<CircomCircuit::<E>, CircomError> {
+    let sections = read_sections(&mut reader, "r1cs", 1)?;
+
+    seek_section(&mut reader, &sections, 1)?;
+    read_field_header::<E::Fr, _>(&mut reader)?;
+    let num_variables = reader.read_u32::<LittleEndian>()? as usize;
+    let num_outputs = reader.read_u32::<LittleEndian>()? as usize;
+    let num_public_inputs = reader.read_u32::<LittleEndian>()? as usize;
+    let _num_private_inputs = reader.read_u32::<LittleEndian>()?;
+    let _num_labels = reader.read_u64::<LittleEndian>()?;
+    let num_constraints = reader.read_u32::<LittleEndian>()? as usize;
+
+    let num_inputs = num_outputs + num_public_inputs + 1;
+    if num_inputs > num_variables {
+        return Err(CircomError::IndexOutOfRange { index: num_inputs - 1, num_variables });
+    }
+    let num_aux = num_variables - num_inputs;
+
+    let section_size = seek_section(&mut reader, &sections, 2)?;
+    // a term is a u32 index and a field element, a constraint is at least
+    // the three u32 term counts of its linear combinations
+    let term_size = 4 + <E::Fr as PrimeField>::Repr::default().as_ref().len() * 8;
+    let read_lc = |reader: &mut R| -> Result<Vec<(usize, E::Fr)>, CircomError> {
+        let num_terms = reader.read_u32::<LittleEndian>()? as usize;
+        let mut lc = Vec::with_capacity(capacity_for(num_terms, section_size, term_size));
+        for _ in 0..num_terms {
+            let index = reader.read_u32::<LittleEndian>()? as usize;
+            if index >= num_variables {
+                return Err(CircomError::IndexOutOfRange { index, num_variables });
+            }
+            lc.push((index, read_field_element::<E, _>(reader)?));
+        }
+        Ok(lc)
     };
+
+    let mut constraints = Vec::with_capacity(capacity_for(num_constraints, section_size, 12));
+    for _ in 0..num_constraints {
+        let a = read_lc(&mut reader)?;
+        let b = read_lc(&mut reader)?;
+        let c = read_lc(&mut reader)?;
+        constraints.push((a, b, c));
+    }
+
+    return Ok(CircomCircuit {
+        num_inputs,
+        num_aux,
+        num_constraints,
+        witness: None,
+        constraints,
+    });
+}
+
+/// Loads a circuit from either a binary `.r1cs` file or a legacy
+/// circom `circuit.json`, depending on the file extension.
+pub fn circuit_from_file<E: Engine>(filename: &str) -> Result<CircomCircuit::<E>, CircomError> {
+    if filename.ends_with(".r1cs") {
+        circuit_from_r1cs_file(filename)
+    } else {
+        circuit_from_json_file(filename)
+    }
+}
+
+/// Loads a witness from either a binary `.wtns` file or a JSON array
+/// of decimal strings, depending on the file extension.
+pub fn witness_from_file<E: Engine>(filename: &str) -> Result<Vec<E::Fr>, CircomError> {
+    if filename.ends_with(".wtns") {
+        witness_from_wtns_file::<E>(filename)
+    } else {
+        witness_from_json_file::<E>(filename)
+    }
 }
 
 pub fn create_rng() -> Box<dyn Rng> {
     return Box::new(OsRng::new().unwrap())
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use byteorder::WriteBytesExt;
+    use phase1::tests::TestPowers;
+    use std::io::Cursor;
+
+    fn write_header(bytes: &mut Vec<u8>, magic: &[u8], version: u32, sections: &[(u32, Vec<u8>)]) {
+        bytes.extend_from_slice(magic);
+        bytes.write_u32::<LittleEndian>(version).unwrap();
+        bytes.write_u32::<LittleEndian>(sections.len() as u32).unwrap();
+        for (section_type, data) in sections {
+            bytes.write_u32::<LittleEndian>(*section_type).unwrap();
+            bytes.write_u64::<LittleEndian>(data.len() as u64).unwrap();
+            bytes.extend_from_slice(data);
+        }
+    }
+
+    fn write_field_header(bytes: &mut Vec<u8>) {
+        bytes.write_u32::<LittleEndian>(32).unwrap();
+        Fr::char().write_le(&mut *bytes).unwrap();
+    }
+
+    fn write_fr(bytes: &mut Vec<u8>, value: &str) {
+        Fr::from_str(value).unwrap().into_repr().write_le(&mut *bytes).unwrap();
+    }
+
+    fn r1cs_header(num_variables: u32, num_constraints: u32) -> Vec<u8> {
+        let mut header = vec![];
+        write_field_header(&mut header);
+        header.write_u32::<LittleEndian>(num_variables).unwrap();
+        // outputs, public inputs, private inputs
+        header.write_u32::<LittleEndian>(1).unwrap();
+        header.write_u32::<LittleEndian>(0).unwrap();
+        header.write_u32::<LittleEndian>(2).unwrap();
+        // labels
+        header.write_u64::<LittleEndian>(4).unwrap();
+        header.write_u32::<LittleEndian>(num_constraints).unwrap();
+        header
+    }
+
+    fn write_lc(bytes: &mut Vec<u8>, lc: &[(u32, &str)]) {
+        bytes.write_u32::<LittleEndian>(lc.len() as u32).unwrap();
+        for (index, coeff) in lc {
+            bytes.write_u32::<LittleEndian>(*index).unwrap();
+            write_fr(bytes, coeff);
+        }
+    }
+
+    /// The constraints section of `a * b = c`, with the output `c` as
+    /// variable 1 and the private inputs `a`, `b` as variables 2 and 3.
+    fn multiplier_constraints() -> Vec<u8> {
+        let mut constraints = vec![];
+        write_lc(&mut constraints, &[(2, "1")]);
+        write_lc(&mut constraints, &[(3, "1")]);
+        write_lc(&mut constraints, &[(1, "1")]);
+        constraints
+    }
+
+    /// An `.r1cs` file of `a * b = c`, laid out the way circom writes it.
+    pub(crate) fn multiplier_r1cs() -> Vec<u8> {
+        let mut r1cs = vec![];
+        write_header(&mut r1cs, b"r1cs", 1, &[
+            (1, r1cs_header(4, 1)),
+            (2, multiplier_constraints()),
+            // wire to label map, which isn't needed
+            (3, vec![0; 32]),
+        ]);
+        r1cs
+    }
+
+    /// A `.wtns` file assigning `values` to the variables.
+    pub(crate) fn wtns(values: &[&str]) -> Vec<u8> {
+        let mut header = vec![];
+        write_field_header(&mut header);
+        header.write_u32::<LittleEndian>(values.len() as u32).unwrap();
+        let mut witness = vec![];
+        for value in values {
+            write_fr(&mut witness, value);
+        }
+
+        let mut wtns = vec![];
+        write_header(&mut wtns, b"wtns", 2, &[(1, header), (2, witness)]);
+        wtns
+    }
+
+    /// The circuit of `multiplier_r1cs` with a witness for `3 * 11 = 33`.
+    pub(crate) fn multiplier_circuit() -> CircomCircuit<Bn256> {
+        let mut circuit = circuit_from_r1cs::<Bn256, _>(Cursor::new(multiplier_r1cs())).unwrap();
+        circuit.set_witness(witness_from_wtns::<Bn256, _>(Cursor::new(wtns(&["1", "33", "3", "11"]))).unwrap()).unwrap();
+        circuit
+    }
+
+    fn fr(value: &str) -> Fr {
+        Fr::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_r1cs() {
+        let circuit = circuit_from_r1cs::<Bn256, _>(Cursor::new(multiplier_r1cs())).unwrap();
+        assert_eq!(circuit.num_inputs, 2);
+        assert_eq!(circuit.num_aux, 2);
+        assert_eq!(circuit.num_constraints, 1);
+        assert_eq!(circuit.constraints, vec![(vec![(2, fr("1"))], vec![(3, fr("1"))], vec![(1, fr("1"))])]);
+
+        // Sections may come in any order
+        let mut reordered = vec![];
+        write_header(&mut reordered, b"r1cs", 1, &[(2, multiplier_constraints()), (1, r1cs_header(4, 1))]);
+        let reordered = circuit_from_r1cs::<Bn256, _>(Cursor::new(reordered)).unwrap();
+        assert_eq!(reordered.constraints, circuit.constraints);
+    }
+
+    #[test]
+    fn test_wtns() {
+        let witness = witness_from_wtns::<Bn256, _>(Cursor::new(wtns(&["1", "33", "3", "11"]))).unwrap();
+        assert_eq!(witness, vec![fr("1"), fr("33"), fr("3"), fr("11")]);
+
+        let circuit = multiplier_circuit();
+        assert_eq!(circuit.get_public_inputs(), Some(vec![fr("33")]));
+    }
+
+    #[test]
+    fn test_check_witness() {
+        let mut circuit = multiplier_circuit();
+        circuit.check_witness().unwrap();
+
+        circuit.witness = Some(vec![fr("1"), fr("34"), fr("3"), fr("11")]);
+        match circuit.check_witness() {
+            Err(CircomError::UnsatisfiedConstraint { index: 0, ref ab, ref c }) if ab == "33" && c == "34" => {},
+            _ => panic!("unsatisfied constraint accepted"),
+        }
+
+        // Satisfies the constraint, which doesn't refer to variable 0
+        circuit.witness = Some(vec![fr("2"), fr("33"), fr("3"), fr("11")]);
+        match circuit.check_witness() {
+            Err(CircomError::InvalidConstantWire(ref value)) if value == "2" => {},
+            _ => panic!("witness with variable 0 = 2 accepted"),
+        }
+
+        circuit.witness = Some(vec![fr("1"), fr("33"), fr("3")]);
+        match circuit.check_witness() {
+            Err(CircomError::WitnessLengthMismatch { expected: 4, actual: 3 }) => {},
+            _ => panic!("short witness accepted"),
+        }
+
+        circuit.witness = None;
+        match circuit.check_witness() {
+            Err(CircomError::MissingWitness) => {},
+            _ => panic!("missing witness accepted"),
+        }
+    }
+
+    fn r1cs_error(r1cs: Vec<u8>) -> CircomError {
+        match circuit_from_r1cs::<Bn256, _>(Cursor::new(r1cs)) {
+            Err(e) => e,
+            Ok(_) => panic!("invalid .r1cs file accepted"),
+        }
+    }
+
+    /// The circuit of `multiplier_r1cs` in the JSON of older circom
+    /// versions, with `constraint` as its only constraint.
+    fn multiplier_json(constraint: &str, num_outputs: usize) -> String {
+        format!(r#"{{"constraints": [{}], "nPubInputs": 0, "nOutputs": {}, "nVars": 4}}"#, constraint, num_outputs)
+    }
+
+    const MULTIPLIER_CONSTRAINT: &str = r#"[{"2": "1"}, {"3": "1"}, {"1": "1"}]"#;
+
+    #[test]
+    fn test_invalid_circuit_json() {
+        let circuit = circuit_from_json::<Bn256, _>(multiplier_json(MULTIPLIER_CONSTRAINT, 1).as_bytes()).unwrap();
+        assert_eq!(circuit.num_inputs, 2);
+        assert_eq!(circuit.num_aux, 2);
+        assert_eq!(circuit.constraints, vec![(vec![(2, fr("1"))], vec![(3, fr("1"))], vec![(1, fr("1"))])]);
+
+        match circuit_from_json::<Bn256, _>(multiplier_json(r#"[{"a": "1"}, {"3": "1"}, {"1": "1"}]"#, 1).as_bytes()) {
+            Err(CircomError::InvalidIndex(ref index)) if index == "a" => {},
+            _ => panic!("non-numeric variable index accepted"),
+        }
+        match circuit_from_json::<Bn256, _>(multiplier_json(r#"[{"2": "1"}, {"4": "1"}, {"1": "1"}]"#, 1).as_bytes()) {
+            Err(CircomError::IndexOutOfRange { index: 4, num_variables: 4 }) => {},
+            _ => panic!("variable out of range accepted"),
+        }
+        match circuit_from_json::<Bn256, _>(multiplier_json(MULTIPLIER_CONSTRAINT, 4).as_bytes()) {
+            Err(CircomError::IndexOutOfRange { index: 4, num_variables: 4 }) => {},
+            _ => panic!("more inputs than variables accepted"),
+        }
+        match circuit_from_json::<Bn256, _>(multiplier_json(r#"[{"2": "0x1"}, {"3": "1"}, {"1": "1"}]"#, 1).as_bytes()) {
+            Err(CircomError::InvalidFieldElement(ref coeff)) if coeff == "0x1" => {},
+            _ => panic!("hexadecimal coefficient accepted"),
+        }
+        match circuit_from_json::<Bn256, _>(&multiplier_json(MULTIPLIER_CONSTRAINT, 1).as_bytes()[..20]) {
+            Err(CircomError::JsonError(_)) => {},
+            _ => panic!("truncated JSON accepted"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_witness_json() {
+        let witness = witness_from_json::<Bn256, _>(&br#"["1", "33", "3", "11"]"#[..]).unwrap();
+        assert_eq!(witness, vec![fr("1"), fr("33"), fr("3"), fr("11")]);
+
+        match witness_from_json::<Bn256, _>(&br#"["1", "33", "three", "11"]"#[..]) {
+            Err(CircomError::InvalidFieldElement(ref value)) if value == "three" => {},
+            _ => panic!("invalid witness value accepted"),
+        }
+        match witness_from_json::<Bn256, _>(&br#"["1", "33", 3, "11"]"#[..]) {
+            Err(CircomError::JsonError(_)) => {},
+            _ => panic!("witness value that isn't a string accepted"),
+        }
+
+        let mut circuit = circuit_from_json::<Bn256, _>(multiplier_json(MULTIPLIER_CONSTRAINT, 1).as_bytes()).unwrap();
+        let witness = witness_from_json::<Bn256, _>(&br#"["1", "33", "3"]"#[..]).unwrap();
+        match circuit.set_witness(witness) {
+            Err(CircomError::WitnessLengthMismatch { expected: 4, actual: 3 }) => {},
+            _ => panic!("short witness accepted"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_r1cs() {
+        let r1cs = multiplier_r1cs();
+
+        match r1cs_error(wtns(&["1"])) {
+            CircomError::InvalidFile(BinaryFileError::InvalidMagic { expected: "r1cs" }) => {},
+            e => panic!("unexpected error {}", e),
+        }
+
+        let mut version = r1cs.clone();
+        version[4] = 2;
+        match r1cs_error(version) {
+            CircomError::InvalidFile(BinaryFileError::UnsupportedVersion(2)) => {},
+            e => panic!("unexpected error {}", e),
+        }
+
+        let mut missing = vec![];
+        write_header(&mut missing, b"r1cs", 1, &[(1, r1cs_header(4, 1))]);
+        match r1cs_error(missing) {
+            CircomError::InvalidFile(BinaryFileError::MissingSection(2)) => {},
+            e => panic!("unexpected error {}", e),
+        }
+
+        // The field element size is the first word of the header section
+        let mut field_size = r1cs.clone();
+        field_size[24] = 48;
+        match r1cs_error(field_size) {
+            CircomError::InvalidFile(BinaryFileError::FieldSizeMismatch { expected: 32, actual: 48 }) => {},
+            e => panic!("unexpected error {}", e),
+        }
+
+        let mut prime = r1cs.clone();
+        prime[28] ^= 1;
+        match r1cs_error(prime) {
+            CircomError::InvalidFile(BinaryFileError::PrimeMismatch) => {},
+            e => panic!("unexpected error {}", e),
+        }
+
+        let mut out_of_range = vec![];
+        write_header(&mut out_of_range, b"r1cs", 1, &[(1, r1cs_header(3, 1)), (2, multiplier_constraints())]);
+        match r1cs_error(out_of_range) {
+            CircomError::IndexOutOfRange { index: 3, num_variables: 3 } => {},
+            e => panic!("unexpected error {}", e),
+        }
+
+        // Cut in the middle of the constraints
+        let mut truncated = vec![];
+        write_header(&mut truncated, b"r1cs", 1, &[(1, r1cs_header(4, 1)), (2, multiplier_constraints())]);
+        let len = truncated.len();
+        truncated.truncate(len - 20);
+        match r1cs_error(truncated) {
+            CircomError::IoError(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {},
+            e => panic!("unexpected error {}", e),
+        }
+
+        // Counts larger than the constraints section don't reserve memory for them
+        let mut many_constraints = vec![];
+        write_header(&mut many_constraints, b"r1cs", 1, &[(1, r1cs_header(4, u32::MAX)), (2, multiplier_constraints())]);
+        match r1cs_error(many_constraints) {
+            CircomError::IoError(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {},
+            e => panic!("unexpected error {}", e),
+        }
+
+        let mut terms = vec![];
+        terms.write_u32::<LittleEndian>(u32::MAX).unwrap();
+        let mut many_terms = vec![];
+        write_header(&mut many_terms, b"r1cs", 1, &[(1, r1cs_header(4, 1)), (2, terms)]);
+        match r1cs_error(many_terms) {
+            CircomError::IoError(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {},
+            e => panic!("unexpected error {}", e),
+        }
+    }
+
+    #[test]
+    fn test_invalid_wtns() {
+        let wtns = wtns(&["1", "33", "3", "11"]);
+
+        let mut magic = wtns.clone();
+        magic[0] = b'x';
+        assert!(witness_from_wtns::<Bn256, _>(Cursor::new(magic)).is_err());
+
+        // Not a canonical field element
+        let mut value = wtns.clone();
+        let len = value.len();
+        for byte in &mut value[len - 32..] {
+            *byte = 0xff;
+        }
+        match witness_from_wtns::<Bn256, _>(Cursor::new(value)) {
+            Err(CircomError::InvalidFieldElement(_)) => {},
+            _ => panic!("invalid field element accepted"),
+        }
+
+        let truncated = &wtns[..wtns.len() - 1];
+        match witness_from_wtns::<Bn256, _>(Cursor::new(truncated)) {
+            Err(CircomError::IoError(ref e)) if e.kind() == io::ErrorKind::UnexpectedEof => {},
+            _ => panic!("truncated .wtns file accepted"),
+        }
+
+        // The witness count is the word after the prime in the header section
+        let mut count = wtns.clone();
+        count[60..64].copy_from_slice(&[0xff; 4]);
+        match witness_from_wtns::<Bn256, _>(Cursor::new(count)) {
+            Err(CircomError::IoError(ref e)) if e.kind() == io::ErrorKind::UnexpectedEof => {},
+            _ => panic!(".wtns file with a wrong witness count accepted"),
+        }
+    }
+
+    // The generator of G2, with `Fq2` elements as `[c0, c1]`.
+    const G2_ONE: &str = r#"[
+        ["10857046999023057135944570762232829481370756359578518086990519993285655852781", "11559732032986387107991004021392285783925812861821192530917403151452391805634"],
+        ["8495653923123431417604973247489272438418190587263600148770280649306958101930", "4082367875863433681332203403145435568316851327593401208105741076214120093531"],
+        ["1", "0"]
+    ]"#;
+
+    /// A proof as snarkjs writes it, with every point projective. `pi_c`
+    /// is the generator of G1 too, with `z = 2`.
+    fn snarkjs_proof() -> String {
+        format!(r#"{{
+            "pi_a": ["1", "2", "1"],
+            "pi_b": {},
+            "pi_c": ["4", "16", "2"],
+            "protocol": "groth16",
+            "curve": "bn128"
+        }}"#, G2_ONE)
+    }
+
+    /// A verifying key as snarkjs writes it, with `vk_alpha_1` and
+    /// `vk_alphabeta_12` instead of `vk_alfa_1` and `vk_alfabeta_12`.
+    fn snarkjs_verifying_key(num_public: usize) -> String {
+        format!(r#"{{
+            "protocol": "groth16",
+            "curve": "bn128",
+            "nPublic": {},
+            "vk_alpha_1": ["1", "2", "1"],
+            "vk_beta_2": {g2},
+            "vk_gamma_2": {g2},
+            "vk_delta_2": {g2},
+            "vk_alphabeta_12": [[["1", "0"], ["0", "0"], ["0", "0"]], [["0", "0"], ["0", "0"], ["0", "0"]]],
+            "IC": [["1", "2", "1"], ["4", "16", "2"]]
+        }}"#, num_public, g2 = G2_ONE)
+    }
+
+    #[test]
+    fn test_proof_from_json() {
+        let proof = proof_from_json(snarkjs_proof().as_bytes()).unwrap();
+        assert!(proof.a == G1Affine::one());
+        assert!(proof.b == G2Affine::one());
+        assert!(proof.c == G1Affine::one());
+
+        let json = proof_to_json(&proof).unwrap();
+        assert!(proof_from_json(json.as_bytes()).unwrap() == proof);
+
+        // Affine `[x, y]` points, and the point at infinity.
+        let json = format!(r#"{{"pi_a": ["1", "2"], "pi_b": {}, "pi_c": ["0", "1", "0"]}}"#, G2_ONE);
+        let proof = proof_from_json(json.as_bytes()).unwrap();
+        assert!(proof.a == G1Affine::one());
+        assert!(proof.c.is_zero());
+    }
+
+    #[test]
+    fn test_verification_key_from_json() {
+        let vk = verification_key_from_json(snarkjs_verifying_key(1).as_bytes()).unwrap();
+        assert!(vk.alpha_g1 == G1Affine::one());
+        assert!(vk.beta_g2 == G2Affine::one());
+        assert!(vk.gamma_g2 == G2Affine::one());
+        assert!(vk.delta_g2 == G2Affine::one());
+        assert!(vk.ic == [G1Affine::one(), G1Affine::one()]);
+        assert!(vk.beta_g1.is_zero() && vk.delta_g1.is_zero());
+
+        let filename = std::env::temp_dir().join(format!("phase2-snarkjs-vk-{}.json", std::process::id()));
+        fs::write(&filename, snarkjs_verifying_key(1)).unwrap();
+        let loaded = load_verifying_key_file(filename.to_str().unwrap());
+        fs::remove_file(&filename).unwrap();
+        assert!(loaded.unwrap() == vk);
+
+        match verification_key_from_json(snarkjs_verifying_key(2).as_bytes()) {
+            Err(KeyJsonError::PublicInputsMismatch { expected: 1, actual: 2 }) => {},
+            _ => panic!("wrong nPublic accepted"),
+        }
+    }
+
+    #[test]
+    fn test_verification_key_json_roundtrip() {
+        let params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        let params = params.get_params();
+        let json = verification_key_json(params).unwrap();
+        assert!(json.contains("\"vk_alfa_1\""));
+
+        let vk = verification_key_from_json(json.as_bytes()).unwrap();
+        assert!(vk.alpha_g1 == params.vk.alpha_g1);
+        assert!(vk.beta_g2 == params.vk.beta_g2);
+        assert!(vk.gamma_g2 == params.vk.gamma_g2);
+        assert!(vk.delta_g2 == params.vk.delta_g2);
+        assert!(vk.ic == params.vk.ic);
+    }
+
+    #[test]
+    fn test_invalid_json_points() {
+        let proof = |pi_a: &str| format!(r#"{{"pi_a": {}, "pi_b": {}, "pi_c": ["1", "2"]}}"#, pi_a, G2_ONE);
+
+        match proof_from_json(proof(r#"["1", "1"]"#).as_bytes()) {
+            Err(KeyJsonError::InvalidPoint(GroupDecodingError::NotOnCurve)) => {},
+            _ => panic!("point off the curve accepted"),
+        }
+        match proof_from_json(proof(r#"["1", "2", "1", "1"]"#).as_bytes()) {
+            Err(KeyJsonError::MalformedPoint(_)) => {},
+            _ => panic!("point with 4 coordinates accepted"),
+        }
+        match proof_from_json(proof(r#"["1", "0x2"]"#).as_bytes()) {
+            Err(KeyJsonError::InvalidFieldElement(_)) => {},
+            _ => panic!("hexadecimal coordinate accepted"),
+        }
+        match proof_from_json(&b"{\"pi_a\": []}"[..]) {
+            Err(KeyJsonError::JsonError(_)) => {},
+            _ => panic!("incomplete proof accepted"),
+        }
+    }
+}
@@ -13,6 +13,18 @@ extern crate num_traits;
 extern crate cfg_if;
 extern crate itertools;
 extern crate blake2;
+extern crate hex;
+extern crate tiny_keccak;
+#[cfg(feature = "powersoftau")]
+extern crate powersoftau;
+#[cfg(test)]
+extern crate syn;
+#[cfg(all(test, feature = "powersoftau"))]
+extern crate memmap;
+#[cfg(test)]
+extern crate revm;
+#[cfg(test)]
+extern crate bn;
 
 use cfg_if::cfg_if;
 
@@ -20,8 +32,15 @@ pub mod keypair;
 pub mod keypair_assembly;
 pub mod hash_writer;
 pub mod parameters;
+pub mod phase1;
+pub mod streaming;
 pub mod utils;
 pub mod circom_circuit;
+pub mod zkey;
+pub mod template;
+pub mod solidity;
+pub mod verifier;
+pub mod calldata;
 
 cfg_if! {
     if #[cfg(feature = "wasm")] {
@@ -16,14 +16,12 @@ use byteorder::{
 };
 
 use std::{
+    error::Error,
+    fmt,
     io::{
         self,
         Read,
         Write,
-        BufReader
-    },
-    fs::{
-        File
     },
     sync::{
         Arc
@@ -45,9 +43,7 @@ use bellman_ce::pairing::{
         G1,
         G2,
         G1Affine,
-        G1Uncompressed,
         G2Affine,
-        G2Uncompressed
     }
 };
 
@@ -74,6 +70,180 @@ use super::hash_writer::*;
 use super::keypair_assembly::*;
 use super::keypair::*;
 use super::utils::*;
+use super::phase1::{Phase1Error, Phase1Radix, Phase1Source, RadixDirectory};
+
+/// Reasons why the initial parameters for a circuit can't be created.
+#[derive(Debug)]
+pub enum Phase2Error {
+    /// The circuit couldn't be synthesized.
+    Synthesis(SynthesisError),
+    /// The phase 1 parameters for the domain of the circuit couldn't be
+    /// obtained, e.g. its `phase1radix2m{exp}` file is missing.
+    Phase1(Phase1Error),
+}
+
+impl From<SynthesisError> for Phase2Error {
+    fn from(e: SynthesisError) -> Phase2Error {
+        Phase2Error::Synthesis(e)
+    }
+}
+
+impl From<Phase1Error> for Phase2Error {
+    fn from(e: Phase1Error) -> Phase2Error {
+        Phase2Error::Phase1(e)
+    }
+}
+
+impl Error for Phase2Error {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            Phase2Error::Synthesis(ref e) => Some(e),
+            Phase2Error::Phase1(ref e) => Some(e),
+        }
+    }
+}
+
+impl fmt::Display for Phase2Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Phase2Error::Synthesis(ref e) => write!(f, "unable to synthesize the circuit: {}", e),
+            Phase2Error::Phase1(ref e) => write!(f, "unable to obtain the phase 1 parameters: {}", e),
+        }
+    }
+}
+
+/// Reasons why `MPCParameters::verify` or `verify_contribution` can
+/// reject a set of parameters. Contribution indices are zero-based
+/// positions in the transcript.
+#[derive(Debug)]
+pub enum Phase2VerificationError {
+    /// The initial parameters could not be recreated from the circuit.
+    Synthesis(SynthesisError),
+    /// The phase 1 parameters the initial parameters are recreated from
+    /// could not be obtained.
+    Phase1(Phase1Error),
+    /// The parameters could not be read, see `streaming::verify_contribution`.
+    IoError(io::Error),
+    /// The new parameters must append exactly one contribution.
+    ContributionCountMismatch { expected: usize, actual: usize },
+    /// A contribution already present in the old transcript was altered.
+    ContributionChanged { index: usize },
+    /// The H query changed length.
+    HLengthMismatch { expected: usize, actual: usize },
+    /// The L query changed length.
+    LLengthMismatch { expected: usize, actual: usize },
+    /// The A query must never change.
+    AQueryChanged,
+    /// The B query in G1 must never change.
+    BG1QueryChanged,
+    /// The B query in G2 must never change.
+    BG2QueryChanged,
+    /// alpha in G1 must never change.
+    AlphaG1Changed,
+    /// beta in G1 must never change.
+    BetaG1Changed,
+    /// beta in G2 must never change.
+    BetaG2Changed,
+    /// gamma in G2 must never change.
+    GammaG2Changed,
+    /// The IC query must never change, as gamma doesn't.
+    IcChanged,
+    /// The parameters were made for a different circuit.
+    CsHashMismatch { expected: Box<[u8; 64]>, actual: Box<[u8; 64]> },
+    /// The transcript hash stored in a public key doesn't match the
+    /// hash of the transcript preceding it.
+    TranscriptMismatch { index: usize, expected: Box<[u8; 64]>, actual: Box<[u8; 64]> },
+    /// The contributor didn't prove knowledge of their delta.
+    InvalidSignatureOfKnowledge { index: usize },
+    /// `delta_after` isn't the previous delta multiplied by the
+    /// contributor's delta.
+    InconsistentDeltaChange { index: usize },
+    /// delta in G1 doesn't match the last contribution.
+    DeltaG1Mismatch,
+    /// delta in G2 doesn't have the same ratio as delta in G1.
+    DeltaG2Mismatch,
+    /// The H query wasn't rescaled by delta^-1.
+    HQueryNotRescaled,
+    /// The L query wasn't rescaled by delta^-1.
+    LQueryNotRescaled,
+}
+
+impl From<SynthesisError> for Phase2VerificationError {
+    fn from(e: SynthesisError) -> Phase2VerificationError {
+        Phase2VerificationError::Synthesis(e)
+    }
+}
+
+impl From<Phase1Error> for Phase2VerificationError {
+    fn from(e: Phase1Error) -> Phase2VerificationError {
+        Phase2VerificationError::Phase1(e)
+    }
+}
+
+impl From<Phase2Error> for Phase2VerificationError {
+    fn from(e: Phase2Error) -> Phase2VerificationError {
+        match e {
+            Phase2Error::Synthesis(e) => Phase2VerificationError::Synthesis(e),
+            Phase2Error::Phase1(e) => Phase2VerificationError::Phase1(e),
+        }
+    }
+}
+
+impl From<io::Error> for Phase2VerificationError {
+    fn from(e: io::Error) -> Phase2VerificationError {
+        Phase2VerificationError::IoError(e)
+    }
+}
+
+impl Error for Phase2VerificationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            Phase2VerificationError::Synthesis(ref e) => Some(e),
+            Phase2VerificationError::Phase1(ref e) => Some(e),
+            Phase2VerificationError::IoError(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Phase2VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Phase2VerificationError::*;
+
+        match *self {
+            Synthesis(ref e) => write!(f, "unable to create initial parameters: {}", e),
+            Phase1(ref e) => write!(f, "unable to create initial parameters: {}", e),
+            IoError(ref e) => write!(f, "unable to read parameters: {}", e),
+            ContributionCountMismatch { expected, actual } =>
+                write!(f, "expected {} contributions, found {}", expected, actual),
+            ContributionChanged { index } => write!(f, "contribution #{} was modified", index),
+            HLengthMismatch { expected, actual } =>
+                write!(f, "H query length changed from {} to {}", expected, actual),
+            LLengthMismatch { expected, actual } =>
+                write!(f, "L query length changed from {} to {}", expected, actual),
+            AQueryChanged => write!(f, "A query changed"),
+            BG1QueryChanged => write!(f, "B query in G1 changed"),
+            BG2QueryChanged => write!(f, "B query in G2 changed"),
+            AlphaG1Changed => write!(f, "alpha in G1 changed"),
+            BetaG1Changed => write!(f, "beta in G1 changed"),
+            BetaG2Changed => write!(f, "beta in G2 changed"),
+            GammaG2Changed => write!(f, "gamma in G2 changed"),
+            IcChanged => write!(f, "IC query changed"),
+            CsHashMismatch { ref expected, ref actual } =>
+                write!(f, "cs_hash mismatch: expected 0x{}, found 0x{}", hex::encode(&expected[..]), hex::encode(&actual[..])),
+            TranscriptMismatch { index, ref expected, ref actual } =>
+                write!(f, "transcript of contribution #{} mismatch: expected 0x{}, found 0x{}", index, hex::encode(&expected[..]), hex::encode(&actual[..])),
+            InvalidSignatureOfKnowledge { index } =>
+                write!(f, "invalid signature of knowledge of delta in contribution #{}", index),
+            InconsistentDeltaChange { index } =>
+                write!(f, "contribution #{} doesn't update delta consistently", index),
+            DeltaG1Mismatch => write!(f, "delta in G1 doesn't match the last contribution"),
+            DeltaG2Mismatch => write!(f, "delta in G2 isn't consistent with delta in G1"),
+            HQueryNotRescaled => write!(f, "H query wasn't updated with delta^-1"),
+            LQueryNotRescaled => write!(f, "L query wasn't updated with delta^-1"),
+        }
+    }
+}
 
 /// MPC parameters are just like bellman `Parameters` except, when serialized,
 /// they contain a transcript of contributions at the end, which can be verified.
@@ -96,112 +266,41 @@ impl MPCParameters {
     /// Create new Groth16 parameters (compatible with bellman) for a
     /// given circuit. The resulting parameters are unsafe to use
     /// until there are contributions (see `contribute()`).
+    ///
+    /// A missing or truncated `phase1radix2m{exp}` file is reported as
+    /// `Phase2Error::Phase1` with `Phase1Error::MissingRadixFile` or
+    /// `Phase1Error::InvalidRadixFileSize`.
     pub fn new<C>(
         circuit: C,
         should_filter_points_at_infinity: bool,
         radix_directory: &String,
-    ) -> Result<MPCParameters, SynthesisError>
+    ) -> Result<MPCParameters, Phase2Error>
         where C: Circuit<Bn256>
     {
-        let mut assembly = KeypairAssembly {
-            num_inputs: 0,
-            num_aux: 0,
-            num_constraints: 0,
-            at_inputs: vec![],
-            bt_inputs: vec![],
-            ct_inputs: vec![],
-            at_aux: vec![],
-            bt_aux: vec![],
-            ct_aux: vec![]
-        };
-
-        // Allocate the "one" input variable
-        assembly.alloc_input(|| "", || Ok(Fr::one()))?;
-
-        // Synthesize the circuit.
-        circuit.synthesize(&mut assembly)?;
-
-        // Input constraints to ensure full density of IC query
-        // x * 0 = 0
-        for i in 0..assembly.num_inputs {
-            assembly.enforce(|| "",
-                             |lc| lc + Variable::new_unchecked(Index::Input(i)),
-                             |lc| lc,
-                             |lc| lc,
-            );
-        }
-
-        // Compute the size of our evaluation domain
-        let mut m = 1;
-        let mut exp = 0;
-        while m < assembly.num_constraints {
-            m *= 2;
-            exp += 1;
-
-            // Powers of Tau ceremony can't support more than 2^28
-            if exp > 28 {
-                return Err(SynthesisError::PolynomialDegreeTooLarge)
-            }
-        }
-
-        // Try to load "radix_directory/phase1radix2m{}"
-        let f = match File::open(format!("{}/phase1radix2m{}", radix_directory, exp)) {
-            Ok(f) => f,
-            Err(e) => {
-                panic!("Couldn't load phase1radix2m{}: {:?}", exp, e);
-            }
-        };
-        let f = &mut BufReader::with_capacity(1024 * 1024, f);
-
-        let read_g1 = |reader: &mut BufReader<File>| -> io::Result<G1Affine> {
-            let mut repr = G1Uncompressed::empty();
-            reader.read_exact(repr.as_mut())?;
-
-            repr.into_affine_unchecked()
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-                .and_then(|e| if e.is_zero() {
-                    Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-                } else {
-                    Ok(e)
-                })
-        };
-
-        let read_g2 = |reader: &mut BufReader<File>| -> io::Result<G2Affine> {
-            let mut repr = G2Uncompressed::empty();
-            reader.read_exact(repr.as_mut())?;
-
-            repr.into_affine_unchecked()
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-                .and_then(|e| if e.is_zero() {
-                    Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-                } else {
-                    Ok(e)
-                })
-        };
-
-        let alpha = read_g1(f)?;
-        let beta_g1 = read_g1(f)?;
-        let beta_g2 = read_g2(f)?;
-
-        let mut coeffs_g1 = Vec::with_capacity(m);
-        for _ in 0..m {
-            coeffs_g1.push(read_g1(f)?);
-        }
-
-        let mut coeffs_g2 = Vec::with_capacity(m);
-        for _ in 0..m {
-            coeffs_g2.push(read_g2(f)?);
-        }
+        MPCParameters::new_with_source(circuit, should_filter_points_at_infinity, &mut RadixDirectory::new(radix_directory))
+    }
 
-        let mut alpha_coeffs_g1 = Vec::with_capacity(m);
-        for _ in 0..m {
-            alpha_coeffs_g1.push(read_g1(f)?);
-        }
+    /// Same as `new`, taking the phase 1 parameters from `phase1`, which
+    /// is asked only for the domain size the circuit needs.
+    pub fn new_with_source<C, S>(
+        circuit: C,
+        should_filter_points_at_infinity: bool,
+        phase1: &mut S,
+    ) -> Result<MPCParameters, Phase2Error>
+        where C: Circuit<Bn256>, S: Phase1Source + ?Sized
+    {
+        let (assembly, exp) = assemble_keypair(circuit)?;
 
-        let mut beta_coeffs_g1 = Vec::with_capacity(m);
-        for _ in 0..m {
-            beta_coeffs_g1.push(read_g1(f)?);
-        }
+        let Phase1Radix {
+            alpha_g1: alpha,
+            beta_g1,
+            beta_g2,
+            coeffs_g1,
+            coeffs_g2,
+            alpha_coeffs_g1,
+            beta_coeffs_g1,
+            h,
+        } = phase1.radix(exp)?;
 
         // These are `Arc` so that later it'll be easier
         // to use multiexp during QAP evaluation (which
@@ -211,11 +310,6 @@ impl MPCParameters {
         let alpha_coeffs_g1 = Arc::new(alpha_coeffs_g1);
         let beta_coeffs_g1 = Arc::new(beta_coeffs_g1);
 
-        let mut h = Vec::with_capacity(m-1);
-        for _ in 0..m-1 {
-            h.push(read_g1(f)?);
-        }
-
         let mut ic = vec![G1::zero(); assembly.num_inputs];
         let mut l = vec![G1::zero(); assembly.num_aux];
         let mut a_g1 = vec![G1::zero(); assembly.num_inputs + assembly.num_aux];
@@ -342,7 +436,7 @@ impl MPCParameters {
         // the L query is always fully dense.
         for e in l.iter() {
             if e.is_zero() {
-                return Err(SynthesisError::UnconstrainedVariable);
+                return Err(SynthesisError::UnconstrainedVariable.into());
             }
         }
 
@@ -402,6 +496,16 @@ impl MPCParameters {
         &self.params
     }
 
+    /// Get the hash of the circuit the parameters were created for
+    pub fn get_cs_hash(&self) -> &[u8; 64] {
+        &self.cs_hash
+    }
+
+    /// Get the public keys of the contributions made so far
+    pub fn get_contributions(&self) -> &[PublicKey] {
+        &self.contributions
+    }
+
     /// Contributes some randomness to the parameters. Only one
     /// contributor needs to be honest for the parameters to be
     /// secure.
@@ -420,81 +524,6 @@ impl MPCParameters {
         // Generate a keypair
         let (pubkey, privkey) = keypair(rng, self);
 
-        #[cfg(not(feature = "wasm"))]
-        fn batch_exp<C: CurveAffine>(bases: &mut [C], coeff: C::Scalar, progress_update_interval: &u32, total_exps: &u32) {
-            let coeff = coeff.into_repr();
-
-            let mut projective = vec![C::Projective::zero(); bases.len()];
-            let cpus = num_cpus::get();
-            let chunk_size = if bases.len() < cpus {
-                1
-            } else {
-                bases.len() / cpus
-            };
-
-            // Perform wNAF over multiple cores, placing results into `projective`.
-            crossbeam::scope(|scope| {
-                for (bases, projective) in bases.chunks_mut(chunk_size)
-                    .zip(projective.chunks_mut(chunk_size))
-                    {
-                        scope.spawn(move |_| {
-                            let mut wnaf = Wnaf::new();
-                            let mut count = 0;
-                            for (base, projective) in bases.iter_mut()
-                                .zip(projective.iter_mut())
-                                {
-                                    *projective = wnaf.base(base.into_projective(), 1).scalar(coeff);
-                                    count = count + 1;
-                                    if *progress_update_interval > 0 && count % *progress_update_interval == 0 {
-                                        println!("progress {} {}", *progress_update_interval, *total_exps)
-                                    }
-                                }
-                        });
-                    }
-            }).unwrap();
-
-            // Perform batch normalization
-            crossbeam::scope(|scope| {
-                for projective in projective.chunks_mut(chunk_size)
-                    {
-                        scope.spawn(move |_| {
-                            C::Projective::batch_normalization(projective);
-                        });
-                    }
-            }).unwrap();
-
-            // Turn it all back into affine points
-            for (projective, affine) in projective.iter().zip(bases.iter_mut()) {
-                *affine = projective.into_affine();
-            }
-        }
-
-        #[cfg(feature = "wasm")]
-        fn batch_exp<C: CurveAffine>(bases: &mut [C], coeff: C::Scalar, progress_update_interval: &u32, total_exps: &u32) {
-            let coeff = coeff.into_repr();
-
-            let mut projective = vec![C::Projective::zero(); bases.len()];
-
-            // Perform wNAF, placing results into `projective`.
-            let mut wnaf = Wnaf::new();
-            let mut count = 0;
-            for (base, projective) in bases.iter_mut().zip(projective.iter_mut()) {
-                *projective = wnaf.base(base.into_projective(), 1).scalar(coeff);
-                count = count + 1;
-                if *progress_update_interval > 0 && count % *progress_update_interval == 0 {
-                    println!("progress {} {}", *progress_update_interval, *total_exps)
-                }
-            }
-
-            // Perform batch normalization
-            C::Projective::batch_normalization(&mut projective);
-
-            // Turn it all back into affine points
-            for (projective, affine) in projective.iter().zip(bases.iter_mut()) {
-                *affine = projective.into_affine();
-            }
-        }
-
         let delta_inv = privkey.delta.inverse().expect("nonzero");
         let mut l = (&self.params.l[..]).to_vec();
         let mut h = (&self.params.h[..]).to_vec();
@@ -531,105 +560,29 @@ impl MPCParameters {
         circuit: C,
         should_filter_points_at_infinity: bool,
         radix_directory: &String,
-    ) -> Result<Vec<[u8; 64]>, ()>
+    ) -> Result<Vec<[u8; 64]>, Phase2VerificationError>
     {
-        let initial_params = MPCParameters::new(circuit, should_filter_points_at_infinity, radix_directory).map_err(|_| ())?;
-
-        // H/L will change, but should have same length
-        if initial_params.params.h.len() != self.params.h.len() {
-            return Err(());
-        }
-        if initial_params.params.l.len() != self.params.l.len() {
-            return Err(());
-        }
-
-        // A/B_G1/B_G2 doesn't change at all
-        if initial_params.params.a != self.params.a {
-            return Err(());
-        }
-        if initial_params.params.b_g1 != self.params.b_g1 {
-            return Err(());
-        }
-        if initial_params.params.b_g2 != self.params.b_g2 {
-            return Err(());
-        }
-
-        // alpha/beta/gamma don't change
-        if initial_params.params.vk.alpha_g1 != self.params.vk.alpha_g1 {
-            return Err(());
-        }
-        if initial_params.params.vk.beta_g1 != self.params.vk.beta_g1 {
-            return Err(());
-        }
-        if initial_params.params.vk.beta_g2 != self.params.vk.beta_g2 {
-            return Err(());
-        }
-        if initial_params.params.vk.gamma_g2 != self.params.vk.gamma_g2 {
-            return Err(());
-        }
-
-        // IC shouldn't change, as gamma doesn't change
-        if initial_params.params.vk.ic != self.params.vk.ic {
-            return Err(());
-        }
-
-        // cs_hash should be the same
-        if &initial_params.cs_hash[..] != &self.cs_hash[..] {
-            return Err(());
-        }
-
-        let sink = io::sink();
-        let mut sink = HashWriter::new(sink);
-        sink.write_all(&initial_params.cs_hash[..]).unwrap();
-
-        let mut current_delta = G1Affine::one();
-        let mut result = vec![];
-
-        for pubkey in &self.contributions {
-            let mut our_sink = sink.clone();
-            our_sink.write_all(pubkey.s.into_uncompressed().as_ref()).unwrap();
-            our_sink.write_all(pubkey.s_delta.into_uncompressed().as_ref()).unwrap();
-
-            pubkey.write(&mut sink).unwrap();
-
-            let h = our_sink.into_hash();
-
-            // The transcript must be consistent
-            if &pubkey.transcript[..] != h.as_ref() {
-                return Err(());
-            }
-
-            let r = hash_to_g2(h.as_ref()).into_affine();
-
-            // Check the signature of knowledge
-            if !same_ratio((r, pubkey.r_delta), (pubkey.s, pubkey.s_delta)) {
-                return Err(());
-            }
+        self.verify_with_source(circuit, should_filter_points_at_infinity, &mut RadixDirectory::new(radix_directory))
+    }
 
-            // Check the change from the old delta is consistent
-            if !same_ratio(
-                (current_delta, pubkey.delta_after),
-                (r, pubkey.r_delta)
-            ) {
-                return Err(());
-            }
+    /// Same as `verify`, taking the phase 1 parameters from `phase1`.
+    pub fn verify_with_source<C, S>(
+        &self,
+        circuit: C,
+        should_filter_points_at_infinity: bool,
+        phase1: &mut S,
+    ) -> Result<Vec<[u8; 64]>, Phase2VerificationError>
+        where C: Circuit<Bn256>, S: Phase1Source + ?Sized
+    {
+        let initial_params = MPCParameters::new_with_source(circuit, should_filter_points_at_infinity, phase1)?;
 
-            current_delta = pubkey.delta_after;
+        check_unchanged_parts(&initial_params, self)?;
 
-            {
-                let sink = io::sink();
-                let mut sink = HashWriter::new(sink);
-                pubkey.write(&mut sink).unwrap();
-                let h = sink.into_hash();
-                let mut response = [0u8; 64];
-                response.copy_from_slice(h.as_ref());
-                result.push(response);
-            }
-        }
+        let (result, current_delta) = verify_transcript(&initial_params.cs_hash, &self.contributions)?;
 
         // Current parameters should have consistent delta in G1
         if current_delta != self.params.vk.delta_g1 {
-            return Err(());
+            return Err(Phase2VerificationError::DeltaG1Mismatch);
         }
 
         // Current parameters should have consistent delta in G2
@@ -637,7 +590,7 @@ impl MPCParameters {
             (G1Affine::one(), current_delta),
             (G2Affine::one(), self.params.vk.delta_g2)
         ) {
-            return Err(());
+            return Err(Phase2VerificationError::DeltaG2Mismatch);
         }
 
         // H and L queries should be updated with delta^-1
@@ -645,14 +598,14 @@ impl MPCParameters {
             merge_pairs(&initial_params.params.h, &self.params.h),
             (self.params.vk.delta_g2, G2Affine::one()) // reversed for inverse
         ) {
-            return Err(());
+            return Err(Phase2VerificationError::HQueryNotRescaled);
         }
 
         if !same_ratio(
             merge_pairs(&initial_params.params.l, &self.params.l),
             (self.params.vk.delta_g2, G2Affine::one()) // reversed for inverse
         ) {
-            return Err(());
+            return Err(Phase2VerificationError::LQueryNotRescaled);
         }
 
         Ok(result)
@@ -721,75 +674,273 @@ pub fn contains_contribution(
     return false
 }
 
-/// Verify a contribution, given the old parameters and
-/// the new parameters. Returns the hash of the contribution.
-pub fn verify_contribution(
-    before: &MPCParameters,
-    after: &MPCParameters
-) -> Result<[u8; 64], ()>
+/// Synthesizes the circuit with the input constraints bellman adds to it,
+/// and returns it with the exponent of the size of its evaluation domain.
+pub(crate) fn assemble_keypair<C: Circuit<Bn256>>(
+    circuit: C
+) -> Result<(KeypairAssembly<Bn256>, u32), Phase2Error>
 {
-    // Transformation involves a single new object
-    if after.contributions.len() != (before.contributions.len() + 1) {
-        return Err(());
+    let mut assembly = KeypairAssembly {
+        num_inputs: 0,
+        num_aux: 0,
+        num_constraints: 0,
+        at_inputs: vec![],
+        bt_inputs: vec![],
+        ct_inputs: vec![],
+        at_aux: vec![],
+        bt_aux: vec![],
+        ct_aux: vec![]
+    };
+
+    // Allocate the "one" input variable
+    assembly.alloc_input(|| "", || Ok(Fr::one()))?;
+
+    // Synthesize the circuit.
+    circuit.synthesize(&mut assembly)?;
+
+    // Input constraints to ensure full density of IC query
+    // x * 0 = 0
+    for i in 0..assembly.num_inputs {
+        assembly.enforce(|| "",
+                         |lc| lc + Variable::new_unchecked(Index::Input(i)),
+                         |lc| lc,
+                         |lc| lc,
+        );
     }
 
-    // None of the previous transformations should change
-    if &before.contributions[..] != &after.contributions[0..before.contributions.len()] {
-        return Err(());
+    // Compute the size of our evaluation domain
+    let mut m = 1;
+    let mut exp = 0;
+    while m < assembly.num_constraints {
+        m *= 2;
+        exp += 1;
+
+        // Powers of Tau ceremony can't support more than 2^28
+        if exp > 28 {
+            return Err(SynthesisError::PolynomialDegreeTooLarge.into())
+        }
+    }
+
+    Ok((assembly, exp))
+}
+
+/// Checks the chain of public keys in `contributions`, starting from
+/// delta in G1 being the generator. Returns the contribution hashes and
+/// the delta in G1 after the last contribution.
+pub(crate) fn verify_transcript(
+    cs_hash: &[u8; 64],
+    contributions: &[PublicKey]
+) -> Result<(Vec<[u8; 64]>, G1Affine), Phase2VerificationError>
+{
+    let sink = io::sink();
+    let mut sink = HashWriter::new(sink);
+    sink.write_all(&cs_hash[..]).unwrap();
+
+    let mut current_delta = G1Affine::one();
+    let mut result = vec![];
+
+    for (index, pubkey) in contributions.iter().enumerate() {
+        let mut our_sink = sink.clone();
+        our_sink.write_all(pubkey.s.into_uncompressed().as_ref()).unwrap();
+        our_sink.write_all(pubkey.s_delta.into_uncompressed().as_ref()).unwrap();
+
+        pubkey.write(&mut sink).unwrap();
+
+        let h = our_sink.into_hash();
+
+        // The transcript must be consistent
+        if &pubkey.transcript[..] != h.as_ref() {
+            return Err(Phase2VerificationError::TranscriptMismatch {
+                index,
+                expected: Box::new(h),
+                actual: Box::new(pubkey.transcript),
+            });
+        }
+
+        let r = hash_to_g2(h.as_ref()).into_affine();
+
+        // Check the signature of knowledge
+        if !same_ratio((r, pubkey.r_delta), (pubkey.s, pubkey.s_delta)) {
+            return Err(Phase2VerificationError::InvalidSignatureOfKnowledge { index });
+        }
+
+        // Check the change from the old delta is consistent
+        if !same_ratio(
+            (current_delta, pubkey.delta_after),
+            (r, pubkey.r_delta)
+        ) {
+            return Err(Phase2VerificationError::InconsistentDeltaChange { index });
+        }
+
+        current_delta = pubkey.delta_after;
+
+        {
+            let sink = io::sink();
+            let mut sink = HashWriter::new(sink);
+            pubkey.write(&mut sink).unwrap();
+            let h = sink.into_hash();
+            let mut response = [0u8; 64];
+            response.copy_from_slice(h.as_ref());
+            result.push(response);
+        }
     }
 
+    Ok((result, current_delta))
+}
+
+/// Checks the parts of the parameters which contributions must
+/// not touch: everything except delta and the H/L queries, whose
+/// lengths must still be preserved.
+fn check_unchanged_parts(
+    before: &MPCParameters,
+    after: &MPCParameters
+) -> Result<(), Phase2VerificationError>
+{
     // H/L will change, but should have same length
     if before.params.h.len() != after.params.h.len() {
-        return Err(());
+        return Err(Phase2VerificationError::HLengthMismatch {
+            expected: before.params.h.len(),
+            actual: after.params.h.len(),
+        });
     }
     if before.params.l.len() != after.params.l.len() {
-        return Err(());
+        return Err(Phase2VerificationError::LLengthMismatch {
+            expected: before.params.l.len(),
+            actual: after.params.l.len(),
+        });
     }
 
     // A/B_G1/B_G2 doesn't change at all
     if before.params.a != after.params.a {
-        return Err(());
+        return Err(Phase2VerificationError::AQueryChanged);
     }
     if before.params.b_g1 != after.params.b_g1 {
-        return Err(());
+        return Err(Phase2VerificationError::BG1QueryChanged);
     }
     if before.params.b_g2 != after.params.b_g2 {
-        return Err(());
+        return Err(Phase2VerificationError::BG2QueryChanged);
     }
 
+    check_unchanged_keys(&before.params.vk, &after.params.vk, &before.cs_hash, &after.cs_hash)
+}
+
+/// Checks the parts of the verifying key and the circuit hash that no
+/// contribution may change.
+pub(crate) fn check_unchanged_keys(
+    before_vk: &VerifyingKey<Bn256>,
+    after_vk: &VerifyingKey<Bn256>,
+    before_cs_hash: &[u8; 64],
+    after_cs_hash: &[u8; 64]
+) -> Result<(), Phase2VerificationError>
+{
     // alpha/beta/gamma don't change
-    if before.params.vk.alpha_g1 != after.params.vk.alpha_g1 {
-        return Err(());
+    if before_vk.alpha_g1 != after_vk.alpha_g1 {
+        return Err(Phase2VerificationError::AlphaG1Changed);
     }
-    if before.params.vk.beta_g1 != after.params.vk.beta_g1 {
-        return Err(());
+    if before_vk.beta_g1 != after_vk.beta_g1 {
+        return Err(Phase2VerificationError::BetaG1Changed);
     }
-    if before.params.vk.beta_g2 != after.params.vk.beta_g2 {
-        return Err(());
+    if before_vk.beta_g2 != after_vk.beta_g2 {
+        return Err(Phase2VerificationError::BetaG2Changed);
     }
-    if before.params.vk.gamma_g2 != after.params.vk.gamma_g2 {
-        return Err(());
+    if before_vk.gamma_g2 != after_vk.gamma_g2 {
+        return Err(Phase2VerificationError::GammaG2Changed);
     }
 
     // IC shouldn't change, as gamma doesn't change
-    if before.params.vk.ic != after.params.vk.ic {
-        return Err(());
+    if before_vk.ic != after_vk.ic {
+        return Err(Phase2VerificationError::IcChanged);
     }
 
     // cs_hash should be the same
-    if &before.cs_hash[..] != &after.cs_hash[..] {
-        return Err(());
+    if before_cs_hash[..] != after_cs_hash[..] {
+        return Err(Phase2VerificationError::CsHashMismatch {
+            expected: Box::new(*before_cs_hash),
+            actual: Box::new(*after_cs_hash),
+        });
     }
 
+    Ok(())
+}
+
+/// Verify a contribution, given the old parameters and
+/// the new parameters. Returns the hash of the contribution.
+pub fn verify_contribution(
+    before: &MPCParameters,
+    after: &MPCParameters
+) -> Result<[u8; 64], Phase2VerificationError>
+{
+    check_transcript_extension(&before.contributions, &after.contributions)?;
+
+    check_unchanged_parts(before, after)?;
+
+    let pubkey = check_new_contribution(&before.cs_hash, &before.contributions, &after.contributions, &before.params.vk, &after.params.vk)?;
+
+    // H and L queries should be updated with delta^-1
+    if !same_ratio(
+        merge_pairs(&before.params.h, &after.params.h),
+        (after.params.vk.delta_g2, before.params.vk.delta_g2) // reversed for inverse
+    ) {
+        return Err(Phase2VerificationError::HQueryNotRescaled);
+    }
+
+    if !same_ratio(
+        merge_pairs(&before.params.l, &after.params.l),
+        (after.params.vk.delta_g2, before.params.vk.delta_g2) // reversed for inverse
+    ) {
+        return Err(Phase2VerificationError::LQueryNotRescaled);
+    }
+
+    Ok(contribution_hash(pubkey))
+}
+
+/// Checks that `after` is `before` with exactly one more contribution.
+pub(crate) fn check_transcript_extension(
+    before: &[PublicKey],
+    after: &[PublicKey]
+) -> Result<(), Phase2VerificationError>
+{
+    let index = before.len();
+
+    // Transformation involves a single new object
+    if after.len() != (index + 1) {
+        return Err(Phase2VerificationError::ContributionCountMismatch {
+            expected: index + 1,
+            actual: after.len(),
+        });
+    }
+
+    // None of the previous transformations should change
+    for (i, (old, new)) in before.iter().zip(after.iter()).enumerate() {
+        if old != new {
+            return Err(Phase2VerificationError::ContributionChanged { index: i });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the last public key of `after`, which must extend `before`,
+/// against the deltas of the verifying keys and returns it.
+pub(crate) fn check_new_contribution<'a>(
+    cs_hash: &[u8; 64],
+    before: &[PublicKey],
+    after: &'a [PublicKey],
+    before_vk: &VerifyingKey<Bn256>,
+    after_vk: &VerifyingKey<Bn256>
+) -> Result<&'a PublicKey, Phase2VerificationError>
+{
+    let index = before.len();
+
     let sink = io::sink();
     let mut sink = HashWriter::new(sink);
-    sink.write_all(&before.cs_hash[..]).unwrap();
+    sink.write_all(&cs_hash[..]).unwrap();
 
-    for pubkey in &before.contributions {
+    for pubkey in before {
         pubkey.write(&mut sink).unwrap();
     }
 
-    let pubkey = after.contributions.last().unwrap();
+    let pubkey = after.last().unwrap();
     sink.write_all(pubkey.s.into_uncompressed().as_ref()).unwrap();
     sink.write_all(pubkey.s_delta.into_uncompressed().as_ref()).unwrap();
 
@@ -797,52 +948,46 @@ pub fn verify_contribution(
 
     // The transcript must be consistent
     if &pubkey.transcript[..] != h.as_ref() {
-        return Err(());
+        return Err(Phase2VerificationError::TranscriptMismatch {
+            index,
+            expected: Box::new(h),
+            actual: Box::new(pubkey.transcript),
+        });
     }
 
     let r = hash_to_g2(h.as_ref()).into_affine();
 
     // Check the signature of knowledge
     if !same_ratio((r, pubkey.r_delta), (pubkey.s, pubkey.s_delta)) {
-        return Err(());
+        return Err(Phase2VerificationError::InvalidSignatureOfKnowledge { index });
     }
 
     // Check the change from the old delta is consistent
     if !same_ratio(
-        (before.params.vk.delta_g1, pubkey.delta_after),
+        (before_vk.delta_g1, pubkey.delta_after),
         (r, pubkey.r_delta)
     ) {
-        return Err(());
+        return Err(Phase2VerificationError::InconsistentDeltaChange { index });
     }
 
     // Current parameters should have consistent delta in G1
-    if pubkey.delta_after != after.params.vk.delta_g1 {
-        return Err(());
+    if pubkey.delta_after != after_vk.delta_g1 {
+        return Err(Phase2VerificationError::DeltaG1Mismatch);
     }
 
     // Current parameters should have consistent delta in G2
     if !same_ratio(
         (G1Affine::one(), pubkey.delta_after),
-        (G2Affine::one(), after.params.vk.delta_g2)
+        (G2Affine::one(), after_vk.delta_g2)
     ) {
-        return Err(());
+        return Err(Phase2VerificationError::DeltaG2Mismatch);
     }
 
-    // H and L queries should be updated with delta^-1
-    if !same_ratio(
-        merge_pairs(&before.params.h, &after.params.h),
-        (after.params.vk.delta_g2, before.params.vk.delta_g2) // reversed for inverse
-    ) {
-        return Err(());
-    }
-
-    if !same_ratio(
-        merge_pairs(&before.params.l, &after.params.l),
-        (after.params.vk.delta_g2, before.params.vk.delta_g2) // reversed for inverse
-    ) {
-        return Err(());
-    }
+    Ok(pubkey)
+}
 
+/// Hash of a contribution's public key, as returned by `contribute`.
+pub fn contribution_hash(pubkey: &PublicKey) -> [u8; 64] {
     let sink = io::sink();
     let mut sink = HashWriter::new(sink);
     pubkey.write(&mut sink).unwrap();
@@ -850,9 +995,85 @@ pub fn verify_contribution(
     let mut response = [0u8; 64];
     response.copy_from_slice(h.as_ref());
 
-    Ok(response)
+    response
+}
+
+
+/// Multiplies all of the `bases` by `coeff`.
+#[cfg(not(feature = "wasm"))]
+pub(crate) fn batch_exp<C: CurveAffine>(bases: &mut [C], coeff: C::Scalar, progress_update_interval: &u32, total_exps: &u32) {
+    let coeff = coeff.into_repr();
+
+    let mut projective = vec![C::Projective::zero(); bases.len()];
+    let cpus = num_cpus::get();
+    let chunk_size = if bases.len() < cpus {
+        1
+    } else {
+        bases.len() / cpus
+    };
+
+    // Perform wNAF over multiple cores, placing results into `projective`.
+    crossbeam::scope(|scope| {
+        for (bases, projective) in bases.chunks_mut(chunk_size)
+            .zip(projective.chunks_mut(chunk_size))
+            {
+                scope.spawn(move |_| {
+                    let mut wnaf = Wnaf::new();
+                    let mut count = 0;
+                    for (base, projective) in bases.iter_mut()
+                        .zip(projective.iter_mut())
+                        {
+                            *projective = wnaf.base(base.into_projective(), 1).scalar(coeff);
+                            count = count + 1;
+                            if *progress_update_interval > 0 && count % *progress_update_interval == 0 {
+                                println!("progress {} {}", *progress_update_interval, *total_exps)
+                            }
+                        }
+                });
+            }
+    }).unwrap();
+
+    // Perform batch normalization
+    crossbeam::scope(|scope| {
+        for projective in projective.chunks_mut(chunk_size)
+            {
+                scope.spawn(move |_| {
+                    C::Projective::batch_normalization(projective);
+                });
+            }
+    }).unwrap();
+
+    // Turn it all back into affine points
+    for (projective, affine) in projective.iter().zip(bases.iter_mut()) {
+        *affine = projective.into_affine();
+    }
 }
 
+#[cfg(feature = "wasm")]
+pub(crate) fn batch_exp<C: CurveAffine>(bases: &mut [C], coeff: C::Scalar, progress_update_interval: &u32, total_exps: &u32) {
+    let coeff = coeff.into_repr();
+
+    let mut projective = vec![C::Projective::zero(); bases.len()];
+
+    // Perform wNAF, placing results into `projective`.
+    let mut wnaf = Wnaf::new();
+    let mut count = 0;
+    for (base, projective) in bases.iter_mut().zip(projective.iter_mut()) {
+        *projective = wnaf.base(base.into_projective(), 1).scalar(coeff);
+        count = count + 1;
+        if *progress_update_interval > 0 && count % *progress_update_interval == 0 {
+            println!("progress {} {}", *progress_update_interval, *total_exps)
+        }
+    }
+
+    // Perform batch normalization
+    C::Projective::batch_normalization(&mut projective);
+
+    // Turn it all back into affine points
+    for (projective, affine) in projective.iter().zip(bases.iter_mut()) {
+        *affine = projective.into_affine();
+    }
+}
 
 /// Compute a keypair, given the current parameters. Keypairs
 /// cannot be reused for multiple contributions or contributions
@@ -861,6 +1082,18 @@ pub fn keypair<R: Rng>(
     rng: &mut R,
     current: &MPCParameters,
 ) -> (PublicKey, PrivateKey)
+{
+    keypair_for_transcript(rng, &current.cs_hash, &current.contributions, &current.params.vk.delta_g1)
+}
+
+/// Same as `keypair`, given only the parts of the current parameters
+/// the keypair depends on.
+pub(crate) fn keypair_for_transcript<R: Rng>(
+    rng: &mut R,
+    cs_hash: &[u8; 64],
+    contributions: &[PublicKey],
+    delta_g1: &G1Affine,
+) -> (PublicKey, PrivateKey)
 {
     // Sample random delta
     let delta: Fr = rng.gen();
@@ -874,8 +1107,8 @@ pub fn keypair<R: Rng>(
         let sink = io::sink();
         let mut sink = HashWriter::new(sink);
 
-        sink.write_all(&current.cs_hash[..]).unwrap();
-        for pubkey in &current.contributions {
+        sink.write_all(&cs_hash[..]).unwrap();
+        for pubkey in contributions {
             pubkey.write(&mut sink).unwrap();
         }
         sink.write_all(s.into_uncompressed().as_ref()).unwrap();
@@ -895,7 +1128,7 @@ pub fn keypair<R: Rng>(
 
     (
         PublicKey {
-            delta_after: current.params.vk.delta_g1.mul(delta).into_affine(),
+            delta_after: delta_g1.mul(delta).into_affine(),
             s: s,
             s_delta: s_delta,
             r_delta: r_delta,
@@ -906,3 +1139,186 @@ pub fn keypair<R: Rng>(
         }
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use circom_circuit::tests::multiplier_circuit;
+    use phase1::tests::TestPowers;
+    use rand::XorShiftRng;
+
+    #[test]
+    fn test_contribute_and_verify() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let initial = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+
+        let mut params = initial.clone();
+        let first = params.contribute(&mut rng, &0);
+        let before = params.clone();
+        let second = params.contribute(&mut rng, &0);
+
+        let hashes = params.verify_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        assert!(hashes[..] == [first, second]);
+        assert!(verify_contribution(&before, &params).unwrap() == second);
+
+        let mut bytes = vec![];
+        params.write(&mut bytes).unwrap();
+        assert!(MPCParameters::read(&bytes[..], false, true).unwrap() == params);
+    }
+
+    /// Contributes twice and returns the parameters before and after the
+    /// second contribution.
+    fn contributed_params() -> (MPCParameters, MPCParameters) {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let mut params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        params.contribute(&mut rng, &0);
+        let before = params.clone();
+        params.contribute(&mut rng, &0);
+
+        (before, params)
+    }
+
+    fn check(params: &MPCParameters) -> Result<Vec<[u8; 64]>, Phase2VerificationError> {
+        params.verify_with_source(multiplier_circuit(), false, &mut TestPowers::new(3))
+    }
+
+    #[test]
+    fn test_query_length_changed() {
+        let (before, after) = contributed_params();
+        let h_len = after.params.h.len();
+        let l_len = after.params.l.len();
+
+        let mut params = after.clone();
+        Arc::make_mut(&mut params.params.h).pop();
+        match check(&params) {
+            Err(Phase2VerificationError::HLengthMismatch { expected, actual }) if expected == h_len && actual == h_len - 1 => {},
+            _ => panic!("shorter H query not reported"),
+        }
+        match verify_contribution(&before, &params) {
+            Err(Phase2VerificationError::HLengthMismatch { expected, actual }) if expected == h_len && actual == h_len - 1 => {},
+            _ => panic!("shorter H query not reported"),
+        }
+
+        let mut params = after.clone();
+        Arc::make_mut(&mut params.params.l).push(G1Affine::one());
+        match check(&params) {
+            Err(Phase2VerificationError::LLengthMismatch { expected, actual }) if expected == l_len && actual == l_len + 1 => {},
+            _ => panic!("longer L query not reported"),
+        }
+        match verify_contribution(&before, &params) {
+            Err(Phase2VerificationError::LLengthMismatch { expected, actual }) if expected == l_len && actual == l_len + 1 => {},
+            _ => panic!("longer L query not reported"),
+        }
+    }
+
+    #[test]
+    fn test_delta_g2_changed() {
+        let (before, mut params) = contributed_params();
+        params.params.vk.delta_g2 = G2Affine::one();
+
+        match check(&params) {
+            Err(Phase2VerificationError::DeltaG2Mismatch) => {},
+            _ => panic!("delta in G2 with another ratio not reported"),
+        }
+        match verify_contribution(&before, &params) {
+            Err(Phase2VerificationError::DeltaG2Mismatch) => {},
+            _ => panic!("delta in G2 with another ratio not reported"),
+        }
+    }
+
+    #[test]
+    fn test_transcript_changed() {
+        let (before, after) = contributed_params();
+
+        let mut params = after.clone();
+        params.contributions[1].transcript[0] ^= 1;
+        match check(&params) {
+            Err(Phase2VerificationError::TranscriptMismatch { index: 1, .. }) => {},
+            _ => panic!("transcript of contribution #1 not reported"),
+        }
+        match verify_contribution(&before, &params) {
+            Err(Phase2VerificationError::TranscriptMismatch { index: 1, .. }) => {},
+            _ => panic!("transcript of contribution #1 not reported"),
+        }
+
+        // Changing an earlier contribution also changes the transcript
+        // of the later ones, so it's the first one that's reported.
+        let mut params = after.clone();
+        params.contributions[0].transcript[0] ^= 1;
+        match check(&params) {
+            Err(Phase2VerificationError::TranscriptMismatch { index: 0, .. }) => {},
+            _ => panic!("transcript of contribution #0 not reported"),
+        }
+        match verify_contribution(&before, &params) {
+            Err(Phase2VerificationError::ContributionChanged { index: 0 }) => {},
+            _ => panic!("modified contribution #0 not reported"),
+        }
+    }
+
+    #[test]
+    fn test_ab_queries_changed() {
+        let (before, after) = contributed_params();
+
+        let mut params = after.clone();
+        Arc::make_mut(&mut params.params.a)[0] = G1Affine::one();
+        match check(&params) {
+            Err(Phase2VerificationError::AQueryChanged) => {},
+            _ => panic!("changed A query not reported"),
+        }
+        match verify_contribution(&before, &params) {
+            Err(Phase2VerificationError::AQueryChanged) => {},
+            _ => panic!("changed A query not reported"),
+        }
+
+        let mut params = after.clone();
+        Arc::make_mut(&mut params.params.b_g1)[0] = G1Affine::one();
+        match check(&params) {
+            Err(Phase2VerificationError::BG1QueryChanged) => {},
+            _ => panic!("changed B query in G1 not reported"),
+        }
+        match verify_contribution(&before, &params) {
+            Err(Phase2VerificationError::BG1QueryChanged) => {},
+            _ => panic!("changed B query in G1 not reported"),
+        }
+
+        let mut params = after.clone();
+        Arc::make_mut(&mut params.params.b_g2)[0] = G2Affine::one();
+        match check(&params) {
+            Err(Phase2VerificationError::BG2QueryChanged) => {},
+            _ => panic!("changed B query in G2 not reported"),
+        }
+        match verify_contribution(&before, &params) {
+            Err(Phase2VerificationError::BG2QueryChanged) => {},
+            _ => panic!("changed B query in G2 not reported"),
+        }
+    }
+
+    #[test]
+    fn test_missing_radix_file() {
+        let directory = std::env::temp_dir().join(format!("phase2-missing-radix-{}", std::process::id()));
+        let directory = directory.to_string_lossy().into_owned();
+
+        match MPCParameters::new(multiplier_circuit(), false, &directory) {
+            Err(Phase2Error::Phase1(Phase1Error::MissingRadixFile { exp: 2, .. })) => {},
+            _ => panic!("missing phase1radix2m2 not reported"),
+        }
+
+        let params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        match params.verify(multiplier_circuit(), false, &directory) {
+            Err(Phase2VerificationError::Phase1(Phase1Error::MissingRadixFile { exp: 2, .. })) => {},
+            _ => panic!("missing phase1radix2m2 not reported"),
+        }
+    }
+
+    #[test]
+    fn test_synthesis_error_display() {
+        let e = Phase2Error::Synthesis(SynthesisError::PolynomialDegreeTooLarge);
+        assert_eq!(e.to_string(), "unable to synthesize the circuit: polynomial degree is too large");
+    }
+
+    #[test]
+    fn test_verification_synthesis_error_display() {
+        let e = Phase2VerificationError::Synthesis(SynthesisError::Unsatisfiable);
+        assert_eq!(e.to_string(), "unable to create initial parameters: unsatisfiable constraint system");
+    }
+}
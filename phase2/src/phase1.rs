@@ -0,0 +1,751 @@
+extern crate bellman_ce;
+extern crate byteorder;
+
+#[cfg(feature = "wasm")]
+use bellman_ce::singlecore::Worker;
+#[cfg(not(feature = "wasm"))]
+use bellman_ce::multicore::Worker;
+
+use std::{
+    error::Error,
+    fmt,
+    fs::{self, File},
+    io::{self, Read, Write, Seek, SeekFrom, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+
+#[cfg(feature = "powersoftau")]
+use powersoftau::{
+    parameters::{CheckForCorrectness, DeserializationError},
+    ptau::{
+        PtauReader,
+        TAU_G1_SECTION,
+        TAU_G2_SECTION,
+        ALPHA_TAU_G1_SECTION,
+        BETA_TAU_G1_SECTION,
+        BETA_G2_SECTION,
+    },
+};
+
+use bellman_ce::{
+    domain::{
+        EvaluationDomain,
+        Point,
+    },
+    pairing::{
+        CurveAffine,
+        CurveProjective,
+        EncodedPoint,
+        GroupDecodingError,
+        RawEncodable,
+        ff::PrimeField,
+        bn256::{
+            Bn256,
+            Fr,
+            G1,
+            G2,
+            G1Affine,
+            G2Affine,
+            G1Uncompressed,
+            G2Uncompressed,
+        }
+    }
+};
+
+
+/// Errors that might occur while obtaining the phase 1 parameters.
+#[derive(Debug)]
+pub enum Phase1Error {
+    IoError(io::Error),
+    /// A `.ptau` file couldn't be parsed.
+    #[cfg(feature = "powersoftau")]
+    Ptau(DeserializationError),
+    InvalidPoint(GroupDecodingError),
+    PointAtInfinity,
+    /// The ceremony doesn't have enough powers of tau for the domain.
+    NotEnoughPowers { required: u32, available: u32 },
+    /// The `phase1radix2m{exp}` file for the domain doesn't exist.
+    MissingRadixFile { exp: u32, path: PathBuf, expected_size: u64 },
+    /// The `phase1radix2m{exp}` file for the domain has a wrong size,
+    /// e.g. it was truncated while copying.
+    InvalidRadixFileSize { exp: u32, path: PathBuf, expected_size: u64, actual_size: u64 },
+    /// The file has neither the size of a challenge nor of a response
+    /// file of the powers of tau ceremony.
+    InvalidAccumulatorSize { size: u64 },
+}
+
+impl fmt::Display for Phase1Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Phase1Error::IoError(ref e) => write!(f, "I/O error: {}", e),
+            #[cfg(feature = "powersoftau")]
+            Phase1Error::Ptau(ref e) => write!(f, "invalid .ptau file: {}", e),
+            Phase1Error::InvalidPoint(ref e) => write!(f, "invalid point: {}", e),
+            Phase1Error::PointAtInfinity => write!(f, "point at infinity found"),
+            Phase1Error::NotEnoughPowers { required, available } => write!(f, "domain of size 2^{} is required, while the ceremony supports up to 2^{}", required, available),
+            Phase1Error::MissingRadixFile { exp, ref path, expected_size } => write!(f, "phase1radix2m{} is required, but {} doesn't exist (expected a file of {} bytes)", exp, path.display(), expected_size),
+            Phase1Error::InvalidRadixFileSize { exp, ref path, expected_size, actual_size } => write!(f, "phase1radix2m{} at {} should be {} bytes, but it's {}", exp, path.display(), expected_size, actual_size),
+            Phase1Error::InvalidAccumulatorSize { size } => write!(f, "{} bytes is neither the size of a challenge nor of a response file", size),
+        }
+    }
+}
+
+impl Error for Phase1Error {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            Phase1Error::IoError(ref e) => Some(e),
+            #[cfg(feature = "powersoftau")]
+            Phase1Error::Ptau(ref e) => Some(e),
+            Phase1Error::InvalidPoint(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Phase1Error {
+    fn from(err: io::Error) -> Phase1Error {
+        Phase1Error::IoError(err)
+    }
+}
+
+#[cfg(feature = "powersoftau")]
+impl From<DeserializationError> for Phase1Error {
+    fn from(err: DeserializationError) -> Phase1Error {
+        Phase1Error::Ptau(err)
+    }
+}
+
+impl From<GroupDecodingError> for Phase1Error {
+    fn from(err: GroupDecodingError) -> Phase1Error {
+        Phase1Error::InvalidPoint(err)
+    }
+}
+
+/// The phase 1 parameters for an evaluation domain of size `m`, with
+/// the powers of tau turned into Lagrange coefficients. This is the
+/// content of a `phase1radix2m{exp}` file.
+pub struct Phase1Radix {
+    pub alpha_g1: G1Affine,
+    pub beta_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub coeffs_g1: Vec<G1Affine>,
+    pub coeffs_g2: Vec<G2Affine>,
+    pub alpha_coeffs_g1: Vec<G1Affine>,
+    pub beta_coeffs_g1: Vec<G1Affine>,
+    /// `tau^i * (tau^m - 1)` for `i` in `0..m-1`
+    pub h: Vec<G1Affine>,
+}
+
+impl Phase1Radix {
+    /// The size of a `phase1radix2m{exp}` file for a domain of size `m`.
+    pub fn file_size(m: usize) -> u64 {
+        let g1_size = G1Uncompressed::size() as u64;
+        let g2_size = G2Uncompressed::size() as u64;
+        let m = m as u64;
+
+        // alpha, beta in G1 and beta in G2
+        2 * g1_size + g2_size +
+            // Lagrange coefficients in G1 and G2, alpha and beta ones in G1
+            m * (3 * g1_size + g2_size) +
+            // H query
+            (m - 1) * g1_size
+    }
+
+    /// Reads the parameters for a domain of size `m` in the
+    /// `phase1radix2m{exp}` file format.
+    pub fn read<R: Read>(reader: &mut R, m: usize) -> Result<Phase1Radix, Phase1Error> {
+        fn read_uncompressed<G: CurveAffine, R: Read>(reader: &mut R) -> Result<G, Phase1Error> {
+            let mut repr = G::Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
+
+            let point = repr.into_affine_unchecked()?;
+            if point.is_zero() {
+                return Err(Phase1Error::PointAtInfinity);
+            }
+            Ok(point)
+        }
+
+        fn read_uncompressed_points<G: CurveAffine, R: Read>(reader: &mut R, count: usize) -> Result<Vec<G>, Phase1Error> {
+            (0..count).map(|_| read_uncompressed(reader)).collect()
+        }
+
+        Ok(Phase1Radix {
+            alpha_g1: read_uncompressed(reader)?,
+            beta_g1: read_uncompressed(reader)?,
+            beta_g2: read_uncompressed(reader)?,
+            coeffs_g1: read_uncompressed_points(reader, m)?,
+            coeffs_g2: read_uncompressed_points(reader, m)?,
+            alpha_coeffs_g1: read_uncompressed_points(reader, m)?,
+            beta_coeffs_g1: read_uncompressed_points(reader, m)?,
+            h: read_uncompressed_points(reader, m - 1)?,
+        })
+    }
+
+    /// Writes the parameters in the `phase1radix2m{exp}` file format.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        fn write_points<G: CurveAffine, W: Write>(writer: &mut W, points: &[G]) -> io::Result<()> {
+            for point in points {
+                writer.write_all(point.into_uncompressed().as_ref())?;
+            }
+            Ok(())
+        }
+
+        writer.write_all(self.alpha_g1.into_uncompressed().as_ref())?;
+        writer.write_all(self.beta_g1.into_uncompressed().as_ref())?;
+        writer.write_all(self.beta_g2.into_uncompressed().as_ref())?;
+        write_points(writer, &self.coeffs_g1)?;
+        write_points(writer, &self.coeffs_g2)?;
+        write_points(writer, &self.alpha_coeffs_g1)?;
+        write_points(writer, &self.beta_coeffs_g1)?;
+        write_points(writer, &self.h)
+    }
+
+    /// Computes the parameters for a domain of size `2^exp` from the
+    /// powers of tau, reading only the powers this domain needs.
+    pub fn from_tau_powers<T: TauPowers + ?Sized>(powers: &mut T, exp: u32) -> Result<Phase1Radix, Phase1Error> {
+        if exp > powers.max_exp() {
+            return Err(Phase1Error::NotEnoughPowers { required: exp, available: powers.max_exp() });
+        }
+        let m = 1 << exp;
+        let worker = Worker::new();
+
+        let tau_powers_g1 = powers.tau_powers_g1(2 * m - 1)?;
+        let alpha_tau_powers_g1 = powers.alpha_tau_powers_g1(m)?;
+        let beta_tau_powers_g1 = powers.beta_tau_powers_g1(m)?;
+
+        // H query of Groth16 needs x^(i + m) - x^i for i in 0..=(m-2)
+        let mut h = Vec::with_capacity(m - 1);
+        for i in 0..(m - 1) {
+            let mut tmp = tau_powers_g1[i + m].into_projective();
+            tmp.sub_assign(&tau_powers_g1[i].into_projective());
+            h.push(tmp);
+        }
+        G1::batch_normalization(&mut h);
+
+        Ok(Phase1Radix {
+            alpha_g1: alpha_tau_powers_g1[0],
+            beta_g1: beta_tau_powers_g1[0],
+            beta_g2: powers.beta_g2()?,
+            coeffs_g1: lagrange_coeffs::<G1>(&tau_powers_g1[0..m], &worker),
+            coeffs_g2: lagrange_coeffs::<G2>(&powers.tau_powers_g2(m)?, &worker),
+            alpha_coeffs_g1: lagrange_coeffs::<G1>(&alpha_tau_powers_g1, &worker),
+            beta_coeffs_g1: lagrange_coeffs::<G1>(&beta_tau_powers_g1, &worker),
+            h: h.into_iter().map(|e| e.into_affine()).collect(),
+        })
+    }
+}
+
+/// Converts powers of tau into Lagrange coefficients over the
+/// evaluation domain of the same size.
+fn lagrange_coeffs<G: CurveProjective>(powers: &[G::Affine], worker: &Worker) -> Vec<G::Affine> {
+    let mut domain = EvaluationDomain::<G::Engine, _>::from_coeffs(
+        powers.iter().map(|e| Point(e.into_projective())).collect()
+    ).expect("the number of powers is a power of two within the ceremony limits");
+    domain.ifft(worker);
+
+    let mut coeffs = domain.into_coeffs().into_iter().map(|e| e.0).collect::<Vec<G>>();
+    G::batch_normalization(&mut coeffs);
+    coeffs.into_iter().map(|e| e.into_affine()).collect()
+}
+
+/// The result of a powers of tau ceremony.
+pub trait TauPowers {
+    /// The largest `exp` for which a domain of size `2^exp` is supported.
+    fn max_exp(&self) -> u32;
+    /// `tau^i` in G1 for `i` in `0..count`.
+    fn tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error>;
+    /// `tau^i` in G2 for `i` in `0..count`.
+    fn tau_powers_g2(&mut self, count: usize) -> Result<Vec<G2Affine>, Phase1Error>;
+    /// `alpha * tau^i` in G1 for `i` in `0..count`.
+    fn alpha_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error>;
+    /// `beta * tau^i` in G1 for `i` in `0..count`.
+    fn beta_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error>;
+    /// `beta` in G2.
+    fn beta_g2(&mut self) -> Result<G2Affine, Phase1Error>;
+}
+
+impl<T: TauPowers + ?Sized> TauPowers for Box<T> {
+    fn max_exp(&self) -> u32 {
+        (**self).max_exp()
+    }
+    fn tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+        (**self).tau_powers_g1(count)
+    }
+    fn tau_powers_g2(&mut self, count: usize) -> Result<Vec<G2Affine>, Phase1Error> {
+        (**self).tau_powers_g2(count)
+    }
+    fn alpha_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+        (**self).alpha_tau_powers_g1(count)
+    }
+    fn beta_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+        (**self).beta_tau_powers_g1(count)
+    }
+    fn beta_g2(&mut self) -> Result<G2Affine, Phase1Error> {
+        (**self).beta_g2()
+    }
+}
+
+/// A challenge or response file of the powers of tau ceremony
+/// for `2^power` powers.
+pub struct Accumulator<R> {
+    reader: R,
+    power: u32,
+    compressed: bool,
+}
+
+impl<R> Accumulator<R> {
+    /// Response files are compressed, challenge files are not.
+    pub fn new(reader: R, power: u32, compressed: bool) -> Accumulator<R> {
+        Accumulator { reader, power, compressed }
+    }
+
+    fn point_size<G: CurveAffine>(&self) -> u64 {
+        if self.compressed {
+            G::Compressed::size() as u64
+        } else {
+            G::Uncompressed::size() as u64
+        }
+    }
+
+    /// Offsets of the tau powers in G1 and G2, the alpha and beta tau
+    /// powers and beta in G2, following the hash of the previous contribution.
+    fn offsets(&self) -> [u64; 5] {
+        let powers_length = 1u64 << self.power;
+        let g1_size = self.point_size::<G1Affine>();
+        let g2_size = self.point_size::<G2Affine>();
+
+        let tau_g1 = 64;
+        let tau_g2 = tau_g1 + (2 * powers_length - 1) * g1_size;
+        let alpha_g1 = tau_g2 + powers_length * g2_size;
+        let beta_g1 = alpha_g1 + powers_length * g1_size;
+        let beta_g2 = beta_g1 + powers_length * g1_size;
+        [tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2]
+    }
+
+    /// Response files end with the public key of their contributor,
+    /// made of 6 points in G1 and 3 in G2, uncompressed.
+    fn file_size(&self) -> u64 {
+        let mut size = self.offsets()[4] + self.point_size::<G2Affine>();
+        if self.compressed {
+            size += 6 * G1Uncompressed::size() as u64 + 3 * G2Uncompressed::size() as u64;
+        }
+        size
+    }
+}
+
+impl<R: Read + Seek> Accumulator<R> {
+    /// Opens a challenge or response file, telling them apart and finding
+    /// their number of powers by their size.
+    pub fn open(mut reader: R) -> Result<Accumulator<R>, Phase1Error> {
+        let size = reader.seek(SeekFrom::End(0))?;
+        for power in 0..=Fr::S {
+            for &compressed in &[false, true] {
+                if Accumulator::new((), power, compressed).file_size() == size {
+                    return Ok(Accumulator::new(reader, power, compressed));
+                }
+            }
+        }
+        Err(Phase1Error::InvalidAccumulatorSize { size })
+    }
+
+    fn read_points<G: CurveAffine>(&mut self, offset: u64, count: usize) -> Result<Vec<G>, Phase1Error> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let mut reader = BufReader::with_capacity(1024 * 1024, &mut self.reader);
+
+        let mut points = Vec::with_capacity(count);
+        for _ in 0..count {
+            let point = if self.compressed {
+                let mut repr = G::Compressed::empty();
+                reader.read_exact(repr.as_mut())?;
+                repr.into_affine()?
+            } else {
+                let mut repr = G::Uncompressed::empty();
+                reader.read_exact(repr.as_mut())?;
+                repr.into_affine()?
+            };
+            if point.is_zero() {
+                return Err(Phase1Error::PointAtInfinity);
+            }
+            points.push(point);
+        }
+        Ok(points)
+    }
+}
+
+impl<R: Read + Seek> TauPowers for Accumulator<R> {
+    fn max_exp(&self) -> u32 {
+        self.power
+    }
+    fn tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+        let offset = self.offsets()[0];
+        self.read_points(offset, count)
+    }
+    fn tau_powers_g2(&mut self, count: usize) -> Result<Vec<G2Affine>, Phase1Error> {
+        let offset = self.offsets()[1];
+        self.read_points(offset, count)
+    }
+    fn alpha_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+        let offset = self.offsets()[2];
+        self.read_points(offset, count)
+    }
+    fn beta_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+        let offset = self.offsets()[3];
+        self.read_points(offset, count)
+    }
+    fn beta_g2(&mut self) -> Result<G2Affine, Phase1Error> {
+        let offset = self.offsets()[4];
+        Ok(self.read_points(offset, 1)?[0])
+    }
+}
+
+/// A snarkjs `.ptau` file, read with the parser of the powers of tau
+/// ceremony.
+#[cfg(feature = "powersoftau")]
+pub struct PtauFile<R> {
+    reader: PtauReader<R>,
+}
+
+#[cfg(feature = "powersoftau")]
+impl<R: Read + Seek> PtauFile<R> {
+    pub fn new(reader: R) -> Result<PtauFile<R>, Phase1Error> {
+        Ok(PtauFile { reader: PtauReader::new::<Bn256>(reader)? })
+    }
+
+    fn read_section<G: RawEncodable>(&mut self, section_type: u32, count: usize) -> Result<Vec<G>, Phase1Error> {
+        Ok(self.reader.read_section(section_type, count, CheckForCorrectness::Yes)?)
+    }
+}
+
+#[cfg(feature = "powersoftau")]
+impl<R: Read + Seek> TauPowers for PtauFile<R> {
+    fn max_exp(&self) -> u32 {
+        self.reader.power() as u32
+    }
+    fn tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+        self.read_section(TAU_G1_SECTION, count)
+    }
+    fn tau_powers_g2(&mut self, count: usize) -> Result<Vec<G2Affine>, Phase1Error> {
+        self.read_section(TAU_G2_SECTION, count)
+    }
+    fn alpha_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+        self.read_section(ALPHA_TAU_G1_SECTION, count)
+    }
+    fn beta_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+        self.read_section(BETA_TAU_G1_SECTION, count)
+    }
+    fn beta_g2(&mut self) -> Result<G2Affine, Phase1Error> {
+        Ok(self.read_section(BETA_G2_SECTION, 1)?[0])
+    }
+}
+
+/// Provides the phase 1 parameters `MPCParameters::new` starts from.
+pub trait Phase1Source {
+    /// Returns the parameters for the domain of size `2^exp`.
+    fn radix(&mut self, exp: u32) -> Result<Phase1Radix, Phase1Error>;
+}
+
+/// A directory of precomputed `phase1radix2m{exp}` files, as created
+/// by `prepare_phase2`.
+pub struct RadixDirectory {
+    directory: PathBuf,
+}
+
+impl RadixDirectory {
+    pub fn new<P: AsRef<Path>>(directory: P) -> RadixDirectory {
+        RadixDirectory { directory: directory.as_ref().to_path_buf() }
+    }
+
+    fn path(&self, exp: u32) -> PathBuf {
+        self.directory.join(format!("phase1radix2m{}", exp))
+    }
+}
+
+impl Phase1Source for RadixDirectory {
+    fn radix(&mut self, exp: u32) -> Result<Phase1Radix, Phase1Error> {
+        let path = self.path(exp);
+        let m = 1 << exp;
+        let expected_size = Phase1Radix::file_size(m);
+
+        let f = match File::open(&path) {
+            Ok(f) => f,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(Phase1Error::MissingRadixFile { exp, path, expected_size });
+            },
+            Err(e) => return Err(e.into()),
+        };
+        let actual_size = f.metadata()?.len();
+        if actual_size != expected_size {
+            return Err(Phase1Error::InvalidRadixFileSize { exp, path, expected_size, actual_size });
+        }
+
+        let mut f = BufReader::with_capacity(1024 * 1024, f);
+        Phase1Radix::read(&mut f, m)
+    }
+}
+
+/// Computes the phase 1 parameters on demand from the powers of tau.
+/// With a cache directory, computed parameters are stored there as
+/// `phase1radix2m{exp}` files and reused on later calls.
+pub struct LagrangeSource<T> {
+    powers: T,
+    cache: Option<RadixDirectory>,
+}
+
+impl<T: TauPowers> LagrangeSource<T> {
+    pub fn new(powers: T) -> LagrangeSource<T> {
+        LagrangeSource { powers, cache: None }
+    }
+
+    pub fn with_cache<P: AsRef<Path>>(self, directory: P) -> LagrangeSource<T> {
+        LagrangeSource { powers: self.powers, cache: Some(RadixDirectory::new(directory)) }
+    }
+}
+
+impl<T: TauPowers> Phase1Source for LagrangeSource<T> {
+    fn radix(&mut self, exp: u32) -> Result<Phase1Radix, Phase1Error> {
+        if let Some(ref mut cache) = self.cache {
+            match cache.radix(exp) {
+                Err(Phase1Error::MissingRadixFile { .. }) | Err(Phase1Error::InvalidRadixFileSize { .. }) => {},
+                result => return result,
+            }
+        }
+
+        let radix = Phase1Radix::from_tau_powers(&mut self.powers, exp)?;
+
+        if let Some(ref cache) = self.cache {
+            // Write to a temporary file first, so that an interrupted
+            // write doesn't leave a truncated file in the cache
+            let path = cache.path(exp);
+            let tmp_path = path.with_extension("tmp");
+            {
+                let mut writer = BufWriter::new(File::create(&tmp_path)?);
+                radix.write(&mut writer)?;
+                writer.flush()?;
+            }
+            fs::rename(tmp_path, path)?;
+        }
+
+        Ok(radix)
+    }
+}
+
+/// Opens the phase 1 parameters at `path`, either a snarkjs `.ptau` file,
+/// a challenge or response file of the powers of tau ceremony, or a
+/// directory of `phase1radix2m{exp}` files. `.ptau` files need the
+/// `powersoftau` feature.
+pub fn phase1_source(path: &str) -> Result<Box<dyn Phase1Source>, Phase1Error> {
+    #[cfg(feature = "powersoftau")]
+    {
+        if path.ends_with(".ptau") {
+            let reader = BufReader::new(File::open(path)?);
+            return Ok(Box::new(LagrangeSource::new(PtauFile::new(reader)?)));
+        }
+    }
+
+    if Path::new(path).is_file() {
+        Ok(Box::new(LagrangeSource::new(Accumulator::open(File::open(path)?)?)))
+    } else {
+        Ok(Box::new(RadixDirectory::new(path)))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use bellman_ce::pairing::ff::{Field, PrimeField};
+    use bellman_ce::pairing::bn256::Fr;
+    use std::io::Cursor;
+
+    /// Powers of known tau, alpha and beta, standing in for a ceremony.
+    pub(crate) struct TestPowers {
+        pub power: u32,
+        pub tau: Fr,
+        pub alpha: Fr,
+        pub beta: Fr,
+    }
+
+    impl TestPowers {
+        pub fn new(power: u32) -> TestPowers {
+            TestPowers {
+                power,
+                tau: Fr::from_str("5").unwrap(),
+                alpha: Fr::from_str("7").unwrap(),
+                beta: Fr::from_str("11").unwrap(),
+            }
+        }
+
+        fn powers<G: CurveAffine<Scalar = Fr>>(&self, scale: Fr, count: usize) -> Vec<G> {
+            let mut acc = scale;
+            (0..count).map(|_| {
+                let point = G::one().mul(acc).into_affine();
+                acc.mul_assign(&self.tau);
+                point
+            }).collect()
+        }
+    }
+
+    impl TauPowers for TestPowers {
+        fn max_exp(&self) -> u32 {
+            self.power
+        }
+        fn tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+            Ok(self.powers(Fr::one(), count))
+        }
+        fn tau_powers_g2(&mut self, count: usize) -> Result<Vec<G2Affine>, Phase1Error> {
+            Ok(self.powers(Fr::one(), count))
+        }
+        fn alpha_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+            Ok(self.powers(self.alpha, count))
+        }
+        fn beta_tau_powers_g1(&mut self, count: usize) -> Result<Vec<G1Affine>, Phase1Error> {
+            Ok(self.powers(self.beta, count))
+        }
+        fn beta_g2(&mut self) -> Result<G2Affine, Phase1Error> {
+            Ok(G2Affine::one().mul(self.beta).into_affine())
+        }
+    }
+
+    impl Phase1Source for TestPowers {
+        fn radix(&mut self, exp: u32) -> Result<Phase1Radix, Phase1Error> {
+            Phase1Radix::from_tau_powers(self, exp)
+        }
+    }
+
+    fn radix_bytes(radix: &Phase1Radix) -> Vec<u8> {
+        let mut bytes = vec![];
+        radix.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_lagrange_coeffs() {
+        let mut powers = TestPowers::new(3);
+        let radix = Phase1Radix::from_tau_powers(&mut powers, 3).unwrap();
+        assert_eq!(radix.coeffs_g1.len(), 8);
+        assert_eq!(radix.h.len(), 7);
+
+        // The Lagrange basis sums up to the constant polynomial 1
+        let mut sum = G1::zero();
+        for coeff in &radix.coeffs_g1 {
+            sum.add_assign_mixed(coeff);
+        }
+        assert!(sum.into_affine() == G1Affine::one());
+
+        match Phase1Radix::from_tau_powers(&mut powers, 4) {
+            Err(Phase1Error::NotEnoughPowers { required: 4, available: 3 }) => {},
+            _ => panic!("domain larger than the ceremony accepted"),
+        }
+    }
+
+    #[test]
+    fn test_radix_cache() {
+        let directory = std::env::temp_dir().join(format!("phase1-radix-cache-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        match RadixDirectory::new(&directory).radix(2) {
+            Err(Phase1Error::MissingRadixFile { exp: 2, .. }) => {},
+            _ => panic!("missing radix file not reported"),
+        }
+
+        let mut source = LagrangeSource::new(TestPowers::new(3)).with_cache(&directory);
+        let radix = source.radix(2).unwrap();
+        let cached = RadixDirectory::new(&directory).radix(2).unwrap();
+        assert_eq!(radix_bytes(&radix), radix_bytes(&cached));
+
+        File::create(directory.join("phase1radix2m2")).unwrap().write_all(&[0; 10]).unwrap();
+        match RadixDirectory::new(&directory).radix(2) {
+            Err(Phase1Error::InvalidRadixFileSize { exp: 2, actual_size: 10, .. }) => {},
+            _ => panic!("truncated radix file not reported"),
+        }
+        // A broken cache entry is computed again
+        assert_eq!(radix_bytes(&source.radix(2).unwrap()), radix_bytes(&radix));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(feature = "powersoftau")]
+    #[test]
+    fn test_ptau_file() {
+        use powersoftau::batched_accumulator::BatchedAccumulator;
+        use powersoftau::parameters::CeremonyParams;
+
+        let mut powers = TestPowers::new(3);
+        let parameters = CeremonyParams::<Bn256>::new(3, 4);
+        let mut accumulator = BatchedAccumulator::empty(&parameters);
+        accumulator.tau_powers_g1 = powers.tau_powers_g1(parameters.powers_g1_length).unwrap();
+        accumulator.tau_powers_g2 = powers.tau_powers_g2(parameters.powers_length).unwrap();
+        accumulator.alpha_tau_powers_g1 = powers.alpha_tau_powers_g1(parameters.powers_length).unwrap();
+        accumulator.beta_tau_powers_g1 = powers.beta_tau_powers_g1(parameters.powers_length).unwrap();
+        accumulator.beta_g2 = powers.beta_g2().unwrap();
+
+        let mut ptau = vec![];
+        accumulator.write_ptau(&mut ptau).unwrap();
+
+        let mut file = PtauFile::new(Cursor::new(&ptau)).unwrap();
+        assert_eq!(file.max_exp(), 3);
+        assert!(file.tau_powers_g1(15).unwrap() == accumulator.tau_powers_g1);
+        assert!(file.beta_g2().unwrap() == accumulator.beta_g2);
+        let radix = Phase1Radix::from_tau_powers(&mut file, 2).unwrap();
+        assert_eq!(radix_bytes(&radix), radix_bytes(&powers.radix(2).unwrap()));
+
+        let mut bad_magic = ptau.clone();
+        bad_magic[0] = b'x';
+        match PtauFile::new(Cursor::new(&bad_magic)) {
+            Err(Phase1Error::Ptau(DeserializationError::InvalidMagic)) => {},
+            _ => panic!("invalid magic accepted"),
+        }
+
+        let truncated = &ptau[..ptau.len() / 2];
+        assert!(PtauFile::new(Cursor::new(truncated)).and_then(|mut f| f.tau_powers_g2(8)).is_err());
+    }
+
+    #[cfg(feature = "powersoftau")]
+    #[test]
+    fn test_accumulator() {
+        use memmap::MmapMut;
+        use powersoftau::batched_accumulator::BatchedAccumulator;
+        use powersoftau::parameters::{CeremonyParams, UseCompression};
+
+        let mut powers = TestPowers::new(3);
+        let parameters = CeremonyParams::<Bn256>::new(3, 4);
+        let mut accumulator = BatchedAccumulator::empty(&parameters);
+        accumulator.tau_powers_g1 = powers.tau_powers_g1(parameters.powers_g1_length).unwrap();
+        accumulator.tau_powers_g2 = powers.tau_powers_g2(parameters.powers_length).unwrap();
+        accumulator.alpha_tau_powers_g1 = powers.alpha_tau_powers_g1(parameters.powers_length).unwrap();
+        accumulator.beta_tau_powers_g1 = powers.beta_tau_powers_g1(parameters.powers_length).unwrap();
+        accumulator.beta_g2 = powers.beta_g2().unwrap();
+
+        // A challenge is uncompressed, a response is compressed and followed
+        // by a public key which isn't read
+        let mut challenge = MmapMut::map_anon(parameters.accumulator_size).unwrap();
+        accumulator.serialize(&mut challenge, UseCompression::No, &parameters).unwrap();
+        let mut response = MmapMut::map_anon(parameters.contribution_size).unwrap();
+        accumulator.serialize(&mut response, UseCompression::Yes, &parameters).unwrap();
+
+        for (file, compressed) in &[(&challenge[..], false), (&response[..], true)] {
+            let mut file = Accumulator::open(Cursor::new(file)).unwrap();
+            assert_eq!(file.max_exp(), 3);
+            assert_eq!(file.compressed, *compressed);
+            assert!(file.tau_powers_g1(15).unwrap() == accumulator.tau_powers_g1);
+            assert!(file.beta_g2().unwrap() == accumulator.beta_g2);
+
+            let mut source = LagrangeSource::new(file);
+            for exp in 1..4 {
+                assert_eq!(radix_bytes(&source.radix(exp).unwrap()), radix_bytes(&powers.radix(exp).unwrap()));
+            }
+        }
+
+        match Accumulator::open(Cursor::new(&challenge[1..])) {
+            Err(Phase1Error::InvalidAccumulatorSize { size }) => assert_eq!(size, challenge.len() as u64 - 1),
+            _ => panic!("truncated challenge accepted"),
+        }
+
+        // `phase1_source` opens files which aren't `.ptau` as accumulators
+        let path = std::env::temp_dir().join(format!("phase1-challenge-{}", std::process::id()));
+        File::create(&path).unwrap().write_all(&challenge).unwrap();
+        let radix = phase1_source(path.to_str().unwrap()).unwrap().radix(2).unwrap();
+        assert_eq!(radix_bytes(&radix), radix_bytes(&powers.radix(2).unwrap()));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,561 @@
+extern crate bellman_ce;
+
+use std::fmt;
+use std::error::Error;
+
+use bellman_ce::{
+    groth16::VerifyingKey,
+    pairing::{
+        Engine,
+        CurveAffine,
+        ff::{
+            PrimeField,
+        },
+        bn256::{
+            Bn256,
+            Fq,
+            Fr,
+        }
+    }
+};
+
+use template::{self, Context, TemplateError};
+use utils::repr_to_big;
+
+const LEGACY_TEMPLATE: &str = include_str!("verifier_groth.sol");
+const ASSEMBLY_TEMPLATE: &str = include_str!("verifier_groth_asm.sol");
+const YUL_TEMPLATE: &str = include_str!("verifier_groth.yul");
+
+/// The kind of verifier to generate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifierVariant {
+    /// The `Pairing` library based verifier taking the proof as ABI encoded
+    /// `bytes` and the inputs as a fixed size array, for Solidity 0.6.0 and
+    /// later.
+    Legacy,
+    /// A gas-optimized verifier taking the proof and the inputs as
+    /// `uint256[] calldata`, with the verifying key inlined into inline
+    /// assembly, for Solidity 0.7.5 and later.
+    Assembly,
+    /// A standalone Yul object with the verifying key inlined, taking the
+    /// proof and the inputs as raw calldata without a function selector.
+    Yul,
+}
+
+impl VerifierVariant {
+    /// The oldest Solidity version the variant compiles with.
+    fn min_version(&self) -> (u32, u32, u32) {
+        match *self {
+            VerifierVariant::Legacy => (0, 6, 0),
+            VerifierVariant::Assembly | VerifierVariant::Yul => (0, 7, 5),
+        }
+    }
+
+    /// The version constraint used when the options don't set one.
+    fn default_pragma(&self) -> &'static str {
+        match *self {
+            VerifierVariant::Legacy => "^0.6.0",
+            VerifierVariant::Assembly | VerifierVariant::Yul => ">=0.7.5 <0.9.0",
+        }
+    }
+}
+
+impl fmt::Display for VerifierVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerifierVariant::Legacy => write!(f, "legacy"),
+            VerifierVariant::Assembly => write!(f, "assembly"),
+            VerifierVariant::Yul => write!(f, "yul"),
+        }
+    }
+}
+
+/// Options of the generated verifier.
+#[derive(Clone, Debug)]
+pub struct SolidityOptions {
+    /// The version constraint of the `pragma solidity` directive, like
+    /// `^0.8.0` or `>=0.7.5 <0.9.0`, or of the solc versions named in the
+    /// header of the Yul verifier. Every version it allows must be
+    /// supported by the variant, alternatives joined with `||` aren't
+    /// accepted. Defaults to every version the variant supports.
+    pub pragma: Option<String>,
+    pub variant: VerifierVariant,
+    /// Whether to add a `verifyProofs` entry point checking many proofs
+    /// with a single pairing, only supported by the assembly variant.
+    pub batch: bool,
+}
+
+impl Default for SolidityOptions {
+    fn default() -> SolidityOptions {
+        SolidityOptions {
+            pragma: None,
+            variant: VerifierVariant::Legacy,
+            batch: false,
+        }
+    }
+}
+
+/// Errors that might occur while generating a verifier.
+#[derive(Debug)]
+pub enum SolidityError {
+    TemplateError(TemplateError),
+    /// A pragma which doesn't name a Solidity version.
+    InvalidPragma(String),
+    /// A pragma allowing Solidity versions the variant doesn't compile with.
+    UnsupportedVersion { variant: VerifierVariant, pragma: String },
+    /// Batch verification was requested for a variant which doesn't
+    /// support it.
+    BatchUnsupported(VerifierVariant),
+}
+
+impl fmt::Display for SolidityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SolidityError::TemplateError(ref e) => write!(f, "template error: {}", e),
+            SolidityError::InvalidPragma(ref pragma) => write!(f, "invalid Solidity pragma {:?}", pragma),
+            SolidityError::UnsupportedVersion { variant, ref pragma } => {
+                let (major, minor, patch) = variant.min_version();
+                write!(f, "the {} verifier requires Solidity {}.{}.{} or later, pragma {:?} allows older versions", variant, major, minor, patch, pragma)
+            },
+            SolidityError::BatchUnsupported(variant) => write!(f, "the {} verifier doesn't support batch verification", variant),
+        }
+    }
+}
+
+impl Error for SolidityError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            SolidityError::TemplateError(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<TemplateError> for SolidityError {
+    fn from(err: TemplateError) -> SolidityError {
+        SolidityError::TemplateError(err)
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.split('.').map(|part| part.parse::<u32>());
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => Some((major, minor, patch)),
+        _ => None,
+    }
+}
+
+/// Parses a pragma made of `major.minor.patch` versions prefixed with
+/// `^`, `~`, `=`, `>`, `>=`, `<` or `<=`, all of which must hold, and
+/// returns the lowest version it allows. Upper bounds only narrow the
+/// range down, so a pragma without a lower bound allows every version
+/// from `0.0.0` on. Returns `None` if the pragma can't be parsed, or
+/// allows no version at all.
+fn pragma_lower_bound(pragma: &str) -> Option<(u32, u32, u32)> {
+    let mut lower = (0, 0, 0);
+    // The lowest version excluded by the upper bounds.
+    let mut upper: Option<(u32, u32, u32)> = None;
+    let mut rest = pragma.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let op_end = rest.find(|c: char| !"^~=<>".contains(c)).unwrap_or(rest.len());
+        let op = &rest[..op_end];
+        rest = rest[op_end..].trim_start();
+        let version_end = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let (major, minor, patch) = parse_version(&rest[..version_end])?;
+        rest = rest[version_end..].trim_start();
+
+        let (from, to) = match op {
+            "" | "=" => (Some((major, minor, patch)), Some((major, minor, patch + 1))),
+            ">=" => (Some((major, minor, patch)), None),
+            ">" => (Some((major, minor, patch + 1)), None),
+            "<" => (None, Some((major, minor, patch))),
+            "<=" => (None, Some((major, minor, patch + 1))),
+            "~" => (Some((major, minor, patch)), Some((major, minor + 1, 0))),
+            "^" if major > 0 => (Some((major, minor, patch)), Some((major + 1, 0, 0))),
+            "^" => (Some((major, minor, patch)), Some((major, minor + 1, 0))),
+            _ => return None,
+        };
+        if let Some(from) = from {
+            lower = lower.max(from);
+        }
+        if let Some(to) = to {
+            upper = Some(upper.map_or(to, |upper| upper.min(to)));
+        }
+    }
+
+    match upper {
+        Some(upper) if upper <= lower => None,
+        _ => Some(lower),
+    }
+}
+
+fn g1_coordinates(p: &<Bn256 as Engine>::G1Affine) -> [String; 2] {
+    [repr_to_big(p.get_x().into_repr()), repr_to_big(p.get_y().into_repr())]
+}
+
+/// The coordinates of a G2 point in the order the pairing precompile
+/// expects them, the imaginary part of each coordinate first.
+fn g2_coordinates(p: &<Bn256 as Engine>::G2Affine) -> [String; 4] {
+    let x = p.get_x();
+    let y = p.get_y();
+    [
+        repr_to_big(x.c1.into_repr()),
+        repr_to_big(x.c0.into_repr()),
+        repr_to_big(y.c1.into_repr()),
+        repr_to_big(y.c0.into_repr()),
+    ]
+}
+
+fn set_g2(context: &mut Context, name: &str, p: &<Bn256 as Engine>::G2Affine) {
+    let [x1, x0, y1, y0] = g2_coordinates(p);
+    context.set(&format!("{}_x1", name), x1);
+    context.set(&format!("{}_x0", name), x0);
+    context.set(&format!("{}_y1", name), y1);
+    context.set(&format!("{}_y0", name), y0);
+}
+
+fn legacy_context(vk: &VerifyingKey<Bn256>) -> Context {
+    let p1_to_str = |p: &<Bn256 as Engine>::G1Affine| {
+        let [x, y] = g1_coordinates(p);
+        format!("uint256({}), uint256({})", x, y)
+    };
+    let p2_to_str = |p: &<Bn256 as Engine>::G2Affine| {
+        let [x1, x0, y1, y0] = g2_coordinates(p);
+        format!("[uint256({}), uint256({})], [uint256({}), uint256({})]", x1, x0, y1, y0)
+    };
+
+    let mut vi = String::from("");
+    for i in 0..vk.ic.len() {
+        vi = format!("{}{}vk.IC[{}] = Pairing.G1Point({});\n", vi, if vi.is_empty() { "" } else { "        " }, i, &p1_to_str(&vk.ic[i]));
+    }
+
+    let mut context = Context::new();
+    context
+        .set("vk_alfa1", p1_to_str(&vk.alpha_g1))
+        .set("vk_beta2", p2_to_str(&vk.beta_g2))
+        .set("vk_gamma2", p2_to_str(&vk.gamma_g2))
+        .set("vk_delta2", p2_to_str(&vk.delta_g2))
+        .set("vk_ic_length", vk.ic.len())
+        .set("vk_input_length", vk.ic.len() - 1)
+        .set("vk_ic_pts", vi);
+    context
+}
+
+fn inlined_context(vk: &VerifyingKey<Bn256>, variant: VerifierVariant) -> Context {
+    let mut context = Context::new();
+    let [alpha_x, alpha_y] = g1_coordinates(&vk.alpha_g1);
+    context
+        .set("prime_q", repr_to_big(Fq::char()))
+        .set("snark_scalar_field", repr_to_big(Fr::char()))
+        .set("vk_input_length", vk.ic.len() - 1)
+        .set("calldata_size", format!("0x{:x}", (8 + vk.ic.len() - 1) * 32))
+        .set("vk_alpha_x", alpha_x)
+        .set("vk_alpha_y", alpha_y);
+    set_g2(&mut context, "vk_beta", &vk.beta_g2);
+    set_g2(&mut context, "vk_gamma", &vk.gamma_g2);
+    set_g2(&mut context, "vk_delta", &vk.delta_g2);
+
+    let [ic0_x, ic0_y] = g1_coordinates(&vk.ic[0]);
+    let mut lines = vec![];
+    match variant {
+        VerifierVariant::Yul => {
+            lines.push(format!("            mstore(0x00, {})", ic0_x));
+            lines.push(format!("            mstore(0x20, {})", ic0_y));
+            for (i, ic) in vk.ic.iter().enumerate().skip(1) {
+                let [x, y] = g1_coordinates(ic);
+                lines.push(String::from("            {"));
+                lines.push(format!("                let s := calldataload(0x{:x})", (8 + i - 1) * 32));
+                lines.push(String::from("                if iszero(lt(s, r)) { revert(0, 0) }"));
+                lines.push(format!("                mstore(0x40, {})", x));
+                lines.push(format!("                mstore(0x60, {})", y));
+                lines.push(String::from("                mstore(0x80, s)"));
+                lines.push(String::from("                if iszero(staticcall(gas(), 7, 0x40, 0x60, 0x40, 0x40)) { revert(0, 0) }"));
+                lines.push(String::from("                if iszero(staticcall(gas(), 6, 0x00, 0x80, 0x00, 0x40)) { revert(0, 0) }"));
+                lines.push(String::from("            }"));
+            }
+        },
+        _ => {
+            lines.push(format!("        x = {};", ic0_x));
+            lines.push(format!("        y = {};", ic0_y));
+            for (i, ic) in vk.ic.iter().enumerate().skip(1) {
+                let [x, y] = g1_coordinates(ic);
+                lines.push(format!("        (x, y) = mulAdd(x, y, {}, {}, input[offset + {}]);", x, y, i - 1));
+            }
+        },
+    }
+    context.set("vk_ic_accumulate", lines.join("\n"));
+    context
+}
+
+/// Generates a verifier for the Groth16 verifying key.
+pub fn generate_verifier(vk: &VerifyingKey<Bn256>, options: &SolidityOptions) -> Result<String, SolidityError> {
+    let pragma = options.pragma.as_deref().unwrap_or(options.variant.default_pragma());
+    let version = pragma_lower_bound(pragma)
+        .ok_or_else(|| SolidityError::InvalidPragma(pragma.to_string()))?;
+    if version < options.variant.min_version() {
+        return Err(SolidityError::UnsupportedVersion { variant: options.variant, pragma: pragma.to_string() });
+    }
+    if options.batch && options.variant != VerifierVariant::Assembly {
+        return Err(SolidityError::BatchUnsupported(options.variant));
+    }
+
+    let (template, mut context) = match options.variant {
+        VerifierVariant::Legacy => (LEGACY_TEMPLATE, legacy_context(vk)),
+        VerifierVariant::Assembly => (ASSEMBLY_TEMPLATE, inlined_context(vk, options.variant)),
+        VerifierVariant::Yul => (YUL_TEMPLATE, inlined_context(vk, options.variant)),
+    };
+    context
+        .set("pragma", pragma)
+        .set_flag("batch", options.batch);
+
+    Ok(template::render(template, &context)?)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use bellman_ce::pairing::{CurveProjective, bn256::{G1, G2}};
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    pub(crate) fn verifying_key(num_inputs: usize) -> VerifyingKey<Bn256> {
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        VerifyingKey {
+            alpha_g1: G1::rand(rng).into_affine(),
+            beta_g1: G1::rand(rng).into_affine(),
+            beta_g2: G2::rand(rng).into_affine(),
+            gamma_g2: G2::rand(rng).into_affine(),
+            delta_g1: G1::rand(rng).into_affine(),
+            delta_g2: G2::rand(rng).into_affine(),
+            ic: (0..num_inputs + 1).map(|_| G1::rand(rng).into_affine()).collect(),
+        }
+    }
+
+    /// The verifier `create_verifier_sol` generated before the template
+    /// engine, by replacing the tags of the `^0.6.0` template one by one.
+    fn replaced_verifier(vk: &VerifyingKey<Bn256>) -> String {
+        let template = LEGACY_TEMPLATE.replace("<%pragma%>", "^0.6.0");
+
+        let p1_to_str = |p: &<Bn256 as Engine>::G1Affine| {
+            let x = repr_to_big(p.get_x().into_repr());
+            let y = repr_to_big(p.get_y().into_repr());
+            format!("uint256({}), uint256({})", x, y)
+        };
+        let p2_to_str = |p: &<Bn256 as Engine>::G2Affine| {
+            let x = p.get_x();
+            let y = p.get_y();
+            let x_c0 = repr_to_big(x.c0.into_repr());
+            let x_c1 = repr_to_big(x.c1.into_repr());
+            let y_c0 = repr_to_big(y.c0.into_repr());
+            let y_c1 = repr_to_big(y.c1.into_repr());
+            format!("[uint256({}), uint256({})], [uint256({}), uint256({})]", x_c1, x_c0, y_c1, y_c0)
+        };
+
+        let template = template.replace("<%vk_alfa1%>", &p1_to_str(&vk.alpha_g1));
+        let template = template.replace("<%vk_beta2%>", &p2_to_str(&vk.beta_g2));
+        let template = template.replace("<%vk_gamma2%>", &p2_to_str(&vk.gamma_g2));
+        let template = template.replace("<%vk_delta2%>", &p2_to_str(&vk.delta_g2));
+
+        let template = template.replace("<%vk_ic_length%>", &vk.ic.len().to_string());
+        let template = template.replace("<%vk_input_length%>", &(vk.ic.len() - 1).to_string());
+
+        let mut vi = String::from("");
+        for i in 0..vk.ic.len() {
+            vi = format!("{}{}vk.IC[{}] = Pairing.G1Point({});\n", vi, if vi.is_empty() { "" } else { "        " }, i, &p1_to_str(&vk.ic[i]));
+        }
+        template.replace("<%vk_ic_pts%>", &vi)
+    }
+
+    #[test]
+    fn test_default_matches_replaced_template() {
+        for &num_inputs in &[1, 3] {
+            let vk = verifying_key(num_inputs);
+            let generated = generate_verifier(&vk, &SolidityOptions::default()).unwrap();
+            assert!(!generated.contains("<%"));
+            assert_eq!(generated, replaced_verifier(&vk));
+        }
+    }
+
+    #[test]
+    fn test_pragma_lower_bound() {
+        assert_eq!(pragma_lower_bound("^0.6.0"), Some((0, 6, 0)));
+        assert_eq!(pragma_lower_bound("0.8.17"), Some((0, 8, 17)));
+        assert_eq!(pragma_lower_bound("=0.8.17"), Some((0, 8, 17)));
+        assert_eq!(pragma_lower_bound(">=0.7.5 <0.9.0"), Some((0, 7, 5)));
+        assert_eq!(pragma_lower_bound(">= 0.7.5 < 0.9.0"), Some((0, 7, 5)));
+        assert_eq!(pragma_lower_bound("<0.9.0 >=0.7.5"), Some((0, 7, 5)));
+        assert_eq!(pragma_lower_bound(">0.7.4"), Some((0, 7, 5)));
+        assert_eq!(pragma_lower_bound("~0.8.1"), Some((0, 8, 1)));
+        // Upper bounds alone allow every older version.
+        assert_eq!(pragma_lower_bound("<0.9.0"), Some((0, 0, 0)));
+        assert_eq!(pragma_lower_bound("<=0.8.0"), Some((0, 0, 0)));
+
+        // Empty ranges.
+        assert_eq!(pragma_lower_bound(">=0.8.0 <0.8.0"), None);
+        assert_eq!(pragma_lower_bound("^0.6.0 >=0.7.0"), None);
+        assert_eq!(pragma_lower_bound("^1.2.0 >=2.0.0"), None);
+        // Unsupported syntax.
+        for pragma in &["", "solidity", "^0.8", "0.8.0.1", "^0.6.0 || ^0.8.0", "!0.8.0"] {
+            assert_eq!(pragma_lower_bound(pragma), None, "{:?}", pragma);
+        }
+    }
+
+    #[test]
+    fn test_pragma_checks() {
+        let vk = verifying_key(1);
+        let options = |pragma: &str, variant| SolidityOptions { pragma: Some(pragma.to_string()), variant, batch: false };
+
+        for &variant in &[VerifierVariant::Legacy, VerifierVariant::Assembly] {
+            let generated = generate_verifier(&vk, &SolidityOptions { pragma: None, variant, batch: false }).unwrap();
+            assert!(generated.contains(&format!("pragma solidity {};", variant.default_pragma())));
+        }
+        let generated = generate_verifier(&vk, &options(">=0.8.0 <0.9.0", VerifierVariant::Assembly)).unwrap();
+        assert!(generated.contains("pragma solidity >=0.8.0 <0.9.0;"));
+        assert!(generate_verifier(&vk, &options(">0.7.4", VerifierVariant::Assembly)).is_ok());
+        let generated = generate_verifier(&vk, &SolidityOptions { pragma: None, variant: VerifierVariant::Yul, batch: false }).unwrap();
+        assert!(generated.contains(&format!("of a version matching\n * {}.", VerifierVariant::Yul.default_pragma())));
+
+        for &(pragma, variant) in &[("^0.6.0", VerifierVariant::Assembly), ("<0.9.0", VerifierVariant::Legacy), (">=0.5.0 <0.8.0", VerifierVariant::Legacy), ("^0.6.0", VerifierVariant::Yul)] {
+            match generate_verifier(&vk, &options(pragma, variant)) {
+                Err(SolidityError::UnsupportedVersion { .. }) => {},
+                _ => panic!("unsupported pragma {:?} accepted for the {} verifier", pragma, variant),
+            }
+        }
+        for &variant in &[VerifierVariant::Legacy, VerifierVariant::Yul] {
+            match generate_verifier(&vk, &options("^0.6.0 || ^0.8.0", variant)) {
+                Err(SolidityError::InvalidPragma(_)) => {},
+                _ => panic!("invalid pragma accepted for the {} verifier", variant),
+            }
+        }
+    }
+
+    /// The verifiers of `verifying_key(2)` in `testdata`, for the inlined variants.
+    fn golden_verifiers() -> Vec<(SolidityOptions, &'static str, &'static str)> {
+        let options = |variant, batch| SolidityOptions { pragma: None, variant, batch };
+        vec![
+            (options(VerifierVariant::Assembly, false), "verifier_asm.sol", include_str!("../testdata/verifier_asm.sol")),
+            (options(VerifierVariant::Assembly, true), "verifier_asm_batch.sol", include_str!("../testdata/verifier_asm_batch.sol")),
+            (options(VerifierVariant::Yul, false), "verifier.yul", include_str!("../testdata/verifier.yul")),
+        ]
+    }
+
+    #[test]
+    fn test_golden_verifiers() {
+        let vk = verifying_key(2);
+        for (options, name, expected) in golden_verifiers() {
+            let generated = generate_verifier(&vk, &options).unwrap();
+            assert!(generated == expected, "{} differs from the generated verifier", name);
+        }
+    }
+
+    /// Compiles a verifier with solc, set SOLC to its path if it isn't on
+    /// PATH, and returns the bytecode deploying it.
+    fn compile(source: &str, name: &str, variant: VerifierVariant) -> Vec<u8> {
+        use std::process::Command;
+
+        let solc = std::env::var("SOLC").unwrap_or_else(|_| String::from("solc"));
+        let path = std::env::temp_dir().join(format!("{}_{}", std::process::id(), name));
+        std::fs::write(&path, source).unwrap();
+        let mut command = Command::new(&solc);
+        if variant == VerifierVariant::Yul {
+            command.arg("--strict-assembly");
+        }
+        let output = command.arg("--bin").arg(&path).output().expect("unable to run solc");
+        std::fs::remove_file(&path).unwrap();
+        assert!(output.status.success(), "{} doesn't compile: {}", name, String::from_utf8_lossy(&output.stderr));
+
+        // The binary of the last contract, `Verifier` comes after `Pairing`.
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines = stdout.lines().collect::<Vec<_>>();
+        let binary = lines.iter().rposition(|line| line.starts_with("Binary")).expect("solc printed no binary");
+        hex::decode(lines[binary + 1].trim()).unwrap()
+    }
+
+    /// The legacy verifier of `vk`, for the same Solidity versions as the
+    /// other variants so that a single solc compiles all of them.
+    fn legacy_verifier(vk: &VerifyingKey<Bn256>) -> String {
+        let options = SolidityOptions { pragma: Some(String::from(">=0.7.5 <0.9.0")), variant: VerifierVariant::Legacy, batch: false };
+        generate_verifier(vk, &options).unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs solc, set SOLC to its path if it isn't on PATH"]
+    fn test_golden_verifiers_compile() {
+        for (options, name, source) in golden_verifiers() {
+            compile(source, name, options.variant);
+        }
+        compile(&legacy_verifier(&verifying_key(2)), "verifier.sol", VerifierVariant::Legacy);
+    }
+
+    #[test]
+    #[ignore = "needs solc, set SOLC to its path if it isn't on PATH"]
+    fn test_verifiers_in_evm() {
+        use bellman_ce::pairing::ff::Field;
+        use calldata::{encode_verify_proof, encode_verify_proofs};
+        use circom_circuit::{prove, tests::multiplier_circuit};
+        use parameters::MPCParameters;
+        use phase1::tests::TestPowers;
+        use revm::{
+            db::{CacheDB, EmptyDB},
+            primitives::{Bytes, ExecutionResult, Output, TxKind},
+            Evm,
+        };
+
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let circuit = multiplier_circuit();
+        let mut params = MPCParameters::new_with_source(circuit.clone(), false, &mut TestPowers::new(3)).unwrap();
+        params.contribute(&mut rng, &0);
+        let inputs = circuit.get_public_inputs().unwrap();
+        let proofs = (0..3)
+            .map(|_| (prove(circuit.clone(), params.get_params(), &mut rng).unwrap(), inputs.clone()))
+            .collect::<Vec<_>>();
+        let proof = &proofs[0].0;
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[0].add_assign(&Fr::one());
+        let mut wrong_proofs = proofs.clone();
+        wrong_proofs[1].1 = wrong_inputs.clone();
+
+        let vk = &params.get_params().vk;
+        let options = |variant, batch| SolidityOptions { pragma: None, variant, batch };
+        let verifiers = vec![
+            (VerifierVariant::Legacy, false, "evm_verifier.sol", legacy_verifier(vk)),
+            (VerifierVariant::Assembly, false, "evm_verifier_asm.sol", generate_verifier(vk, &options(VerifierVariant::Assembly, false)).unwrap()),
+            (VerifierVariant::Assembly, true, "evm_verifier_asm_batch.sol", generate_verifier(vk, &options(VerifierVariant::Assembly, true)).unwrap()),
+            (VerifierVariant::Yul, false, "evm_verifier.yul", generate_verifier(vk, &options(VerifierVariant::Yul, false)).unwrap()),
+        ];
+        for (variant, batch, name, source) in verifiers {
+            let mut evm = Evm::builder().with_db(CacheDB::new(EmptyDB::default())).build();
+            let mut transact = |kind, data: Vec<u8>| {
+                let tx = evm.tx_mut();
+                tx.transact_to = kind;
+                tx.data = Bytes::from(data);
+                tx.gas_limit = 10_000_000;
+                match evm.transact_commit().unwrap() {
+                    ExecutionResult::Success { output, .. } => output,
+                    result => panic!("the {} verifier failed: {:?}", name, result),
+                }
+            };
+
+            let address = match transact(TxKind::Create, compile(&source, name, variant)) {
+                Output::Create(_, Some(address)) => address,
+                output => panic!("the {} verifier wasn't deployed: {:?}", name, output),
+            };
+            let mut call = |data| match transact(TxKind::Call(address), data) {
+                Output::Call(output) => output.to_vec(),
+                output => panic!("unexpected output of the {} verifier: {:?}", name, output),
+            };
+
+            let mut valid = [0u8; 32];
+            valid[31] = 1;
+            assert_eq!(call(encode_verify_proof(proof, &inputs, variant)), valid, "the {} verifier rejects a valid proof", name);
+            assert_eq!(call(encode_verify_proof(proof, &wrong_inputs, variant)), [0u8; 32], "the {} verifier accepts a proof of other inputs", name);
+            if batch {
+                assert_eq!(call(encode_verify_proofs(&proofs)), valid, "the {} verifier rejects valid proofs", name);
+                assert_eq!(call(encode_verify_proofs(&wrong_proofs)), [0u8; 32], "the {} verifier accepts a batch with a proof of other inputs", name);
+            }
+        }
+    }
+}
@@ -0,0 +1,819 @@
+extern crate bellman_ce;
+extern crate byteorder;
+extern crate rand;
+
+#[cfg(feature = "wasm")]
+use bellman_ce::singlecore::Worker;
+#[cfg(not(feature = "wasm"))]
+use bellman_ce::multicore::Worker;
+
+use std::cmp;
+use std::io::{self, Read, Write, Seek, SeekFrom};
+use std::ops::Range;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use bellman_ce::{
+    Circuit,
+    SynthesisError,
+    groth16::VerifyingKey,
+    pairing::{
+        CurveAffine,
+        CurveProjective,
+        EncodedPoint,
+        ff::Field,
+        bn256::{
+            Bn256,
+            Fr,
+            G1,
+            G1Affine,
+            G2Affine,
+            G1Uncompressed,
+            G2Uncompressed,
+        }
+    }
+};
+
+use rand::Rng;
+
+use hash_writer::HashWriter;
+use keypair::PublicKey;
+use parameters::{
+    assemble_keypair,
+    batch_exp,
+    keypair_for_transcript,
+    check_transcript_extension,
+    check_unchanged_keys,
+    check_new_contribution,
+    contribution_hash,
+    verify_transcript,
+    Phase2VerificationError,
+};
+use phase1::Phase1Source;
+use utils::{merge_pairs_projective, same_ratio};
+
+/// Number of points kept in memory at once when none is specified.
+pub const DEFAULT_BATCH_SIZE: usize = 1 << 20;
+
+/// Where the parts of a serialized `MPCParameters` are, so that the
+/// H and L queries can be processed without loading them in memory.
+struct ParametersLayout {
+    vk: VerifyingKey<Bn256>,
+    /// Offset of the H query, the L query follows it
+    h_offset: u64,
+    h_len: usize,
+    l_len: usize,
+    /// Sizes of the A, B G1 and B G2 queries, which contributions leave
+    /// unchanged, including their length prefix
+    a_size: u64,
+    b_g1_size: u64,
+    b_g2_size: u64,
+    cs_hash: [u8; 64],
+    contributions: Vec<PublicKey>,
+}
+
+impl ParametersLayout {
+    fn read<R: Read + Seek>(reader: &mut R) -> io::Result<ParametersLayout> {
+        fn skip_query<R: Read + Seek>(reader: &mut R, point_size: usize) -> io::Result<(usize, u64)> {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            let size = (len * point_size) as u64;
+            reader.seek(SeekFrom::Current(size as i64))?;
+            Ok((len, 4 + size))
+        }
+
+        reader.seek(SeekFrom::Start(0))?;
+        let vk = VerifyingKey::read(&mut *reader)?;
+        let h_offset = reader.stream_position()?;
+
+        let (h_len, _) = skip_query(reader, G1Uncompressed::size())?;
+        let (l_len, _) = skip_query(reader, G1Uncompressed::size())?;
+        let (_, a_size) = skip_query(reader, G1Uncompressed::size())?;
+        let (_, b_g1_size) = skip_query(reader, G1Uncompressed::size())?;
+        let (_, b_g2_size) = skip_query(reader, G2Uncompressed::size())?;
+
+        let mut cs_hash = [0u8; 64];
+        reader.read_exact(&mut cs_hash)?;
+
+        let contributions_len = reader.read_u32::<BigEndian>()? as usize;
+        let mut contributions = vec![];
+        for _ in 0..contributions_len {
+            contributions.push(PublicKey::read(&mut *reader)?);
+        }
+
+        Ok(ParametersLayout {
+            vk,
+            h_offset,
+            h_len,
+            l_len,
+            a_size,
+            b_g1_size,
+            b_g2_size,
+            cs_hash,
+            contributions,
+        })
+    }
+
+    /// Offset of the A query, the B queries follow it
+    fn a_offset(&self) -> u64 {
+        self.h_offset + 8 + ((self.h_len + self.l_len) * G1Uncompressed::size()) as u64
+    }
+
+    fn rest_size(&self) -> u64 {
+        self.a_size + self.b_g1_size + self.b_g2_size
+    }
+}
+
+/// The verifying key, the query lengths and the transcript of serialized
+/// `MPCParameters`, everything but the points of the queries.
+#[derive(Clone)]
+pub struct ParametersHeader {
+    pub vk: VerifyingKey<Bn256>,
+    pub h_len: usize,
+    pub l_len: usize,
+    pub a_len: usize,
+    pub b_g1_len: usize,
+    pub b_g2_len: usize,
+    pub cs_hash: [u8; 64],
+    pub contributions: Vec<PublicKey>,
+}
+
+/// Reads the header of `MPCParameters` from the start of `reader`,
+/// seeking over the queries instead of reading them.
+pub fn read_header<R: Read + Seek>(mut reader: R) -> io::Result<ParametersHeader> {
+    let layout = ParametersLayout::read(&mut reader)?;
+    if layout.vk.ic.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "verifying key has no IC"));
+    }
+    let len = |size: u64, point_size: usize| ((size - 4) / point_size as u64) as usize;
+
+    Ok(ParametersHeader {
+        h_len: layout.h_len,
+        l_len: layout.l_len,
+        a_len: len(layout.a_size, G1Uncompressed::size()),
+        b_g1_len: len(layout.b_g1_size, G1Uncompressed::size()),
+        b_g2_len: len(layout.b_g2_size, G2Uncompressed::size()),
+        vk: layout.vk,
+        cs_hash: layout.cs_hash,
+        contributions: layout.contributions,
+    })
+}
+
+fn read_g1<R: Read>(reader: &mut R) -> io::Result<G1Affine> {
+    let mut repr = G1Uncompressed::empty();
+    reader.read_exact(repr.as_mut())?;
+    repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn read_g1_batch<R: Read>(reader: &mut R, size: usize) -> io::Result<Vec<G1Affine>> {
+    (0..size).map(|_| read_g1(reader)).collect()
+}
+
+/// Batches must hold at least one point, or the H and L queries would
+/// never be consumed.
+fn check_batch_size(batch_size: usize) -> io::Result<()> {
+    if batch_size == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "batch size must be at least 1"));
+    }
+    Ok(())
+}
+
+/// Compares the next `size` bytes of `a` and `b`.
+fn same_bytes<A: Read, B: Read>(a: &mut A, b: &mut B, mut size: u64) -> io::Result<bool> {
+    let mut buf_a = [0u8; 8192];
+    let mut buf_b = [0u8; 8192];
+    while size > 0 {
+        let len = cmp::min(size, buf_a.len() as u64) as usize;
+        a.read_exact(&mut buf_a[..len])?;
+        b.read_exact(&mut buf_b[..len])?;
+        if buf_a[..len] != buf_b[..len] {
+            return Ok(false);
+        }
+        size -= len as u64;
+    }
+    Ok(true)
+}
+
+/// Same as `MPCParameters::contribute` followed by `MPCParameters::write`,
+/// but keeps at most `batch_size` points of the H and L queries in memory.
+/// `input` is read from its start and is better buffered, the output is
+/// identical to the one of the in-memory contribution for the same `rng`.
+/// Fails with `InvalidInput` if `batch_size` is 0.
+pub fn contribute<R: Read + Seek, W: Write, G: Rng>(
+    mut input: R,
+    mut output: W,
+    rng: &mut G,
+    batch_size: usize,
+    progress_update_interval: &u32
+) -> io::Result<[u8; 64]>
+{
+    check_batch_size(batch_size)?;
+
+    let layout = ParametersLayout::read(&mut input)?;
+
+    let (pubkey, privkey) = keypair_for_transcript(rng, &layout.cs_hash, &layout.contributions, &layout.vk.delta_g1);
+    let delta_inv = privkey.delta.inverse().expect("nonzero");
+
+    let mut vk = layout.vk.clone();
+    vk.delta_g1 = vk.delta_g1.mul(privkey.delta).into_affine();
+    vk.delta_g2 = vk.delta_g2.mul(privkey.delta).into_affine();
+    vk.write(&mut output)?;
+
+    input.seek(SeekFrom::Start(layout.h_offset))?;
+    let total_exps = (layout.h_len + layout.l_len) as u32;
+
+    // H and L queries are rescaled by delta^-1, L following H
+    for _ in 0..2 {
+        let len = input.read_u32::<BigEndian>()? as usize;
+        output.write_u32::<BigEndian>(len as u32)?;
+
+        let mut remaining = len;
+        while remaining > 0 {
+            let size = cmp::min(batch_size, remaining);
+            let mut batch = read_g1_batch(&mut input, size)?;
+            batch_exp(&mut batch, delta_inv, progress_update_interval, &total_exps);
+            for point in &batch {
+                output.write_all(point.into_uncompressed().as_ref())?;
+            }
+            remaining -= size;
+        }
+    }
+
+    io::copy(&mut (&mut input).take(layout.rest_size()), &mut output)?;
+
+    output.write_all(&layout.cs_hash)?;
+    output.write_u32::<BigEndian>(layout.contributions.len() as u32 + 1)?;
+    for contribution in layout.contributions.iter().chain(Some(&pubkey)) {
+        contribution.write(&mut output)?;
+    }
+
+    // Calculate the hash of the public key and return it
+    let sink = io::sink();
+    let mut sink = HashWriter::new(sink);
+    pubkey.write(&mut sink)?;
+    Ok(sink.into_hash())
+}
+
+/// Same as `parameters::verify_contribution`, but reads `before` and
+/// `after` from their start and keeps at most `batch_size` points of each
+/// of their H and L queries in memory. The random linear combinations
+/// checking that H and L were rescaled by delta^-1 are accumulated batch
+/// by batch. Returns the hash of the contribution. Fails with an
+/// `InvalidInput` `IoError` if `batch_size` is 0.
+pub fn verify_contribution<B: Read + Seek, A: Read + Seek>(
+    mut before: B,
+    mut after: A,
+    batch_size: usize
+) -> Result<[u8; 64], Phase2VerificationError>
+{
+    check_batch_size(batch_size)?;
+
+    let old = ParametersLayout::read(&mut before)?;
+    let new = ParametersLayout::read(&mut after)?;
+
+    check_transcript_extension(&old.contributions, &new.contributions)?;
+
+    check_unchanged_parts(&mut before, &old, &mut after, &new)?;
+
+    let pubkey = check_new_contribution(&old.cs_hash, &old.contributions, &new.contributions, &old.vk, &new.vk)?;
+
+    check_rescaled(&mut before, &old, &mut after, &new, batch_size)?;
+
+    Ok(contribution_hash(pubkey))
+}
+
+/// Same as `MPCParameters::verify_with_source`, but reads `params` from
+/// its start and keeps at most `batch_size` points of each of its queries
+/// and of the initial ones in memory. The initial parameters aren't built:
+/// their L, A and B queries are evaluated `batch_size` variables at a time
+/// and compared with `params` while they are hashed into the cs_hash, only
+/// the constraint system and the phase 1 parameters of its domain are held
+/// whole. Fails with an `InvalidInput` `IoError` if `batch_size` is 0.
+pub fn verify<R, C, S>(
+    mut params: R,
+    circuit: C,
+    should_filter_points_at_infinity: bool,
+    phase1: &mut S,
+    batch_size: usize
+) -> Result<Vec<[u8; 64]>, Phase2VerificationError>
+    where R: Read + Seek, C: Circuit<Bn256>, S: Phase1Source + ?Sized
+{
+    check_batch_size(batch_size)?;
+
+    let new = ParametersLayout::read(&mut params)?;
+
+    let (assembly, exp) = assemble_keypair(circuit)?;
+    let radix = phase1.radix(exp)?;
+    let worker = Worker::new();
+
+    let vk = VerifyingKey {
+        alpha_g1: radix.alpha_g1,
+        beta_g1: radix.beta_g1,
+        beta_g2: radix.beta_g2,
+        gamma_g2: G2Affine::one(),
+        delta_g1: G1Affine::one(),
+        delta_g2: G2Affine::one(),
+        ic: eval_query(&[
+            (&assembly.at_inputs[..], &radix.beta_coeffs_g1[..]),
+            (&assembly.bt_inputs[..], &radix.alpha_coeffs_g1[..]),
+            (&assembly.ct_inputs[..], &radix.coeffs_g1[..]),
+        ], &worker),
+    };
+
+    // H/L will change, but should have same length
+    if radix.h.len() != new.h_len {
+        return Err(Phase2VerificationError::HLengthMismatch {
+            expected: radix.h.len(),
+            actual: new.h_len,
+        });
+    }
+    if assembly.num_aux != new.l_len {
+        return Err(Phase2VerificationError::LLengthMismatch {
+            expected: assembly.num_aux,
+            actual: new.l_len,
+        });
+    }
+
+    // The initial parameters are hashed in the order `MPCParameters::write`
+    // writes them, the rescaling of H and L is only checked once the
+    // transcript is known to be valid
+    let mut hasher = HashWriter::new(io::sink());
+    vk.write(&mut hasher)?;
+
+    params.seek(SeekFrom::Start(new.h_offset))?;
+    let h_pairs = merge_generated_pairs(&mut params, &mut hasher, batch_size, |range| Ok(radix.h[range].to_vec()))?;
+    let l_pairs = merge_generated_pairs(&mut params, &mut hasher, batch_size, |range| {
+        let l = eval_query(&[
+            (&assembly.at_aux[range.clone()], &radix.beta_coeffs_g1[..]),
+            (&assembly.bt_aux[range.clone()], &radix.alpha_coeffs_g1[..]),
+            (&assembly.ct_aux[range], &radix.coeffs_g1[..]),
+        ], &worker);
+
+        // Don't allow any elements be unconstrained, so that
+        // the L query is always fully dense.
+        if l.iter().any(|e| e.is_zero()) {
+            return Err(SynthesisError::UnconstrainedVariable.into());
+        }
+        Ok(l)
+    })?;
+
+    // A/B_G1/B_G2 doesn't change at all
+    let num_variables = assembly.num_inputs + assembly.num_aux;
+    let same_a = same_generated_query(&mut params, &mut hasher, batch_size, num_variables, |range| {
+        eval_variable_query(&assembly.at_inputs, &assembly.at_aux, range, &radix.coeffs_g1, should_filter_points_at_infinity, &worker)
+    })?;
+    if !same_a {
+        return Err(Phase2VerificationError::AQueryChanged);
+    }
+    let same_b_g1 = same_generated_query(&mut params, &mut hasher, batch_size, num_variables, |range| {
+        eval_variable_query(&assembly.bt_inputs, &assembly.bt_aux, range, &radix.coeffs_g1, should_filter_points_at_infinity, &worker)
+    })?;
+    if !same_b_g1 {
+        return Err(Phase2VerificationError::BG1QueryChanged);
+    }
+    let same_b_g2 = same_generated_query(&mut params, &mut hasher, batch_size, num_variables, |range| {
+        eval_variable_query(&assembly.bt_inputs, &assembly.bt_aux, range, &radix.coeffs_g2, should_filter_points_at_infinity, &worker)
+    })?;
+    if !same_b_g2 {
+        return Err(Phase2VerificationError::BG2QueryChanged);
+    }
+
+    let mut cs_hash = [0; 64];
+    cs_hash.copy_from_slice(hasher.into_hash().as_ref());
+    check_unchanged_keys(&vk, &new.vk, &cs_hash, &new.cs_hash)?;
+
+    let (result, current_delta) = verify_transcript(&cs_hash, &new.contributions)?;
+
+    // Current parameters should have consistent delta in G1
+    if current_delta != new.vk.delta_g1 {
+        return Err(Phase2VerificationError::DeltaG1Mismatch);
+    }
+
+    // Current parameters should have consistent delta in G2
+    if !same_ratio(
+        (G1Affine::one(), current_delta),
+        (G2Affine::one(), new.vk.delta_g2)
+    ) {
+        return Err(Phase2VerificationError::DeltaG2Mismatch);
+    }
+
+    // H and L queries should be updated with delta^-1
+    if !same_ratio(h_pairs, (new.vk.delta_g2, vk.delta_g2)) { // reversed for inverse
+        return Err(Phase2VerificationError::HQueryNotRescaled);
+    }
+    if !same_ratio(l_pairs, (new.vk.delta_g2, vk.delta_g2)) { // reversed for inverse
+        return Err(Phase2VerificationError::LQueryNotRescaled);
+    }
+
+    Ok(result)
+}
+
+/// The polynomials of some variables as in `KeypairAssembly`, with the
+/// points of the domain they are nonzero at and their coefficients there.
+type Polynomials<'a> = &'a [Vec<(Fr, usize)>];
+
+/// Evaluates, for each variable, the sum of the bases of each term at
+/// the points of its polynomial, scaled by their coefficients.
+fn eval_query<G: CurveAffine<Scalar = Fr>>(
+    terms: &[(Polynomials, &[G])],
+    worker: &Worker
+) -> Vec<G>
+{
+    let len = terms.first().map_or(0, |(polynomials, _)| polynomials.len());
+    let mut query = vec![G::Projective::zero(); len];
+
+    worker.scope(len, |scope, chunk| {
+        for (index, query) in query.chunks_mut(chunk).enumerate() {
+            scope.spawn(move |_| {
+                for (offset, point) in query.iter_mut().enumerate() {
+                    for &(polynomials, bases) in terms {
+                        for &(coeff, lag) in &polynomials[index * chunk + offset] {
+                            point.add_assign(&bases[lag].mul(coeff));
+                        }
+                    }
+                }
+
+                G::Projective::batch_normalization(query);
+            });
+        }
+    });
+
+    query.into_iter().map(|e| e.into_affine()).collect()
+}
+
+/// `eval_query` of a single polynomial for the variables in `range`, the
+/// inputs being numbered before the auxiliary variables, without the
+/// points at infinity if they are filtered.
+fn eval_variable_query<G: CurveAffine<Scalar = Fr>>(
+    inputs: Polynomials,
+    aux: Polynomials,
+    range: Range<usize>,
+    bases: &[G],
+    should_filter_points_at_infinity: bool,
+    worker: &Worker
+) -> Vec<G>
+{
+    let num_inputs = inputs.len();
+    let input_range = cmp::min(range.start, num_inputs)..cmp::min(range.end, num_inputs);
+    let aux_range = cmp::max(range.start, num_inputs) - num_inputs..cmp::max(range.end, num_inputs) - num_inputs;
+
+    let mut query = eval_query(&[(&inputs[input_range], bases)], worker);
+    query.extend(eval_query(&[(&aux[aux_range], bases)], worker));
+    if should_filter_points_at_infinity {
+        query.retain(|e| !e.is_zero());
+    }
+    query
+}
+
+/// `merge_pairs` over the initial query `before` returns for ranges of
+/// at most `batch_size` indices and the next query of `after`, which must
+/// have the same length. The initial query is hashed the way
+/// `MPCParameters::write` writes it.
+fn merge_generated_pairs<A, W, F>(
+    after: &mut A,
+    hasher: &mut W,
+    batch_size: usize,
+    mut before: F
+) -> Result<(G1Affine, G1Affine), Phase2VerificationError>
+    where A: Read, W: Write, F: FnMut(Range<usize>) -> Result<Vec<G1Affine>, Phase2VerificationError>
+{
+    let len = after.read_u32::<BigEndian>()? as usize;
+    hasher.write_u32::<BigEndian>(len as u32)?;
+
+    let mut s = G1::zero();
+    let mut sx = G1::zero();
+    let mut start = 0;
+    while start < len {
+        let end = cmp::min(start + batch_size, len);
+        let batch = before(start..end)?;
+        for point in &batch {
+            hasher.write_all(point.into_uncompressed().as_ref())?;
+        }
+        let (batch_s, batch_sx) = merge_pairs_projective(&batch, &read_g1_batch(after, end - start)?);
+        s.add_assign(&batch_s);
+        sx.add_assign(&batch_sx);
+        start = end;
+    }
+
+    Ok((s.into_affine(), sx.into_affine()))
+}
+
+/// Compares the next query of `after` with the initial query `before`
+/// returns for ranges of at most `batch_size` of the `num_variables`
+/// variables. The initial query is hashed the way `MPCParameters::write`
+/// writes it, as long as the queries are the same.
+fn same_generated_query<A, W, G, F>(
+    after: &mut A,
+    hasher: &mut W,
+    batch_size: usize,
+    num_variables: usize,
+    mut before: F
+) -> io::Result<bool>
+    where A: Read, W: Write, G: CurveAffine, F: FnMut(Range<usize>) -> Vec<G>
+{
+    let len = after.read_u32::<BigEndian>()? as usize;
+    hasher.write_u32::<BigEndian>(len as u32)?;
+
+    let mut remaining = len;
+    let mut expected = G::Uncompressed::empty();
+    let mut start = 0;
+    while start < num_variables {
+        let end = cmp::min(start + batch_size, num_variables);
+        for point in before(start..end) {
+            if remaining == 0 {
+                return Ok(false);
+            }
+            let encoded = point.into_uncompressed();
+            after.read_exact(expected.as_mut())?;
+            if encoded.as_ref() != expected.as_ref() {
+                return Ok(false);
+            }
+            hasher.write_all(encoded.as_ref())?;
+            remaining -= 1;
+        }
+        start = end;
+    }
+
+    Ok(remaining == 0)
+}
+
+/// Checks the parts of the parameters which contributions must not
+/// touch, see `parameters::check_unchanged_parts`.
+fn check_unchanged_parts<B: Read + Seek, A: Read + Seek>(
+    before: &mut B,
+    old: &ParametersLayout,
+    after: &mut A,
+    new: &ParametersLayout
+) -> Result<(), Phase2VerificationError>
+{
+    // H/L will change, but should have same length
+    if old.h_len != new.h_len {
+        return Err(Phase2VerificationError::HLengthMismatch {
+            expected: old.h_len,
+            actual: new.h_len,
+        });
+    }
+    if old.l_len != new.l_len {
+        return Err(Phase2VerificationError::LLengthMismatch {
+            expected: old.l_len,
+            actual: new.l_len,
+        });
+    }
+
+    // A/B_G1/B_G2 doesn't change at all
+    before.seek(SeekFrom::Start(old.a_offset()))?;
+    after.seek(SeekFrom::Start(new.a_offset()))?;
+    if old.a_size != new.a_size || !same_bytes(before, after, old.a_size)? {
+        return Err(Phase2VerificationError::AQueryChanged);
+    }
+    if old.b_g1_size != new.b_g1_size || !same_bytes(before, after, old.b_g1_size)? {
+        return Err(Phase2VerificationError::BG1QueryChanged);
+    }
+    if old.b_g2_size != new.b_g2_size || !same_bytes(before, after, old.b_g2_size)? {
+        return Err(Phase2VerificationError::BG2QueryChanged);
+    }
+
+    check_unchanged_keys(&old.vk, &new.vk, &old.cs_hash, &new.cs_hash)
+}
+
+/// Checks that the H and L queries of `after` are those of `before`
+/// rescaled by the ratio of their delta^-1.
+fn check_rescaled<B: Read + Seek, A: Read + Seek>(
+    before: &mut B,
+    old: &ParametersLayout,
+    after: &mut A,
+    new: &ParametersLayout,
+    batch_size: usize
+) -> Result<(), Phase2VerificationError>
+{
+    // H and L queries should be updated with delta^-1, L following H
+    before.seek(SeekFrom::Start(old.h_offset))?;
+    after.seek(SeekFrom::Start(new.h_offset))?;
+    if !same_ratio(
+        merge_pairs_in_batches(before, after, batch_size)?,
+        (new.vk.delta_g2, old.vk.delta_g2) // reversed for inverse
+    ) {
+        return Err(Phase2VerificationError::HQueryNotRescaled);
+    }
+
+    if !same_ratio(
+        merge_pairs_in_batches(before, after, batch_size)?,
+        (new.vk.delta_g2, old.vk.delta_g2) // reversed for inverse
+    ) {
+        return Err(Phase2VerificationError::LQueryNotRescaled);
+    }
+
+    Ok(())
+}
+
+/// `merge_pairs` over the next query of `before` and `after`, which must
+/// have the same length, reading `batch_size` points of each at a time.
+fn merge_pairs_in_batches<B: Read, A: Read>(
+    before: &mut B,
+    after: &mut A,
+    batch_size: usize
+) -> io::Result<(G1Affine, G1Affine)>
+{
+    let len = before.read_u32::<BigEndian>()? as usize;
+    after.read_u32::<BigEndian>()?;
+
+    let mut s = G1::zero();
+    let mut sx = G1::zero();
+    let mut remaining = len;
+    while remaining > 0 {
+        let size = cmp::min(batch_size, remaining);
+        let (batch_s, batch_sx) = merge_pairs_projective(
+            &read_g1_batch(before, size)?,
+            &read_g1_batch(after, size)?
+        );
+        s.add_assign(&batch_s);
+        sx.add_assign(&batch_sx);
+        remaining -= size;
+    }
+
+    Ok((s.into_affine(), sx.into_affine()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use circom_circuit::tests::multiplier_circuit;
+    use parameters::MPCParameters;
+    use phase1::tests::TestPowers;
+    use bellman_ce::pairing::bn256::Fr;
+    use bellman_ce::pairing::ff::PrimeField;
+    use rand::{SeedableRng, XorShiftRng};
+    use std::io::Cursor;
+
+    fn seeded_rng() -> XorShiftRng {
+        XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654])
+    }
+
+    #[test]
+    fn test_contribute_matches_in_memory() {
+        let mut params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        params.contribute(&mut seeded_rng(), &0);
+        let mut before = vec![];
+        params.write(&mut before).unwrap();
+
+        let hash = params.contribute(&mut seeded_rng(), &0);
+        let mut expected = vec![];
+        params.write(&mut expected).unwrap();
+
+        for batch_size in &[1, 2, 1024] {
+            let mut after = vec![];
+            let streamed_hash = contribute(Cursor::new(&before), &mut after, &mut seeded_rng(), *batch_size, &0).unwrap();
+            assert!(after == expected);
+            assert!(streamed_hash[..] == hash[..]);
+
+            let verified_hash = verify_contribution(Cursor::new(&before), Cursor::new(&after), *batch_size).unwrap();
+            assert!(verified_hash[..] == hash[..]);
+        }
+    }
+
+    #[test]
+    fn test_contribute_rejects_empty_batches() {
+        let params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        let mut before = vec![];
+        params.write(&mut before).unwrap();
+
+        let mut after = vec![];
+        match contribute(Cursor::new(&before), &mut after, &mut seeded_rng(), 0, &0) {
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {},
+            _ => panic!("contribution with empty batches accepted"),
+        }
+        assert!(after.is_empty());
+    }
+
+    #[test]
+    fn test_verify_matches_in_memory() {
+        // Filtering drops the points at infinity of A and B, whose number
+        // is then only known once they are evaluated
+        for &filter in &[false, true] {
+            let mut params = MPCParameters::new_with_source(multiplier_circuit(), filter, &mut TestPowers::new(3)).unwrap();
+            let mut rng = seeded_rng();
+            params.contribute(&mut rng, &0);
+            params.contribute(&mut rng, &0);
+            let mut bytes = vec![];
+            params.write(&mut bytes).unwrap();
+
+            let expected = params.verify_with_source(multiplier_circuit(), filter, &mut TestPowers::new(3)).unwrap();
+            for batch_size in &[1, 2, 1024] {
+                let hashes = verify(Cursor::new(&bytes), multiplier_circuit(), filter, &mut TestPowers::new(3), *batch_size).unwrap();
+                assert!(hashes == expected);
+            }
+
+            match verify(Cursor::new(&bytes), multiplier_circuit(), !filter, &mut TestPowers::new(3), 2) {
+                Err(Phase2VerificationError::AQueryChanged) => {},
+                _ => panic!("parameters filtered the other way accepted"),
+            }
+        }
+
+        let mut params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        params.contribute(&mut seeded_rng(), &0);
+        let mut bytes = vec![];
+        params.write(&mut bytes).unwrap();
+
+        // Parameters made from another ceremony
+        let mut other = TestPowers::new(3);
+        other.tau = Fr::from_str("6").unwrap();
+        match verify(Cursor::new(&bytes), multiplier_circuit(), false, &mut other, 2) {
+            Err(Phase2VerificationError::AQueryChanged) => {},
+            _ => panic!("parameters of another ceremony accepted"),
+        }
+    }
+
+    #[test]
+    fn test_read_header() {
+        let mut params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        params.contribute(&mut seeded_rng(), &0);
+        let mut bytes = vec![];
+        params.write(&mut bytes).unwrap();
+
+        let header = read_header(Cursor::new(&bytes)).unwrap();
+        let groth_params = params.get_params();
+        assert!(header.vk == groth_params.vk);
+        assert_eq!(header.h_len, groth_params.h.len());
+        assert_eq!(header.l_len, groth_params.l.len());
+        assert_eq!(header.a_len, groth_params.a.len());
+        assert_eq!(header.b_g1_len, groth_params.b_g1.len());
+        assert_eq!(header.b_g2_len, groth_params.b_g2.len());
+        assert!(header.cs_hash[..] == params.get_cs_hash()[..]);
+        assert!(header.contributions == params.get_contributions());
+
+        // The IC always holds the point of the `one` input
+        let ic_offset = 3 * 64 + 3 * 128;
+        let ic_len = groth_params.vk.ic.len() * 64;
+        let mut no_ic = bytes[..ic_offset].to_vec();
+        no_ic.extend_from_slice(&[0; 4]);
+        no_ic.extend_from_slice(&bytes[ic_offset + 4 + ic_len..]);
+        match read_header(Cursor::new(&no_ic)) {
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData => {},
+            _ => panic!("header without IC accepted"),
+        }
+    }
+
+    #[test]
+    fn test_verify_contribution_rejects_tampered_h() {
+        let params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        let mut before = vec![];
+        params.write(&mut before).unwrap();
+        let mut after = vec![];
+        contribute(Cursor::new(&before), &mut after, &mut seeded_rng(), 2, &0).unwrap();
+
+        // Swap the first two points of H, right after its length
+        let layout = ParametersLayout::read(&mut Cursor::new(&after)).unwrap();
+        let start = layout.h_offset as usize + 4;
+        let size = G1Uncompressed::size();
+        let first = after[start..start + size].to_vec();
+        let second = after[start + size..start + 2 * size].to_vec();
+        after[start..start + size].copy_from_slice(&second);
+        after[start + size..start + 2 * size].copy_from_slice(&first);
+
+        match verify_contribution(Cursor::new(&before), Cursor::new(&after), 2) {
+            Err(Phase2VerificationError::HQueryNotRescaled) => {},
+            _ => panic!("tampered H query accepted"),
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_empty_batches() {
+        let params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        let mut before = vec![];
+        params.write(&mut before).unwrap();
+        let mut after = vec![];
+        contribute(Cursor::new(&before), &mut after, &mut seeded_rng(), 2, &0).unwrap();
+
+        match verify_contribution(Cursor::new(&before), Cursor::new(&after), 0) {
+            Err(Phase2VerificationError::IoError(ref e)) if e.kind() == io::ErrorKind::InvalidInput => {},
+            _ => panic!("verification of a contribution with empty batches accepted"),
+        }
+        match verify(Cursor::new(&after), multiplier_circuit(), false, &mut TestPowers::new(3), 0) {
+            Err(Phase2VerificationError::IoError(ref e)) if e.kind() == io::ErrorKind::InvalidInput => {},
+            _ => panic!("verification of parameters with empty batches accepted"),
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_l() {
+        let mut params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        let mut rng = seeded_rng();
+        params.contribute(&mut rng, &0);
+        params.contribute(&mut rng, &0);
+        let mut bytes = vec![];
+        params.write(&mut bytes).unwrap();
+
+        // Double the first point of L, which follows H and its length
+        let layout = ParametersLayout::read(&mut Cursor::new(&bytes)).unwrap();
+        let size = G1Uncompressed::size();
+        let start = layout.h_offset as usize + 4 + layout.h_len * size + 4;
+        let mut point = read_g1(&mut &bytes[start..start + size]).unwrap().into_projective();
+        point.double();
+        bytes[start..start + size].copy_from_slice(point.into_affine().into_uncompressed().as_ref());
+
+        for batch_size in &[1, 2, 1024] {
+            match verify(Cursor::new(&bytes), multiplier_circuit(), false, &mut TestPowers::new(3), *batch_size) {
+                Err(Phase2VerificationError::LQueryNotRescaled) => {},
+                _ => panic!("tampered L query accepted"),
+            }
+        }
+    }
+}
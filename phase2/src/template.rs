@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+/// Errors that might occur while rendering a template.
+#[derive(Debug)]
+pub enum TemplateError {
+    /// A `<%name%>` tag for a variable which isn't set.
+    UnknownVariable(String),
+    /// An `<%if name%>` tag for a flag which isn't set.
+    UnknownFlag(String),
+    /// A `<%` without the matching `%>`.
+    UnclosedTag,
+    /// An `<%if name%>` without the matching `<%endif%>`.
+    UnclosedIf(String),
+    /// An `<%else%>` or `<%endif%>` outside of an `<%if name%>`, or a
+    /// second `<%else%>` in the same block.
+    UnexpectedTag(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TemplateError::UnknownVariable(ref name) => write!(f, "unknown template variable {:?}", name),
+            TemplateError::UnknownFlag(ref name) => write!(f, "unknown template flag {:?}", name),
+            TemplateError::UnclosedTag => write!(f, "template tag is not closed"),
+            TemplateError::UnclosedIf(ref name) => write!(f, "template block {:?} is not closed", name),
+            TemplateError::UnexpectedTag(ref tag) => write!(f, "unexpected template tag {:?}", tag),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+/// The variables and flags a template is rendered with.
+#[derive(Clone, Debug, Default)]
+pub struct Context {
+    variables: BTreeMap<String, String>,
+    flags: BTreeMap<String, bool>,
+}
+
+impl Context {
+    pub fn new() -> Context {
+        Context::default()
+    }
+
+    /// Sets the value `<%name%>` is replaced with.
+    pub fn set<T: ToString>(&mut self, name: &str, value: T) -> &mut Context {
+        self.variables.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets whether the `<%if name%>` blocks are kept.
+    pub fn set_flag(&mut self, name: &str, value: bool) -> &mut Context {
+        self.flags.insert(name.to_string(), value);
+        self
+    }
+}
+
+/// Renders a template, replacing every `<%name%>` tag with the value of
+/// the variable and keeping the `<%if name%> ... <%else%> ... <%endif%>`
+/// blocks according to the flags. Blocks can be nested.
+pub fn render(template: &str, context: &Context) -> Result<String, TemplateError> {
+    let mut output = String::with_capacity(template.len());
+    // For every open block, its flag, whether its condition holds and
+    // whether its `<%else%>` was seen.
+    let mut blocks: Vec<(&str, bool, bool)> = vec![];
+    let mut rest = template;
+
+    while let Some(start) = rest.find("<%") {
+        let active = blocks.iter().all(|&(_, cond, _)| cond);
+        if active {
+            output.push_str(&rest[..start]);
+        }
+        let end = rest[start..].find("%>").ok_or(TemplateError::UnclosedTag)? + start;
+        let tag = rest[start + 2..end].trim();
+        rest = &rest[end + 2..];
+
+        if let Some(name) = tag.strip_prefix("if ") {
+            let name = name.trim();
+            let value = *context.flags.get(name).ok_or_else(|| TemplateError::UnknownFlag(name.to_string()))?;
+            blocks.push((name, value, false));
+        } else if tag == "else" {
+            match blocks.last_mut() {
+                Some(block) if !block.2 => {
+                    block.1 = !block.1;
+                    block.2 = true;
+                },
+                _ => return Err(TemplateError::UnexpectedTag(tag.to_string())),
+            }
+        } else if tag == "endif" {
+            blocks.pop().ok_or_else(|| TemplateError::UnexpectedTag(tag.to_string()))?;
+        } else {
+            let value = context.variables.get(tag).ok_or_else(|| TemplateError::UnknownVariable(tag.to_string()))?;
+            if active {
+                output.push_str(value);
+            }
+        }
+    }
+
+    if let Some(&(name, _, _)) = blocks.last() {
+        return Err(TemplateError::UnclosedIf(name.to_string()));
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> Context {
+        let mut context = Context::new();
+        context
+            .set("name", "Verifier")
+            .set("n", 3)
+            .set_flag("yes", true)
+            .set_flag("no", false);
+        context
+    }
+
+    #[test]
+    fn test_variables() {
+        let context = context();
+        assert_eq!(render("plain text", &context).unwrap(), "plain text");
+        assert_eq!(render("contract <%name%> {}", &context).unwrap(), "contract Verifier {}");
+        assert_eq!(render("<%n%><% n %><%name%>", &context).unwrap(), "33Verifier");
+        assert_eq!(render("", &context).unwrap(), "");
+    }
+
+    #[test]
+    fn test_blocks() {
+        let context = context();
+        assert_eq!(render("a<%if yes%>b<%endif%>c", &context).unwrap(), "abc");
+        assert_eq!(render("a<%if no%>b<%endif%>c", &context).unwrap(), "ac");
+        assert_eq!(render("<%if yes%>b<%else%>c<%endif%>", &context).unwrap(), "b");
+        assert_eq!(render("<%if no%>b<%else%>c<%endif%>", &context).unwrap(), "c");
+    }
+
+    #[test]
+    fn test_nested_blocks() {
+        let context = context();
+        let template = "<%if yes%>1<%if no%>2<%else%>3<%if yes%><%n%><%endif%><%endif%>4<%else%>5<%if yes%>6<%endif%><%endif%>";
+        assert_eq!(render(template, &context).unwrap(), "1334");
+        let template = "<%if no%>1<%if yes%>2<%else%>3<%endif%>4<%else%>5<%if no%>6<%else%>7<%endif%><%endif%>";
+        assert_eq!(render(template, &context).unwrap(), "57");
+    }
+
+    #[test]
+    fn test_errors() {
+        let context = context();
+        match render("<%unset%>", &context) {
+            Err(TemplateError::UnknownVariable(ref name)) if name == "unset" => {},
+            _ => panic!("unknown variable not reported"),
+        }
+        // Tags are checked in discarded blocks too, so a typo can't hide there.
+        match render("<%if no%><%unset%><%endif%>", &context) {
+            Err(TemplateError::UnknownVariable(ref name)) if name == "unset" => {},
+            _ => panic!("unknown variable not reported in a discarded block"),
+        }
+        match render("<%if unset%><%endif%>", &context) {
+            Err(TemplateError::UnknownFlag(ref name)) if name == "unset" => {},
+            _ => panic!("unknown flag not reported"),
+        }
+        match render("<%name", &context) {
+            Err(TemplateError::UnclosedTag) => {},
+            _ => panic!("unclosed tag not reported"),
+        }
+        match render("<%if yes%><%if no%><%endif%>", &context) {
+            Err(TemplateError::UnclosedIf(ref name)) if name == "yes" => {},
+            _ => panic!("unclosed block not reported"),
+        }
+        for template in &["<%else%>", "<%endif%>", "<%if yes%><%endif%><%endif%>", "<%if yes%>a<%else%>b<%else%>c<%endif%>"] {
+            match render(template, &context) {
+                Err(TemplateError::UnexpectedTag(_)) => {},
+                _ => panic!("unexpected tag not reported in {:?}", template),
+            }
+        }
+    }
+}
@@ -57,6 +57,15 @@ pub fn same_ratio<G1: CurveAffine>(
 ///
 /// ... with high probability.
 pub fn merge_pairs<G: CurveAffine>(v1: &[G], v2: &[G]) -> (G, G)
+{
+    let (s, sx) = merge_pairs_projective(v1, v2);
+
+    (s.into_affine(), sx.into_affine())
+}
+
+/// Same as `merge_pairs`, without normalizing the sums, so that the
+/// combinations of consecutive slices can be added together.
+pub(crate) fn merge_pairs_projective<G: CurveAffine>(v1: &[G], v2: &[G]) -> (G::Projective, G::Projective)
 {
     use std::sync::Mutex;
     use rand::{thread_rng};
@@ -98,8 +107,8 @@ pub fn merge_pairs<G: CurveAffine>(v1: &[G], v2: &[G]) -> (G, G)
         }
     }).unwrap();
 
-    let s = s.lock().unwrap().into_affine();
-    let sx = sx.lock().unwrap().into_affine();
+    let s = *s.lock().unwrap();
+    let sx = *sx.lock().unwrap();
 
     (s, sx)
 }
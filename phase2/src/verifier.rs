@@ -0,0 +1,364 @@
+extern crate bellman_ce;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::error::Error;
+use std::path::Path;
+use std::collections::BTreeMap;
+
+use bellman_ce::{
+    groth16::VerifyingKey,
+    pairing::{
+        Engine,
+        CurveAffine,
+        ff::{
+            PrimeField,
+            PrimeFieldRepr,
+        },
+        bn256::{
+            Bn256,
+            Fq,
+        }
+    }
+};
+
+use num_bigint::BigUint;
+use num_traits::Num;
+use serde::Serialize;
+
+use solidity::{self, SolidityError, SolidityOptions};
+use template::{self, Context, TemplateError};
+
+const NOSTD_LIB_TEMPLATE: &str = include_str!("verifier_groth_nostd.rs.tmpl");
+const NOSTD_MANIFEST_TEMPLATE: &str = include_str!("verifier_groth_nostd.toml.tmpl");
+
+/// The `no_std` verifier of `tests::nostd_params`, compiled into the tests.
+#[cfg(test)]
+#[allow(unused_attributes, clippy::enum_variant_names)]
+#[path = "../testdata/verifier_nostd.rs"]
+mod nostd_verifier;
+
+/// Errors that might occur while generating a verifier.
+#[derive(Debug)]
+pub enum VerifierError {
+    SolidityError(SolidityError),
+    TemplateError(TemplateError),
+    JsonError(serde_json::Error),
+}
+
+impl fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerifierError::SolidityError(ref e) => write!(f, "{}", e),
+            VerifierError::TemplateError(ref e) => write!(f, "template error: {}", e),
+            VerifierError::JsonError(ref e) => write!(f, "JSON error: {}", e),
+        }
+    }
+}
+
+impl Error for VerifierError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            VerifierError::SolidityError(ref e) => Some(e),
+            VerifierError::TemplateError(ref e) => Some(e),
+            VerifierError::JsonError(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<SolidityError> for VerifierError {
+    fn from(err: SolidityError) -> VerifierError {
+        VerifierError::SolidityError(err)
+    }
+}
+
+impl From<TemplateError> for VerifierError {
+    fn from(err: TemplateError) -> VerifierError {
+        VerifierError::TemplateError(err)
+    }
+}
+
+impl From<serde_json::Error> for VerifierError {
+    fn from(err: serde_json::Error) -> VerifierError {
+        VerifierError::JsonError(err)
+    }
+}
+
+/// A generated verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GeneratedVerifier {
+    /// A single source file.
+    Source(String),
+    /// A package of files, by their path relative to the package root.
+    Package(BTreeMap<String, String>),
+}
+
+impl GeneratedVerifier {
+    /// Writes a source file to `path`, or a package to the directory
+    /// `path`, creating it if needed.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        match *self {
+            GeneratedVerifier::Source(ref source) => fs::write(path, source.as_bytes()),
+            GeneratedVerifier::Package(ref files) => {
+                for (name, contents) in files {
+                    let file_path = path.join(name);
+                    if let Some(dir) = file_path.parent() {
+                        fs::create_dir_all(dir)?;
+                    }
+                    fs::write(file_path, contents.as_bytes())?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+/// Generates verifiers for some target from a Groth16 verifying key.
+pub trait VerifierGenerator {
+    fn generate(&self, vk: &VerifyingKey<Bn256>) -> Result<GeneratedVerifier, VerifierError>;
+}
+
+/// A Solidity or Yul verifier, see `solidity::generate_verifier`.
+#[derive(Clone, Debug, Default)]
+pub struct SolidityVerifier {
+    pub options: SolidityOptions,
+}
+
+impl VerifierGenerator for SolidityVerifier {
+    fn generate(&self, vk: &VerifyingKey<Bn256>) -> Result<GeneratedVerifier, VerifierError> {
+        Ok(GeneratedVerifier::Source(solidity::generate_verifier(vk, &self.options)?))
+    }
+}
+
+/// A `no_std` Rust crate with the verifying key baked in as constants,
+/// verifying proofs with the `substrate-bn` crate.
+#[derive(Clone, Debug)]
+pub struct RustVerifier {
+    pub crate_name: String,
+}
+
+impl Default for RustVerifier {
+    fn default() -> RustVerifier {
+        RustVerifier { crate_name: String::from("groth16-verifier") }
+    }
+}
+
+fn fq_to_bytes(f: &Fq) -> String {
+    let mut bytes = vec![];
+    f.into_repr().write_be(&mut bytes).expect("writing to a vector never fails");
+    format!("[{}]", bytes.iter().map(|b| format!("0x{:02x}", b)).collect::<Vec<_>>().join(", "))
+}
+
+fn g1_to_bytes(p: &<Bn256 as Engine>::G1Affine) -> String {
+    format!("[{}, {}]", fq_to_bytes(&p.get_x()), fq_to_bytes(&p.get_y()))
+}
+
+fn g2_to_bytes(p: &<Bn256 as Engine>::G2Affine) -> String {
+    let x = p.get_x();
+    let y = p.get_y();
+    format!(
+        "[\n    {},\n    {},\n    {},\n    {},\n]",
+        fq_to_bytes(&x.c1), fq_to_bytes(&x.c0), fq_to_bytes(&y.c1), fq_to_bytes(&y.c0)
+    )
+}
+
+impl VerifierGenerator for RustVerifier {
+    fn generate(&self, vk: &VerifyingKey<Bn256>) -> Result<GeneratedVerifier, VerifierError> {
+        let ic = vk.ic.iter().map(|p| format!("    {},\n", g1_to_bytes(p))).collect::<String>();
+
+        let mut context = Context::new();
+        context
+            .set("crate_name", &self.crate_name)
+            .set("vk_input_length", vk.ic.len() - 1)
+            .set("vk_alpha", g1_to_bytes(&vk.alpha_g1))
+            .set("vk_beta", g2_to_bytes(&vk.beta_g2))
+            .set("vk_gamma", g2_to_bytes(&vk.gamma_g2))
+            .set("vk_delta", g2_to_bytes(&vk.delta_g2))
+            .set("vk_ic", format!("[\n{}]", ic));
+
+        let mut files = BTreeMap::new();
+        files.insert(String::from("Cargo.toml"), template::render(NOSTD_MANIFEST_TEMPLATE, &context)?);
+        files.insert(String::from("src/lib.rs"), template::render(NOSTD_LIB_TEMPLATE, &context)?);
+        Ok(GeneratedVerifier::Package(files))
+    }
+}
+
+#[derive(Serialize)]
+struct EthsnarksVerifyingKeyJson {
+    alpha: Vec<String>,
+    beta: Vec<Vec<String>>,
+    delta: Vec<Vec<String>>,
+    gamma: Vec<Vec<String>>,
+    #[serde(rename = "gammaABC")]
+    gamma_abc: Vec<Vec<String>>,
+}
+
+/// A verifying key in the JSON format of ethsnarks, which has its
+/// coordinates in hexadecimal and G2 coordinates imaginary part first.
+#[derive(Clone, Debug, Default)]
+pub struct EthsnarksVerifier;
+
+fn fq_to_hex(f: &Fq) -> String {
+    let repr = f.into_repr().to_string();
+    format!("0x{}", BigUint::from_str_radix(&repr[2..], 16).unwrap().to_str_radix(16))
+}
+
+fn g1_to_hex(p: &<Bn256 as Engine>::G1Affine) -> Vec<String> {
+    vec![fq_to_hex(&p.get_x()), fq_to_hex(&p.get_y())]
+}
+
+fn g2_to_hex(p: &<Bn256 as Engine>::G2Affine) -> Vec<Vec<String>> {
+    let x = p.get_x();
+    let y = p.get_y();
+    vec![
+        vec![fq_to_hex(&x.c1), fq_to_hex(&x.c0)],
+        vec![fq_to_hex(&y.c1), fq_to_hex(&y.c0)],
+    ]
+}
+
+impl VerifierGenerator for EthsnarksVerifier {
+    fn generate(&self, vk: &VerifyingKey<Bn256>) -> Result<GeneratedVerifier, VerifierError> {
+        let json = EthsnarksVerifyingKeyJson {
+            alpha: g1_to_hex(&vk.alpha_g1),
+            beta: g2_to_hex(&vk.beta_g2),
+            delta: g2_to_hex(&vk.delta_g2),
+            gamma: g2_to_hex(&vk.gamma_g2),
+            gamma_abc: vk.ic.iter().map(g1_to_hex).collect(),
+        };
+
+        let mut output = vec![];
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+        json.serialize(&mut serializer)?;
+        Ok(GeneratedVerifier::Source(String::from_utf8(output).expect("JSON is valid UTF-8")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use circom_circuit::tests::multiplier_circuit;
+    use parameters::MPCParameters;
+    use phase1::tests::TestPowers;
+    use rand::{SeedableRng, XorShiftRng};
+    use solidity::tests::verifying_key;
+    use utils::repr_to_big;
+
+    fn hex_to_decimal(hex: &serde_json::Value) -> String {
+        let hex = hex.as_str().unwrap();
+        assert!(hex.starts_with("0x"));
+        BigUint::from_str_radix(&hex[2..], 16).unwrap().to_str_radix(10)
+    }
+
+    #[test]
+    fn test_rust_verifier() {
+        let vk = verifying_key(3);
+        let files = match RustVerifier::default().generate(&vk).unwrap() {
+            GeneratedVerifier::Package(files) => files,
+            _ => panic!("expected a package"),
+        };
+        assert_eq!(files.keys().collect::<Vec<_>>(), ["Cargo.toml", "src/lib.rs"]);
+
+        let manifest = &files["Cargo.toml"];
+        assert!(manifest.contains("name = \"groth16-verifier\""));
+        assert!(!manifest.contains("<%"));
+
+        let lib = syn::parse_file(&files["src/lib.rs"]).expect("the generated crate parses");
+        let constant = |name: &str| {
+            lib.items.iter().find_map(|item| match *item {
+                syn::Item::Const(ref item) if item.ident == name => Some(item.expr.clone()),
+                _ => None,
+            }).unwrap_or_else(|| panic!("{} is missing", name))
+        };
+        let array_len = |expr: &syn::Expr| match *expr {
+            syn::Expr::Array(ref array) => array.elems.len(),
+            _ => panic!("expected an array"),
+        };
+
+        match *constant("NUM_INPUTS") {
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(ref n), .. }) => assert_eq!(n.base10_parse::<usize>().unwrap(), 3),
+            _ => panic!("NUM_INPUTS isn't an integer"),
+        }
+        assert_eq!(array_len(&constant("ALPHA_G1")), 2);
+        for name in &["BETA_G2", "GAMMA_G2", "DELTA_G2"] {
+            assert_eq!(array_len(&constant(name)), 4);
+        }
+        assert_eq!(array_len(&constant("IC")), 4);
+    }
+
+    /// Parameters of `multiplier_circuit` with one contribution, along with
+    /// the RNG to prove with. testdata/verifier_nostd.rs is their verifier.
+    fn nostd_params() -> (MPCParameters, XorShiftRng) {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let mut params = MPCParameters::new_with_source(multiplier_circuit(), false, &mut TestPowers::new(3)).unwrap();
+        params.contribute(&mut rng, &0);
+        (params, rng)
+    }
+
+    #[test]
+    fn test_golden_rust_verifier() {
+        let (params, _) = nostd_params();
+        let files = match RustVerifier::default().generate(&params.get_params().vk).unwrap() {
+            GeneratedVerifier::Package(files) => files,
+            _ => panic!("expected a package"),
+        };
+        assert!(files["src/lib.rs"] == include_str!("../testdata/verifier_nostd.rs"), "verifier_nostd.rs differs from the generated verifier");
+    }
+
+    #[test]
+    fn test_rust_verifier_checks_proofs() {
+        use bellman_ce::pairing::{ff::Field, bn256::Fr};
+        use calldata::{encode_inputs, encode_proof};
+        use circom_circuit::prove;
+        use super::nostd_verifier::{verify_bytes, Error};
+
+        let (params, mut rng) = nostd_params();
+        let circuit = multiplier_circuit();
+        let proof = encode_proof(&prove(circuit.clone(), params.get_params(), &mut rng).unwrap());
+        let mut inputs = circuit.get_public_inputs().unwrap();
+        let valid_inputs = encode_inputs(&inputs);
+        inputs[0].add_assign(&Fr::one());
+        let wrong_inputs = encode_inputs(&inputs);
+        // C replaced by A, which is still a point on the curve
+        let mut wrong_proof = proof.clone();
+        wrong_proof.copy_within(0..64, 192);
+
+        assert_eq!(verify_bytes(&proof, &valid_inputs), Ok(true));
+        assert_eq!(verify_bytes(&wrong_proof, &valid_inputs), Ok(false));
+        assert_eq!(verify_bytes(&proof, &wrong_inputs), Ok(false));
+        assert_eq!(verify_bytes(&proof[..255], &valid_inputs), Err(Error::InvalidProofLength));
+    }
+
+    #[test]
+    fn test_ethsnarks_verifier() {
+        let vk = verifying_key(2);
+        let json = match EthsnarksVerifier.generate(&vk).unwrap() {
+            GeneratedVerifier::Source(json) => json,
+            _ => panic!("expected a single file"),
+        };
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let g1 = |json: &serde_json::Value, p: &<Bn256 as Engine>::G1Affine| {
+            assert_eq!(hex_to_decimal(&json[0]), repr_to_big(p.get_x().into_repr()));
+            assert_eq!(hex_to_decimal(&json[1]), repr_to_big(p.get_y().into_repr()));
+        };
+        let g2 = |json: &serde_json::Value, p: &<Bn256 as Engine>::G2Affine| {
+            let (x, y) = (p.get_x(), p.get_y());
+            assert_eq!(hex_to_decimal(&json[0][0]), repr_to_big(x.c1.into_repr()));
+            assert_eq!(hex_to_decimal(&json[0][1]), repr_to_big(x.c0.into_repr()));
+            assert_eq!(hex_to_decimal(&json[1][0]), repr_to_big(y.c1.into_repr()));
+            assert_eq!(hex_to_decimal(&json[1][1]), repr_to_big(y.c0.into_repr()));
+        };
+
+        g1(&json["alpha"], &vk.alpha_g1);
+        g2(&json["beta"], &vk.beta_g2);
+        g2(&json["gamma"], &vk.gamma_g2);
+        g2(&json["delta"], &vk.delta_g2);
+        assert_eq!(json["gammaABC"].as_array().unwrap().len(), 3);
+        for (json, p) in json["gammaABC"].as_array().unwrap().iter().zip(vk.ic.iter()) {
+            g1(json, p);
+        }
+    }
+}
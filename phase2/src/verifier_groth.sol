@@ -17,7 +17,7 @@
 
 // 2019 OKIMS
 
-pragma solidity ^0.6.0;
+pragma solidity <%pragma%>;
 
 library Pairing {
     uint256 constant PRIME_Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
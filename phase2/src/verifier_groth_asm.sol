@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: MIT
+
+pragma solidity <%pragma%>;
+
+contract Verifier {
+    uint256 constant SNARK_SCALAR_FIELD = <%snark_scalar_field%>;
+    uint256 constant PRIME_Q = <%prime_q%>;
+    uint256 constant NUM_INPUTS = <%vk_input_length%>;
+<%if batch%>
+    uint256 constant ALPHA_X = <%vk_alpha_x%>;
+    uint256 constant ALPHA_Y = <%vk_alpha_y%>;
+    uint256 constant BETA_X1 = <%vk_beta_x1%>;
+    uint256 constant BETA_X0 = <%vk_beta_x0%>;
+    uint256 constant BETA_Y1 = <%vk_beta_y1%>;
+    uint256 constant BETA_Y0 = <%vk_beta_y0%>;
+    uint256 constant GAMMA_X1 = <%vk_gamma_x1%>;
+    uint256 constant GAMMA_X0 = <%vk_gamma_x0%>;
+    uint256 constant GAMMA_Y1 = <%vk_gamma_y1%>;
+    uint256 constant GAMMA_Y0 = <%vk_gamma_y0%>;
+    uint256 constant DELTA_X1 = <%vk_delta_x1%>;
+    uint256 constant DELTA_X0 = <%vk_delta_x0%>;
+    uint256 constant DELTA_Y1 = <%vk_delta_y1%>;
+    uint256 constant DELTA_Y0 = <%vk_delta_y0%>;
+<%endif%>
+    /*
+     * @returns Whether the proof is valid given the hardcoded verifying key
+     *          and the public inputs. The proof is
+     *          [A.X, A.Y, B.X[1], B.X[0], B.Y[1], B.Y[0], C.X, C.Y].
+     */
+    function verifyProof(
+        uint256[] calldata proof,
+        uint256[] calldata input
+    ) external view returns (bool) {
+        require(proof.length == 8, "verifier-bad-proof-length");
+        require(input.length == NUM_INPUTS, "verifier-bad-input-length");
+        checkProofElements(proof);
+
+        (uint256 x, uint256 y) = accumulateInputs(input, 0);
+        return pairingCheck(proof, x, y);
+    }
+<%if batch%>
+    /*
+     * @returns Whether all the proofs are valid given the hardcoded verifying
+     *          key and their public inputs. The proofs and the inputs are
+     *          concatenated, 8 elements per proof and NUM_INPUTS elements
+     *          per set of public inputs.
+     */
+    function verifyProofs(
+        uint256[] calldata proofs,
+        uint256[] calldata inputs
+    ) external view returns (bool) {
+        uint256 n = proofs.length / 8;
+        require(n > 0 && proofs.length == n * 8, "verifier-bad-proof-length");
+        require(inputs.length == n * NUM_INPUTS, "verifier-bad-input-length");
+        checkProofElements(proofs);
+
+        // The proofs are combined with random coefficients r_i into the
+        // pairing check of [-r_i * A_i, B_i] for every proof, followed by
+        // [sum(r_i) * alpha, beta], [sum(r_i * vk_x_i), gamma] and
+        // [sum(r_i * C_i), delta].
+        uint256[] memory pairs = new uint256[]((n + 3) * 6);
+        uint256 seed = uint256(keccak256(abi.encodePacked(proofs, inputs)));
+        uint256 rSum = 0;
+        for (uint256 i = 0; i < n; i++) {
+            uint256 r = i == 0 ? 1 : uint256(keccak256(abi.encodePacked(seed, i))) >> 128;
+            rSum = addmod(rSum, r, SNARK_SCALAR_FIELD);
+            accumulateProof(proofs, inputs, i, r, pairs);
+        }
+        completeBatch(pairs, rSum);
+
+        return pairing(pairs);
+    }
+
+    function accumulateProof(
+        uint256[] calldata proofs,
+        uint256[] calldata inputs,
+        uint256 i,
+        uint256 r,
+        uint256[] memory pairs
+    ) internal view {
+        uint256 k = pairs.length - 12;
+        (uint256 x, uint256 y) = accumulateInputs(inputs, i * NUM_INPUTS);
+        (x, y) = mulAdd(pairs[k], pairs[k + 1], x, y, r);
+        pairs[k] = x;
+        pairs[k + 1] = y;
+
+        k = pairs.length - 6;
+        (x, y) = mulAdd(pairs[k], pairs[k + 1], proofs[i * 8 + 6], proofs[i * 8 + 7], r);
+        pairs[k] = x;
+        pairs[k + 1] = y;
+
+        k = i * 6;
+        (x, y) = mulAdd(0, 0, proofs[i * 8], proofs[i * 8 + 1], r);
+        pairs[k] = x;
+        pairs[k + 1] = (PRIME_Q - y) % PRIME_Q;
+        for (uint256 j = 2; j < 6; j++) {
+            pairs[k + j] = proofs[i * 8 + j];
+        }
+    }
+
+    function completeBatch(uint256[] memory pairs, uint256 rSum) internal view {
+        uint256 k = pairs.length - 18;
+        (uint256 x, uint256 y) = mulAdd(0, 0, ALPHA_X, ALPHA_Y, rSum);
+        pairs[k] = x;
+        pairs[k + 1] = y;
+        pairs[k + 2] = BETA_X1;
+        pairs[k + 3] = BETA_X0;
+        pairs[k + 4] = BETA_Y1;
+        pairs[k + 5] = BETA_Y0;
+        pairs[k + 8] = GAMMA_X1;
+        pairs[k + 9] = GAMMA_X0;
+        pairs[k + 10] = GAMMA_Y1;
+        pairs[k + 11] = GAMMA_Y0;
+        pairs[k + 14] = DELTA_X1;
+        pairs[k + 15] = DELTA_X0;
+        pairs[k + 16] = DELTA_Y1;
+        pairs[k + 17] = DELTA_Y0;
+    }
+
+    /*
+     * @return ok Whether e(p[0], p[1]) * ... * e(p[2n], p[2n + 1]) == 1 for
+     *            the G1 and G2 points in pairs.
+     */
+    function pairing(uint256[] memory pairs) internal view returns (bool ok) {
+        // solium-disable-next-line security/no-inline-assembly
+        assembly {
+            let success := staticcall(gas(), 8, add(pairs, 0x20), mul(mload(pairs), 0x20), 0x00, 0x20)
+            ok := and(success, mload(0x00))
+        }
+    }
+<%endif%>
+    /*
+     * Makes sure that each element of the proofs is less than the prime q.
+     */
+    function checkProofElements(uint256[] calldata proof) internal pure {
+        for (uint256 i = 0; i < proof.length; i++) {
+            require(proof[i] < PRIME_Q, "verifier-proof-element-gte-prime-q");
+        }
+    }
+
+    /*
+     * @return The linear combination vk_x of the NUM_INPUTS public inputs
+     *         starting at offset.
+     */
+    function accumulateInputs(
+        uint256[] calldata input,
+        uint256 offset
+    ) internal view returns (uint256 x, uint256 y) {
+<%vk_ic_accumulate%>
+    }
+
+    /*
+     * @return The point (x, y) + s * (px, py), making sure that s is less
+     *         than the snark scalar field.
+     */
+    function mulAdd(
+        uint256 x,
+        uint256 y,
+        uint256 px,
+        uint256 py,
+        uint256 s
+    ) internal view returns (uint256 rx, uint256 ry) {
+        require(s < SNARK_SCALAR_FIELD, "verifier-gte-snark-scalar-field");
+        bool success;
+        // solium-disable-next-line security/no-inline-assembly
+        assembly {
+            let m := mload(0x40)
+            mstore(m, px)
+            mstore(add(m, 0x20), py)
+            mstore(add(m, 0x40), s)
+            success := staticcall(gas(), 7, m, 0x60, m, 0x40)
+            mstore(add(m, 0x40), x)
+            mstore(add(m, 0x60), y)
+            success := and(success, staticcall(gas(), 6, m, 0x80, m, 0x40))
+            rx := mload(m)
+            ry := mload(add(m, 0x20))
+        }
+        require(success, "verifier-ec-operation-failed");
+    }
+
+    /*
+     * @return ok Whether e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1
+     */
+    function pairingCheck(
+        uint256[] calldata proof,
+        uint256 x,
+        uint256 y
+    ) internal view returns (bool ok) {
+        // solium-disable-next-line security/no-inline-assembly
+        assembly {
+            let p := mload(0x40)
+            mstore(p, calldataload(proof.offset))
+            mstore(add(p, 0x20), mod(sub(<%prime_q%>, calldataload(add(proof.offset, 0x20))), <%prime_q%>))
+            calldatacopy(add(p, 0x40), add(proof.offset, 0x40), 0x80)
+            mstore(add(p, 0xc0), <%vk_alpha_x%>)
+            mstore(add(p, 0xe0), <%vk_alpha_y%>)
+            mstore(add(p, 0x100), <%vk_beta_x1%>)
+            mstore(add(p, 0x120), <%vk_beta_x0%>)
+            mstore(add(p, 0x140), <%vk_beta_y1%>)
+            mstore(add(p, 0x160), <%vk_beta_y0%>)
+            mstore(add(p, 0x180), x)
+            mstore(add(p, 0x1a0), y)
+            mstore(add(p, 0x1c0), <%vk_gamma_x1%>)
+            mstore(add(p, 0x1e0), <%vk_gamma_x0%>)
+            mstore(add(p, 0x200), <%vk_gamma_y1%>)
+            mstore(add(p, 0x220), <%vk_gamma_y0%>)
+            calldatacopy(add(p, 0x240), add(proof.offset, 0xc0), 0x40)
+            mstore(add(p, 0x280), <%vk_delta_x1%>)
+            mstore(add(p, 0x2a0), <%vk_delta_x0%>)
+            mstore(add(p, 0x2c0), <%vk_delta_y1%>)
+            mstore(add(p, 0x2e0), <%vk_delta_y0%>)
+            let success := staticcall(gas(), 8, p, 0x300, 0x00, 0x20)
+            ok := and(success, mload(0x00))
+        }
+    }
+}
@@ -0,0 +1,851 @@
+extern crate bellman_ce;
+extern crate byteorder;
+
+#[cfg(feature = "wasm")]
+use bellman_ce::singlecore::Worker;
+#[cfg(not(feature = "wasm"))]
+use bellman_ce::multicore::Worker;
+
+use std::fmt;
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write, Seek, BufReader, BufWriter};
+use std::sync::Arc;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use rand::Rng;
+
+use bellman_ce::{
+    SynthesisError,
+    domain::{
+        EvaluationDomain,
+        Point,
+    },
+    groth16::{
+        Parameters,
+        Proof,
+        VerifyingKey,
+        prepare_prover,
+    },
+    pairing::{
+        CurveAffine,
+        CurveProjective,
+        EncodedPoint,
+        GroupDecodingError,
+        RawEncodable,
+        ff::{
+            Field,
+            PrimeField,
+            PrimeFieldRepr,
+            SqrtField,
+            LegendreSymbol,
+        },
+        bn256::{
+            Bn256,
+            Fq,
+            Fr,
+            FrRepr,
+            G1,
+            G1Affine,
+            G2Affine,
+        }
+    }
+};
+
+use circom_circuit::{
+    CircomCircuit,
+    BinaryFileError,
+    prove,
+    filter_params,
+    read_sections,
+    seek_section,
+    read_field_header,
+    check_g2_subgroup,
+};
+use parameters::MPCParameters;
+
+const ZKEY_VERSION: u32 = 1;
+const NUM_SECTIONS: u32 = 10;
+const PROTOCOL_GROTH16: u32 = 1;
+
+const FIELD_SIZE: u64 = 32;
+const G1_SIZE: u64 = 64;
+const G2_SIZE: u64 = 128;
+
+/// Errors that might occur while reading or writing a `.zkey`, or loading
+/// parameters to prove with.
+#[derive(Debug)]
+pub enum ZkeyError {
+    IoError(io::Error),
+    /// A `.zkey` with an invalid header.
+    InvalidFile(BinaryFileError),
+    /// A curve point which doesn't decode to a valid point of the group.
+    InvalidPoint(GroupDecodingError),
+    /// A `.zkey` for a proving system other than Groth16.
+    UnsupportedProtocol(u32),
+    /// An evaluation domain size which can't be converted between snarkjs
+    /// and bellman.
+    UnsupportedDomainSize(usize),
+    /// A `.zkey` with more public inputs than variables.
+    IndexOutOfRange { index: usize, num_variables: usize },
+    /// The parameters weren't created for the given circuit, or have had
+    /// their points at infinity filtered out.
+    ParametersMismatch,
+    /// A `.zkey` whose constraints are laid out neither the way bellman's
+    /// prover synthesizes them nor the way `snarkjs zkey new` does.
+    UnsupportedConstraintLayout,
+}
+
+impl fmt::Display for ZkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ZkeyError::IoError(ref e) => write!(f, "I/O error: {}", e),
+            ZkeyError::InvalidFile(ref e) => write!(f, "{}", e),
+            ZkeyError::InvalidPoint(ref e) => write!(f, "invalid curve point: {}", e),
+            ZkeyError::UnsupportedProtocol(p) => write!(f, "unsupported protocol {}, only Groth16 is supported", p),
+            ZkeyError::UnsupportedDomainSize(m) => write!(f, "unsupported evaluation domain size {}", m),
+            ZkeyError::IndexOutOfRange { index, num_variables } =>
+                write!(f, "variable index {} is out of range, circuit has {} variables", index, num_variables),
+            ZkeyError::ParametersMismatch => write!(f, "parameters don't match the circuit"),
+            ZkeyError::UnsupportedConstraintLayout =>
+                write!(f, "constraints are laid out neither by bellman nor by snarkjs"),
+        }
+    }
+}
+
+impl Error for ZkeyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ZkeyError::IoError(ref e) => Some(e),
+            ZkeyError::InvalidFile(ref e) => Some(e),
+            ZkeyError::InvalidPoint(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ZkeyError {
+    fn from(err: io::Error) -> ZkeyError {
+        ZkeyError::IoError(err)
+    }
+}
+
+impl From<BinaryFileError> for ZkeyError {
+    fn from(err: BinaryFileError) -> ZkeyError {
+        match err {
+            BinaryFileError::IoError(e) => ZkeyError::IoError(e),
+            e => ZkeyError::InvalidFile(e),
+        }
+    }
+}
+
+/// The order in which the constraints of a `.zkey` sit on the points of
+/// its evaluation domain: the constraints of the circuit, followed by a
+/// constraint for each input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstraintLayout {
+    /// On the powers of bellman's root of unity, the order bellman's
+    /// prover synthesizes them in. `write_zkey` keeps this order.
+    Bellman,
+    /// On the powers of snarkjs's root of unity, as `snarkjs zkey new`
+    /// lays them out. For domains larger than 2^5 points those are other
+    /// powers of bellman's root, so the constraints must be moved there
+    /// when proving, see `prove_with_layout`.
+    Snarkjs,
+}
+
+/// snarkjs and bellman pick different primitive roots of unity for the
+/// evaluation domain of a given size, so the same constraint sits at a
+/// different point of the domain in each of them, and the H query is
+/// expressed in a different basis. This maps one domain onto the other.
+struct DomainMapping {
+    m: usize,
+    /// `omega_snarkjs = omega_bellman^k`
+    k: u64,
+    /// `omega_bellman = omega_snarkjs^k_inv`
+    k_inv: u64,
+    /// The primitive `2m`-th root of unity snarkjs uses to move onto the
+    /// odd coset where it evaluates the H polynomial.
+    shift: Fr,
+}
+
+impl DomainMapping {
+    fn new(exp: u32) -> Result<DomainMapping, ZkeyError> {
+        // snarkjs would need a `2m`-th root of unity which doesn't exist
+        // for the largest domain and switches to a different coset there.
+        if exp >= Fr::S {
+            return Err(ZkeyError::UnsupportedDomainSize(1 << exp));
+        }
+
+        let omega_bellman = root_of_unity(Fr::root_of_unity(), exp);
+        let omega_snarkjs = root_of_unity(snarkjs_root_of_unity(), exp);
+
+        Ok(DomainMapping {
+            m: 1 << exp,
+            k: root_of_unity_log(&omega_bellman, &omega_snarkjs, exp),
+            k_inv: root_of_unity_log(&omega_snarkjs, &omega_bellman, exp),
+            shift: root_of_unity(snarkjs_root_of_unity(), exp + 1),
+        })
+    }
+
+    /// Position in the snarkjs domain of the point bellman has at `position`.
+    fn to_snarkjs(&self, position: usize) -> usize {
+        (position as u64 * self.k_inv % self.m as u64) as usize
+    }
+
+    /// Position in the bellman domain of the point snarkjs has at `position`.
+    fn to_bellman(&self, position: usize) -> usize {
+        (position as u64 * self.k % self.m as u64) as usize
+    }
+
+    /// Converts bellman's H query, `tau^j * Z(tau) / delta` for `j < m - 1`,
+    /// into the snarkjs one, which is multiplied by the evaluations of
+    /// `A * B - C` on the odd coset instead of by the coefficients of
+    /// `(A * B - C) / Z`. `Z` is `-2` on that coset, so the Lagrange basis of
+    /// the coset scaled by `-Z(tau) / 2 / delta` does the job.
+    fn h_to_snarkjs(&self, h: &[G1Affine], worker: &Worker) -> Vec<G1Affine> {
+        // Coefficients of the Lagrange basis on the coset are those of the
+        // plain one with the j-th scaled by shift^-j. The missing
+        // `tau^(m-1)` term would only ever be multiplied by zero since
+        // the quotient has degree at most m - 2.
+        let shift_inv = self.shift.inverse().unwrap();
+        let mut power = Fr::one();
+        let mut coeffs = Vec::with_capacity(self.m);
+        for p in h {
+            coeffs.push(Point(p.mul(power)));
+            power.mul_assign(&shift_inv);
+        }
+        coeffs.resize(self.m, Point(G1::zero()));
+
+        let mut domain = EvaluationDomain::<Bn256, _>::from_coeffs(coeffs).expect("domain size was checked");
+        domain.ifft(worker);
+        let coeffs = domain.into_coeffs();
+
+        let mut scale = Fr::one();
+        scale.double();
+        scale = scale.inverse().unwrap();
+        scale.negate();
+
+        (0..self.m).map(|i| coeffs[self.to_bellman(i)].0.into_affine().mul(scale).into_affine()).collect()
+    }
+
+    /// Inverse of `h_to_snarkjs`.
+    fn h_from_snarkjs(&self, h: &[G1Affine], worker: &Worker) -> Vec<G1Affine> {
+        let coeffs = h.iter().map(|p| Point(p.into_projective())).collect();
+        let mut domain = EvaluationDomain::<Bn256, _>::from_coeffs(coeffs).expect("domain size was checked");
+        domain.fft(worker);
+        let coeffs = domain.into_coeffs();
+
+        let mut scale = Fr::one();
+        scale.double();
+        scale.negate();
+
+        let mut result = Vec::with_capacity(self.m - 1);
+        for j in 0..self.m - 1 {
+            result.push(coeffs[self.to_bellman(j)].0.into_affine().mul(scale).into_affine());
+            scale.mul_assign(&self.shift);
+        }
+        result
+    }
+}
+
+/// Squares a primitive `2^S`-th root of unity down to a `2^exp`-th one.
+fn root_of_unity(mut root: Fr, exp: u32) -> Fr {
+    for _ in exp..Fr::S {
+        root.square();
+    }
+    root
+}
+
+/// The primitive `2^S`-th root of unity snarkjs uses: a power of the
+/// smallest quadratic non-residue rather than of the multiplicative
+/// generator bellman takes.
+fn snarkjs_root_of_unity() -> Fr {
+    let mut nqr = Fr::one();
+    nqr.double();
+    while nqr.legendre() != LegendreSymbol::QuadraticNonResidue {
+        nqr.add_assign(&Fr::one());
+    }
+
+    // r = t * 2^S + 1
+    let mut t = Fr::char();
+    t.shr(Fr::S);
+    nqr.pow(t)
+}
+
+/// Finds `k` such that `base^k = target`, where `base` is a primitive
+/// `2^exp`-th root of unity and `target` is any `2^exp`-th root of unity.
+fn root_of_unity_log(base: &Fr, target: &Fr, exp: u32) -> u64 {
+    let base_inv = base.inverse().unwrap();
+    let mut k = 0;
+    // base^-k and base^-(2^i)
+    let mut base_inv_k = Fr::one();
+    let mut base_inv_pow = base_inv;
+    for i in 0..exp {
+        let mut t = *target;
+        t.mul_assign(&base_inv_k);
+        for _ in i + 1..exp {
+            t.square();
+        }
+        if t != Fr::one() {
+            k |= 1 << i;
+            base_inv_k.mul_assign(&base_inv_pow);
+        }
+        base_inv_pow.square();
+    }
+    k
+}
+
+/// snarkjs keeps the coefficients of the constraints multiplied by `R^2`,
+/// `R` being the Montgomery constant.
+fn coefficient_factor() -> Fr {
+    let mut factor = Fr::from_repr(Fr::one().into_raw_repr()).unwrap();
+    factor.square();
+    factor
+}
+
+fn write_section_header<W: Write>(writer: &mut W, section_type: u32, size: u64) -> io::Result<()> {
+    writer.write_u32::<LittleEndian>(section_type)?;
+    writer.write_u64::<LittleEndian>(size)
+}
+
+fn write_field_header<F: PrimeField, W: Write>(writer: &mut W) -> io::Result<()> {
+    writer.write_u32::<LittleEndian>(FIELD_SIZE as u32)?;
+    F::char().write_le(writer)
+}
+
+/// Writes a point with Montgomery form coordinates, the point at infinity
+/// being all zeroes.
+fn write_point<G: RawEncodable, W: Write>(writer: &mut W, point: &G) -> io::Result<()> {
+    let encoded = if point.is_zero() {
+        G::Uncompressed::empty()
+    } else {
+        point.into_raw_uncompressed_le()
+    };
+    writer.write_all(encoded.as_ref())
+}
+
+fn read_point<G: RawEncodable, R: Read>(reader: &mut R) -> Result<G, ZkeyError> {
+    let mut encoded = G::Uncompressed::empty();
+    reader.read_exact(encoded.as_mut())?;
+    G::from_raw_uncompressed_le(&encoded, false).map_err(ZkeyError::InvalidPoint)
+}
+
+pub(crate) fn read_points<G: RawEncodable, R: Read>(reader: &mut R, count: usize) -> Result<Vec<G>, ZkeyError> {
+    (0..count).map(|_| read_point(reader)).collect()
+}
+
+fn read_g2_point<R: Read>(reader: &mut R) -> Result<G2Affine, ZkeyError> {
+    check_g2_subgroup(read_point(reader)?).map_err(ZkeyError::InvalidPoint)
+}
+
+fn read_g2_points<R: Read>(reader: &mut R, count: usize) -> Result<Vec<G2Affine>, ZkeyError> {
+    (0..count).map(|_| read_g2_point(reader)).collect()
+}
+
+pub fn write_zkey_file(params: &MPCParameters, circuit: &CircomCircuit<Bn256>, filename: &str) -> Result<(), ZkeyError> {
+    let writer = File::create(filename)?;
+    let mut writer = BufWriter::new(writer);
+    write_zkey(params, circuit, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the parameters as a snarkjs Groth16 `.zkey`, which snarkjs can
+/// prove with. The circuit must be the one the parameters were created
+/// for, with points at infinity left in. The cs_hash and the transcript
+/// of contributions are kept in the contributions section, but they
+/// follow the phase2 scheme, so `snarkjs zkey verify` won't accept them.
+pub fn write_zkey<W: Write>(mpc_params: &MPCParameters, circuit: &CircomCircuit<Bn256>, mut writer: W) -> Result<(), ZkeyError> {
+    let params = mpc_params.get_params();
+    let num_inputs = circuit.num_inputs;
+    let num_variables = circuit.num_inputs + circuit.num_aux;
+    // bellman adds a constraint for each input to the ones of the circuit
+    let num_constraints = circuit.constraints.len() + num_inputs;
+
+    if params.vk.ic.len() != num_inputs ||
+        params.l.len() != circuit.num_aux ||
+        params.a.len() != num_variables ||
+        params.b_g1.len() != num_variables ||
+        params.b_g2.len() != num_variables
+    {
+        return Err(ZkeyError::ParametersMismatch);
+    }
+
+    let mut exp = 0;
+    while (1 << exp) < num_constraints {
+        exp += 1;
+    }
+    let domain = DomainMapping::new(exp)?;
+    if params.h.len() != domain.m - 1 {
+        return Err(ZkeyError::ParametersMismatch);
+    }
+
+    let num_coefs = circuit.constraints.iter().map(|c| c.0.len() + c.1.len()).sum::<usize>() + num_inputs;
+    let contributions = mpc_params.get_contributions();
+
+    writer.write_all(b"zkey")?;
+    writer.write_u32::<LittleEndian>(ZKEY_VERSION)?;
+    writer.write_u32::<LittleEndian>(NUM_SECTIONS)?;
+
+    write_section_header(&mut writer, 1, 4)?;
+    writer.write_u32::<LittleEndian>(PROTOCOL_GROTH16)?;
+
+    write_section_header(&mut writer, 2, 2 * (4 + FIELD_SIZE) + 3 * 4 + 3 * G1_SIZE + 3 * G2_SIZE)?;
+    write_field_header::<Fq, _>(&mut writer)?;
+    write_field_header::<Fr, _>(&mut writer)?;
+    writer.write_u32::<LittleEndian>(num_variables as u32)?;
+    writer.write_u32::<LittleEndian>((num_inputs - 1) as u32)?;
+    writer.write_u32::<LittleEndian>(domain.m as u32)?;
+    write_point(&mut writer, &params.vk.alpha_g1)?;
+    write_point(&mut writer, &params.vk.beta_g1)?;
+    write_point(&mut writer, &params.vk.beta_g2)?;
+    write_point(&mut writer, &params.vk.gamma_g2)?;
+    write_point(&mut writer, &params.vk.delta_g1)?;
+    write_point(&mut writer, &params.vk.delta_g2)?;
+
+    write_section_header(&mut writer, 3, num_inputs as u64 * G1_SIZE)?;
+    for p in params.vk.ic.iter() {
+        write_point(&mut writer, p)?;
+    }
+
+    write_section_header(&mut writer, 4, 4 + num_coefs as u64 * (3 * 4 + FIELD_SIZE))?;
+    writer.write_u32::<LittleEndian>(num_coefs as u32)?;
+    let factor = coefficient_factor();
+    let mut write_coef = |matrix: u32, position: usize, index: usize, coeff: &Fr| -> io::Result<()> {
+        let mut value = *coeff;
+        value.mul_assign(&factor);
+        writer.write_u32::<LittleEndian>(matrix)?;
+        writer.write_u32::<LittleEndian>(domain.to_snarkjs(position) as u32)?;
+        writer.write_u32::<LittleEndian>(index as u32)?;
+        value.into_repr().write_le(&mut writer)
+    };
+    for (position, constraint) in circuit.constraints.iter().enumerate() {
+        for (index, coeff) in constraint.0.iter() {
+            write_coef(0, position, *index, coeff)?;
+        }
+    }
+    for i in 0..num_inputs {
+        write_coef(0, circuit.constraints.len() + i, i, &Fr::one())?;
+    }
+    for (position, constraint) in circuit.constraints.iter().enumerate() {
+        for (index, coeff) in constraint.1.iter() {
+            write_coef(1, position, *index, coeff)?;
+        }
+    }
+
+    write_section_header(&mut writer, 5, num_variables as u64 * G1_SIZE)?;
+    for p in params.a.iter() {
+        write_point(&mut writer, p)?;
+    }
+
+    write_section_header(&mut writer, 6, num_variables as u64 * G1_SIZE)?;
+    for p in params.b_g1.iter() {
+        write_point(&mut writer, p)?;
+    }
+
+    write_section_header(&mut writer, 7, num_variables as u64 * G2_SIZE)?;
+    for p in params.b_g2.iter() {
+        write_point(&mut writer, p)?;
+    }
+
+    write_section_header(&mut writer, 8, circuit.num_aux as u64 * G1_SIZE)?;
+    for p in params.l.iter() {
+        write_point(&mut writer, p)?;
+    }
+
+    write_section_header(&mut writer, 9, domain.m as u64 * G1_SIZE)?;
+    for p in domain.h_to_snarkjs(&params.h, &Worker::new()) {
+        write_point(&mut writer, &p)?;
+    }
+
+    write_section_header(&mut writer, 10, 64 + 4 + contributions.len() as u64 * (3 * G1_SIZE + G2_SIZE + 64 + 4 + 4))?;
+    writer.write_all(mpc_params.get_cs_hash())?;
+    writer.write_u32::<LittleEndian>(contributions.len() as u32)?;
+    for pubkey in contributions {
+        write_point(&mut writer, &pubkey.delta_after)?;
+        write_point(&mut writer, &pubkey.s)?;
+        write_point(&mut writer, &pubkey.s_delta)?;
+        write_point(&mut writer, &pubkey.r_delta)?;
+        writer.write_all(&pubkey.transcript)?;
+        // contribution type and the length of its parameters
+        writer.write_u32::<LittleEndian>(0)?;
+        writer.write_u32::<LittleEndian>(0)?;
+    }
+
+    Ok(())
+}
+
+pub fn read_zkey_file(filename: &str) -> Result<(Parameters<Bn256>, ConstraintLayout), ZkeyError> {
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(filename)?;
+    read_zkey(BufReader::new(reader))
+}
+
+/// Reads a snarkjs Groth16 `.zkey` into parameters bellman can prove with,
+/// along with the layout of their constraints. Keys written by `write_zkey`
+/// and by `snarkjs zkey new` are accepted, keys which place the constraints
+/// in any other order aren't.
+pub fn read_zkey<R: Read + Seek>(mut reader: R) -> Result<(Parameters<Bn256>, ConstraintLayout), ZkeyError> {
+    let sections = read_sections(&mut reader, "zkey", ZKEY_VERSION)?;
+
+    seek_section(&mut reader, &sections, 1)?;
+    let protocol = reader.read_u32::<LittleEndian>()?;
+    if protocol != PROTOCOL_GROTH16 {
+        return Err(ZkeyError::UnsupportedProtocol(protocol));
+    }
+
+    seek_section(&mut reader, &sections, 2)?;
+    read_field_header::<Fq, _>(&mut reader)?;
+    read_field_header::<Fr, _>(&mut reader)?;
+    let num_variables = reader.read_u32::<LittleEndian>()? as usize;
+    let num_public = reader.read_u32::<LittleEndian>()? as usize;
+    let domain_size = reader.read_u32::<LittleEndian>()? as usize;
+    if !domain_size.is_power_of_two() {
+        return Err(ZkeyError::UnsupportedDomainSize(domain_size));
+    }
+    let domain = DomainMapping::new(domain_size.trailing_zeros())?;
+    if num_public >= num_variables {
+        return Err(ZkeyError::IndexOutOfRange { index: num_public, num_variables });
+    }
+
+    let vk = VerifyingKey {
+        alpha_g1: read_point(&mut reader)?,
+        beta_g1: read_point(&mut reader)?,
+        beta_g2: read_g2_point(&mut reader)?,
+        gamma_g2: read_g2_point(&mut reader)?,
+        delta_g1: read_point(&mut reader)?,
+        delta_g2: read_g2_point(&mut reader)?,
+        ic: {
+            seek_section(&mut reader, &sections, 3)?;
+            read_points(&mut reader, num_public + 1)?
+        },
+    };
+
+    seek_section(&mut reader, &sections, 4)?;
+    let layout = constraint_layout(&mut reader, &domain, num_public)?;
+
+    seek_section(&mut reader, &sections, 5)?;
+    let a = read_points(&mut reader, num_variables)?;
+    seek_section(&mut reader, &sections, 6)?;
+    let b_g1 = read_points(&mut reader, num_variables)?;
+    seek_section(&mut reader, &sections, 7)?;
+    let b_g2 = read_g2_points(&mut reader, num_variables)?;
+    seek_section(&mut reader, &sections, 8)?;
+    let l = read_points(&mut reader, num_variables - num_public - 1)?;
+    seek_section(&mut reader, &sections, 9)?;
+    let h = read_points(&mut reader, domain.m)?;
+
+    let params = Parameters {
+        vk,
+        h: Arc::new(domain.h_from_snarkjs(&h, &Worker::new())),
+        l: Arc::new(l),
+        a: Arc::new(a),
+        b_g1: Arc::new(b_g1),
+        b_g2: Arc::new(b_g2),
+    };
+    Ok((params, layout))
+}
+
+/// bellman's prover puts the constraints of the circuit at the points of
+/// its domain in the order they are synthesized, followed by a constraint
+/// `x_i * 0 = 0` for each input, and `snarkjs zkey new` does the same on
+/// its own domain. Finds the domain on which the zkey has those input
+/// constraints last and in order.
+fn constraint_layout<R: Read>(reader: &mut R, domain: &DomainMapping, num_public: usize) -> Result<ConstraintLayout, ZkeyError> {
+    let one = coefficient_factor().into_repr();
+    let num_coefs = reader.read_u32::<LittleEndian>()?;
+
+    let mut used = vec![false; domain.m];
+    let mut input_constraints = vec![vec![]; num_public + 1];
+    for _ in 0..num_coefs {
+        let matrix = reader.read_u32::<LittleEndian>()?;
+        let constraint = reader.read_u32::<LittleEndian>()? as usize;
+        let signal = reader.read_u32::<LittleEndian>()? as usize;
+        let mut value = FrRepr::default();
+        value.read_le(&mut *reader)?;
+
+        if constraint >= domain.m {
+            return Err(ZkeyError::UnsupportedConstraintLayout);
+        }
+        used[constraint] = true;
+        if matrix == 0 && signal <= num_public && value == one {
+            input_constraints[signal].push(constraint);
+        }
+    }
+
+    // Whether the input constraints come last and in order once the
+    // constraints are mapped to positions on a domain
+    let inputs_last = |to_position: &dyn Fn(usize) -> usize| {
+        let last_position = (0..domain.m).filter(|&c| used[c]).map(to_position).max();
+        match last_position.and_then(|p| p.checked_sub(num_public)) {
+            Some(first_input) => input_constraints.iter().enumerate()
+                .all(|(i, constraints)| constraints.iter().any(|&c| to_position(c) == first_input + i)),
+            None => false,
+        }
+    };
+    if inputs_last(&|c| domain.to_bellman(c)) {
+        Ok(ConstraintLayout::Bellman)
+    } else if inputs_last(&|c| c) {
+        Ok(ConstraintLayout::Snarkjs)
+    } else {
+        Err(ZkeyError::UnsupportedConstraintLayout)
+    }
+}
+
+/// Proves with parameters whose constraints are laid out as `layout`,
+/// moving the constraints of a `ConstraintLayout::Snarkjs` key to the
+/// points of bellman's domain snarkjs has them at.
+pub fn prove_with_layout<R: Rng>(circuit: CircomCircuit<Bn256>, params: &Parameters<Bn256>, layout: ConstraintLayout, mut rng: R) -> Result<Proof<Bn256>, SynthesisError> {
+    if layout == ConstraintLayout::Bellman {
+        return prove(circuit, params, rng);
+    }
+
+    // The H query has a point for every power of tau below m - 1
+    let exp = (params.h.len() + 1).trailing_zeros();
+    let domain = DomainMapping::new(exp).map_err(|_| SynthesisError::PolynomialDegreeTooLarge)?;
+    let mut prover = prepare_prover(circuit)?;
+    prover.place_constraints(domain.m, |i| domain.to_bellman(i))?;
+
+    let mut params = params.clone();
+    filter_params(&mut params);
+    prover.create_random_proof(&params, &mut rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use circom_circuit::{
+        circuit_from_r1cs_file,
+        witness_from_json_file,
+        verification_key_from_json_file,
+        verify,
+        verify_with_key,
+    };
+    use circom_circuit::tests::multiplier_circuit;
+    use phase1::tests::TestPowers;
+    use rand::{SeedableRng, XorShiftRng};
+    use std::io::Cursor;
+
+    /// `x_{i+1} = x_i^2` for `num_squares` steps, with the last one as the
+    /// public output, so the domain is larger than in `multiplier_circuit`.
+    fn squaring_circuit(num_squares: usize) -> CircomCircuit<Bn256> {
+        let mut x = Fr::from_str("3").unwrap();
+        // one, the output, then x_0..x_{num_squares - 1}
+        let mut witness = vec![Fr::one(), Fr::zero(), x];
+        let mut constraints = vec![];
+        for i in 0..num_squares {
+            x.square();
+            let next = if i + 1 == num_squares { 1 } else { i + 3 };
+            constraints.push((vec![(i + 2, Fr::one())], vec![(i + 2, Fr::one())], vec![(next, Fr::one())]));
+            if next == 1 {
+                witness[1] = x;
+            } else {
+                witness.push(x);
+            }
+        }
+
+        CircomCircuit {
+            num_inputs: 2,
+            num_aux: num_squares,
+            num_constraints: num_squares,
+            witness: Some(witness),
+            constraints,
+        }
+    }
+
+    fn check_roundtrip(circuit: CircomCircuit<Bn256>) {
+        circuit.check_witness().unwrap();
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let mut params = MPCParameters::new_with_source(circuit.clone(), false, &mut TestPowers::new(6)).unwrap();
+        params.contribute(&mut rng, &0);
+
+        let mut zkey = vec![];
+        write_zkey(&params, &circuit, &mut zkey).unwrap();
+        let (read, layout) = read_zkey(Cursor::new(&zkey)).unwrap();
+        assert!(&read == params.get_params());
+        assert_eq!(layout, ConstraintLayout::Bellman);
+
+        let proof = prove(circuit.clone(), &read, &mut rng).unwrap();
+        assert!(verify(&circuit, &read, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_zkey_roundtrip() {
+        check_roundtrip(multiplier_circuit());
+        check_roundtrip(squaring_circuit(6));
+        // a 64 points domain, on which snarkjs's roots of unity are other
+        // powers of bellman's
+        check_roundtrip(squaring_circuit(40));
+    }
+
+    /// Files circom and snarkjs wrote, taken from the test vectors of
+    /// ark-circom, see the `.circom` sources next to them.
+    fn snarkjs_fixture(name: &str) -> String {
+        format!("{}/testdata/snarkjs_{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn test_snarkjs_zkey() {
+        // A single constraint, which snarkjs puts where bellman synthesizes it
+        let mut circuit = circuit_from_r1cs_file::<Bn256>(&snarkjs_fixture("multiplier.r1cs")).unwrap();
+        let witness = witness_from_json_file::<Bn256>(&snarkjs_fixture("multiplier_witness.json")).unwrap();
+        circuit.set_witness(witness).unwrap();
+        circuit.check_witness().unwrap();
+
+        let (read, layout) = read_zkey_file(&snarkjs_fixture("multiplier.zkey")).unwrap();
+        assert_eq!(layout, ConstraintLayout::Bellman);
+
+        // the JSON has no beta_g1 and delta_g1
+        let vk = verification_key_from_json_file(&snarkjs_fixture("multiplier_vk.json")).unwrap();
+        assert!(vk.alpha_g1 == read.vk.alpha_g1);
+        assert!(vk.beta_g2 == read.vk.beta_g2);
+        assert!(vk.gamma_g2 == read.vk.gamma_g2);
+        assert!(vk.delta_g2 == read.vk.delta_g2);
+        assert!(vk.ic == read.vk.ic);
+
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let proof = prove_with_layout(circuit.clone(), &read, layout, &mut rng).unwrap();
+        assert!(verify(&circuit, &read, &proof).unwrap());
+        let inputs = circuit.get_public_inputs().unwrap();
+        assert!(verify_with_key(&vk, &proof, &inputs).unwrap());
+    }
+
+    #[test]
+    fn test_snarkjs_zkey_layout() {
+        // On a 2^14 points domain the constraints sit at other points than
+        // where bellman synthesizes them. Reading the whole key is slow, see
+        // test_snarkjs_zkey_prove, so only the layout is read here.
+        let mut reader = BufReader::new(File::open(snarkjs_fixture("squaring.zkey")).unwrap());
+        let sections = read_sections(&mut reader, "zkey", ZKEY_VERSION).unwrap();
+        seek_section(&mut reader, &sections, 2).unwrap();
+        read_field_header::<Fq, _>(&mut reader).unwrap();
+        read_field_header::<Fr, _>(&mut reader).unwrap();
+        let _num_variables = reader.read_u32::<LittleEndian>().unwrap();
+        let num_public = reader.read_u32::<LittleEndian>().unwrap() as usize;
+        assert_eq!(reader.read_u32::<LittleEndian>().unwrap(), 1 << 14);
+
+        let domain = DomainMapping::new(14).unwrap();
+        seek_section(&mut reader, &sections, 4).unwrap();
+        assert_eq!(constraint_layout(&mut reader, &domain, num_public).unwrap(), ConstraintLayout::Snarkjs);
+    }
+
+    #[test]
+    #[ignore = "converts the H query of a 2^14 points domain, run with --release"]
+    fn test_snarkjs_zkey_prove() {
+        let mut circuit = circuit_from_r1cs_file::<Bn256>(&snarkjs_fixture("squaring.r1cs")).unwrap();
+        // one, the output c, the input a, then b[0..9999]: circom merged
+        // b[9999] into c
+        let a = Fr::from_str("3").unwrap();
+        let mut b = vec![a];
+        for _ in 0..10000 {
+            let mut square = *b.last().unwrap();
+            square.square();
+            b.push(square);
+        }
+        let mut witness = vec![Fr::one(), b[10000], a];
+        witness.extend_from_slice(&b[1..10000]);
+        circuit.set_witness(witness).unwrap();
+        circuit.check_witness().unwrap();
+
+        let (read, layout) = read_zkey_file(&snarkjs_fixture("squaring.zkey")).unwrap();
+        assert_eq!(layout, ConstraintLayout::Snarkjs);
+
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let proof = prove_with_layout(circuit.clone(), &read, layout, &mut rng).unwrap();
+        assert!(verify(&circuit, &read, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_h_query_conversion() {
+        let worker = Worker::new();
+        for exp in 1..5 {
+            let domain = DomainMapping::new(exp).unwrap();
+            let h = (1..domain.m as u64)
+                .map(|i| G1Affine::one().mul(Fr::from_str(&i.to_string()).unwrap()).into_affine())
+                .collect::<Vec<_>>();
+            assert!(domain.h_from_snarkjs(&domain.h_to_snarkjs(&h, &worker), &worker) == h);
+
+            for position in 0..domain.m {
+                assert_eq!(domain.to_bellman(domain.to_snarkjs(position)), position);
+            }
+        }
+    }
+
+    #[test]
+    fn test_snarkjs_root_of_unity() {
+        // `Fr.w[Fr.s]` of ffjavascript's bn128
+        assert_eq!(
+            snarkjs_root_of_unity(),
+            Fr::from_str("19103219067921713944291392827692070036145651957329286315305642004821462161904").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_coefficient_factor() {
+        // snarkjs stores 1 as R^2 mod r, R = 2^256
+        let mut r = Fr::from_str("2").unwrap();
+        for _ in 0..8 {
+            r.square();
+        }
+        r.square();
+        assert_eq!(coefficient_factor(), r);
+        assert_eq!(
+            format!("{}", coefficient_factor().into_repr()),
+            "0x0216d0b17f4e44a58c49833d53bb808553fe3ab1e35c59e31bb8e645ae216da7"
+        );
+    }
+
+    #[test]
+    fn test_invalid_zkey() {
+        let circuit = multiplier_circuit();
+        let params = MPCParameters::new_with_source(circuit.clone(), false, &mut TestPowers::new(4)).unwrap();
+        let mut zkey = vec![];
+        write_zkey(&params, &circuit, &mut zkey).unwrap();
+
+        // The protocol is the only word of section 1
+        let mut plonk = zkey.clone();
+        plonk[24] = 2;
+        match read_zkey(Cursor::new(plonk)) {
+            Err(ZkeyError::UnsupportedProtocol(2)) => {},
+            _ => panic!("non-Groth16 zkey accepted"),
+        }
+
+        match write_zkey(&params, &squaring_circuit(6), vec![]) {
+            Err(ZkeyError::ParametersMismatch) => {},
+            _ => panic!("parameters of another circuit accepted"),
+        }
+    }
+
+    #[test]
+    fn test_zkey_g2_outside_subgroup() {
+        use bellman_ce::pairing::bn256::G2Compressed;
+
+        let circuit = multiplier_circuit();
+        let params = MPCParameters::new_with_source(circuit.clone(), false, &mut TestPowers::new(4)).unwrap();
+        let mut zkey = vec![];
+        write_zkey(&params, &circuit, &mut zkey).unwrap();
+
+        // The point of the twist with the smallest x, its y is computed
+        // from the curve equation but it isn't in the subgroup because of
+        // the cofactor
+        let point = (1u8..).filter_map(|x| {
+            let mut encoded = G2Compressed::empty();
+            encoded.as_mut()[63] = x;
+            encoded.into_affine_unchecked().ok()
+        }).next().unwrap();
+        assert!(!point.mul(Fr::char()).is_zero());
+
+        // beta_g2 follows the header, the protocol section, the field
+        // headers of section 2, its counts, alpha_g1 and beta_g1
+        let offset = 12 + 16 + 12 + 72 + 12 + 64 + 64;
+        let beta_g2 = params.get_params().vk.beta_g2.into_raw_uncompressed_le();
+        assert_eq!(&zkey[offset..offset + 128], beta_g2.as_ref());
+        zkey[offset..offset + 128].copy_from_slice(point.into_raw_uncompressed_le().as_ref());
+
+        match read_zkey(Cursor::new(zkey)) {
+            Err(ZkeyError::InvalidPoint(GroupDecodingError::NotInSubgroup)) => {},
+            _ => panic!("G2 point outside the subgroup accepted"),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+// Test vector of ark-circom 0.1.0 (arkworks-rs/circom-compat@170b10f,
+// MIT/Apache-2.0), compiled with circom 1 and set up with `snarkjs zkey new`.
+// circom wrote the .r1cs next to it and snarkjs the .zkey.
+
+template Multiplier() {
+    signal private input a;
+    signal private input b;
+    signal output c;
+
+    c <== a*b;
+}
+
+component main = Multiplier();
+
@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT
+
+pragma solidity >=0.7.5 <0.9.0;
+
+contract Verifier {
+    uint256 constant SNARK_SCALAR_FIELD = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    uint256 constant PRIME_Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+    uint256 constant NUM_INPUTS = 2;
+
+    /*
+     * @returns Whether the proof is valid given the hardcoded verifying key
+     *          and the public inputs. The proof is
+     *          [A.X, A.Y, B.X[1], B.X[0], B.Y[1], B.Y[0], C.X, C.Y].
+     */
+    function verifyProof(
+        uint256[] calldata proof,
+        uint256[] calldata input
+    ) external view returns (bool) {
+        require(proof.length == 8, "verifier-bad-proof-length");
+        require(input.length == NUM_INPUTS, "verifier-bad-input-length");
+        checkProofElements(proof);
+
+        (uint256 x, uint256 y) = accumulateInputs(input, 0);
+        return pairingCheck(proof, x, y);
+    }
+
+    /*
+     * Makes sure that each element of the proofs is less than the prime q.
+     */
+    function checkProofElements(uint256[] calldata proof) internal pure {
+        for (uint256 i = 0; i < proof.length; i++) {
+            require(proof[i] < PRIME_Q, "verifier-proof-element-gte-prime-q");
+        }
+    }
+
+    /*
+     * @return The linear combination vk_x of the NUM_INPUTS public inputs
+     *         starting at offset.
+     */
+    function accumulateInputs(
+        uint256[] calldata input,
+        uint256 offset
+    ) internal view returns (uint256 x, uint256 y) {
+        x = 14095740082478987437073865342774776026730584368930491895342809603479357482673;
+        y = 9081764175410221207477367145584067665833563594924231983527755305859217126035;
+        (x, y) = mulAdd(x, y, 6186102199543374089043631886181599044850643804265332006166119309247399675094, 19086505670173288730322067296783725170989314545489087125030863152201162529878, input[offset + 0]);
+        (x, y) = mulAdd(x, y, 19401258738648811418700978679021053896753634569829649366615753585363014044587, 21077649644743103690003768529360255635170180584252248807325918368797704430441, input[offset + 1]);
+    }
+
+    /*
+     * @return The point (x, y) + s * (px, py), making sure that s is less
+     *         than the snark scalar field.
+     */
+    function mulAdd(
+        uint256 x,
+        uint256 y,
+        uint256 px,
+        uint256 py,
+        uint256 s
+    ) internal view returns (uint256 rx, uint256 ry) {
+        require(s < SNARK_SCALAR_FIELD, "verifier-gte-snark-scalar-field");
+        bool success;
+        // solium-disable-next-line security/no-inline-assembly
+        assembly {
+            let m := mload(0x40)
+            mstore(m, px)
+            mstore(add(m, 0x20), py)
+            mstore(add(m, 0x40), s)
+            success := staticcall(gas(), 7, m, 0x60, m, 0x40)
+            mstore(add(m, 0x40), x)
+            mstore(add(m, 0x60), y)
+            success := and(success, staticcall(gas(), 6, m, 0x80, m, 0x40))
+            rx := mload(m)
+            ry := mload(add(m, 0x20))
+        }
+        require(success, "verifier-ec-operation-failed");
+    }
+
+    /*
+     * @return ok Whether e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1
+     */
+    function pairingCheck(
+        uint256[] calldata proof,
+        uint256 x,
+        uint256 y
+    ) internal view returns (bool ok) {
+        // solium-disable-next-line security/no-inline-assembly
+        assembly {
+            let p := mload(0x40)
+            mstore(p, calldataload(proof.offset))
+            mstore(add(p, 0x20), mod(sub(21888242871839275222246405745257275088696311157297823662689037894645226208583, calldataload(add(proof.offset, 0x20))), 21888242871839275222246405745257275088696311157297823662689037894645226208583))
+            calldatacopy(add(p, 0x40), add(proof.offset, 0x40), 0x80)
+            mstore(add(p, 0xc0), 6310008810243830581197505401117767085462255230026528671403522275274813379351)
+            mstore(add(p, 0xe0), 339626861388516248463704840843642703543453617074289972281365202521917644264)
+            mstore(add(p, 0x100), 19907634416938982579198621480169269848796446632568524698200956001758822211885)
+            mstore(add(p, 0x120), 9291604783473594546349294075758815307407236633215202163728107812866813492163)
+            mstore(add(p, 0x140), 14149750691782567729702665544433731516308254462879222874809470403373607385212)
+            mstore(add(p, 0x160), 9143367822177456053726322185772891125198995121973303527551732517977794031006)
+            mstore(add(p, 0x180), x)
+            mstore(add(p, 0x1a0), y)
+            mstore(add(p, 0x1c0), 11535786220493255152695770514872617607797600413318764668442586318451075072075)
+            mstore(add(p, 0x1e0), 19333245064589668492710373558974029342904999140822717256593139538455132605275)
+            mstore(add(p, 0x200), 15960058711090140352962701449586951764437034694208959371038405982535894622747)
+            mstore(add(p, 0x220), 21752998377183939860100265268480361014624750953017399755455953729611542405770)
+            calldatacopy(add(p, 0x240), add(proof.offset, 0xc0), 0x40)
+            mstore(add(p, 0x280), 7328380951351713321031237544050600550980891702805348102968917089454045712986)
+            mstore(add(p, 0x2a0), 13505117679789909407934673249376373996429584386738088862185812737547096793167)
+            mstore(add(p, 0x2c0), 11443130431940506282314796955638739134990224860430503991731293208392178283333)
+            mstore(add(p, 0x2e0), 13743182282888927751969020659121210138187502803032644432360510348264544891753)
+            let success := staticcall(gas(), 8, p, 0x300, 0x00, 0x20)
+            ok := and(success, mload(0x00))
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Groth16 verifier on the BN254 curve with the verifying key baked in.
+//!
+//! Field elements are 32 bytes big-endian and proofs are laid out like the
+//! calldata of the Solidity verifiers:
+//! `[A.X, A.Y, B.X.c1, B.X.c0, B.Y.c1, B.Y.c0, C.X, C.Y]`.
+
+#![no_std]
+
+use bn::{pairing_batch, AffineG1, AffineG2, Fq, Fq2, Fr, Group, Gt, G1, G2};
+
+/// The number of public inputs of the circuit.
+pub const NUM_INPUTS: usize = 1;
+
+/// The verifying key, with G1 points as `[x, y]` and G2 points as
+/// `[x.c1, x.c0, y.c1, y.c0]`.
+pub const ALPHA_G1: [[u8; 32]; 2] = [[0x17, 0x07, 0x2b, 0x2e, 0xd3, 0xbb, 0x8d, 0x75, 0x9a, 0x53, 0x25, 0xf4, 0x77, 0x62, 0x93, 0x86, 0xcb, 0x6f, 0xc6, 0xec, 0xb8, 0x01, 0xbd, 0x76, 0x98, 0x3a, 0x6b, 0x86, 0xab, 0xff, 0xe0, 0x78], [0x16, 0x8a, 0xda, 0x6c, 0xd1, 0x30, 0xdd, 0x52, 0x01, 0x7b, 0xb5, 0x4b, 0xfa, 0x19, 0x37, 0x7a, 0xad, 0xfe, 0x3b, 0xf0, 0x5d, 0x18, 0xf4, 0x1b, 0x77, 0x80, 0x9f, 0x7f, 0x60, 0xd4, 0xaf, 0x9e]];
+pub const BETA_G2: [[u8; 32]; 4] = [
+    [0x22, 0x8b, 0x51, 0x5a, 0x17, 0xf2, 0x8b, 0x89, 0x92, 0x08, 0x73, 0x20, 0x74, 0x77, 0xf8, 0xc7, 0xfc, 0x05, 0x58, 0x2d, 0xeb, 0xaf, 0x31, 0x84, 0xfe, 0xbf, 0x1c, 0xfd, 0xed, 0xc5, 0xce, 0x88],
+    [0x12, 0xbb, 0x11, 0x56, 0xa9, 0xf6, 0xb3, 0x60, 0xfc, 0xb2, 0x61, 0x4e, 0x15, 0xd8, 0xa3, 0xff, 0x07, 0xf2, 0xc6, 0x99, 0xdc, 0x69, 0xca, 0x83, 0x0b, 0x20, 0xd2, 0xdf, 0x91, 0xfe, 0x9c, 0xd3],
+    [0x2b, 0x15, 0xdc, 0x62, 0xa5, 0xc9, 0xe3, 0x65, 0x97, 0x91, 0x4d, 0xdb, 0xbf, 0xde, 0x48, 0x80, 0x6a, 0x8e, 0xab, 0xe4, 0x5c, 0x8d, 0x3c, 0xcc, 0xf9, 0x57, 0x8a, 0xd0, 0x8e, 0x05, 0x8f, 0x92],
+    [0x02, 0xa4, 0xfd, 0x76, 0x4f, 0x52, 0x47, 0x0e, 0x2f, 0xcf, 0xff, 0x32, 0x5f, 0xb9, 0x69, 0x2f, 0x55, 0xd6, 0xb8, 0xb0, 0x77, 0xee, 0xfe, 0xaa, 0x04, 0xe0, 0x71, 0x52, 0xb4, 0xd1, 0xfa, 0x94],
+];
+pub const GAMMA_G2: [[u8; 32]; 4] = [
+    [0x19, 0x8e, 0x93, 0x93, 0x92, 0x0d, 0x48, 0x3a, 0x72, 0x60, 0xbf, 0xb7, 0x31, 0xfb, 0x5d, 0x25, 0xf1, 0xaa, 0x49, 0x33, 0x35, 0xa9, 0xe7, 0x12, 0x97, 0xe4, 0x85, 0xb7, 0xae, 0xf3, 0x12, 0xc2],
+    [0x18, 0x00, 0xde, 0xef, 0x12, 0x1f, 0x1e, 0x76, 0x42, 0x6a, 0x00, 0x66, 0x5e, 0x5c, 0x44, 0x79, 0x67, 0x43, 0x22, 0xd4, 0xf7, 0x5e, 0xda, 0xdd, 0x46, 0xde, 0xbd, 0x5c, 0xd9, 0x92, 0xf6, 0xed],
+    [0x09, 0x06, 0x89, 0xd0, 0x58, 0x5f, 0xf0, 0x75, 0xec, 0x9e, 0x99, 0xad, 0x69, 0x0c, 0x33, 0x95, 0xbc, 0x4b, 0x31, 0x33, 0x70, 0xb3, 0x8e, 0xf3, 0x55, 0xac, 0xda, 0xdc, 0xd1, 0x22, 0x97, 0x5b],
+    [0x12, 0xc8, 0x5e, 0xa5, 0xdb, 0x8c, 0x6d, 0xeb, 0x4a, 0xab, 0x71, 0x80, 0x8d, 0xcb, 0x40, 0x8f, 0xe3, 0xd1, 0xe7, 0x69, 0x0c, 0x43, 0xd3, 0x7b, 0x4c, 0xe6, 0xcc, 0x01, 0x66, 0xfa, 0x7d, 0xaa],
+];
+pub const DELTA_G2: [[u8; 32]; 4] = [
+    [0x10, 0xa1, 0x9d, 0xc4, 0x63, 0xc4, 0x2c, 0x30, 0x02, 0x51, 0x5f, 0x56, 0xd3, 0x67, 0x44, 0x51, 0x27, 0x11, 0x21, 0x92, 0x14, 0xe3, 0xa9, 0x40, 0xdc, 0x2e, 0x12, 0xad, 0x51, 0xcd, 0x40, 0xb9],
+    [0x09, 0x1f, 0x20, 0xe0, 0x35, 0xd1, 0x82, 0x8f, 0xcf, 0xc8, 0xd2, 0x9d, 0x96, 0x05, 0x07, 0x16, 0xc6, 0x93, 0xfd, 0x5d, 0x3c, 0xe8, 0xbd, 0x2c, 0xec, 0xf3, 0x8b, 0xad, 0x77, 0xc3, 0x14, 0x2a],
+    [0x2f, 0xf7, 0x93, 0x67, 0xdb, 0xa1, 0x76, 0x9d, 0xfc, 0x0e, 0x42, 0x6c, 0x31, 0xef, 0x7e, 0x72, 0xc9, 0x51, 0x41, 0xd6, 0xe3, 0x9f, 0xde, 0xb6, 0x84, 0x9c, 0x52, 0xd6, 0x75, 0x98, 0x5c, 0x75],
+    [0x0a, 0xa0, 0x51, 0xec, 0x86, 0x89, 0x87, 0x31, 0xd9, 0x67, 0xe3, 0x9b, 0x30, 0x45, 0x9f, 0xc3, 0x1e, 0x73, 0x44, 0xe8, 0x56, 0xed, 0x91, 0x86, 0x97, 0x18, 0xcf, 0x39, 0x5d, 0x9a, 0xdf, 0x57],
+];
+pub const IC: [[[u8; 32]; 2]; NUM_INPUTS + 1] = [
+    [[0x0b, 0x2d, 0x47, 0xa2, 0x8e, 0x28, 0x5a, 0x95, 0x69, 0x48, 0x52, 0x4a, 0x16, 0x2c, 0xa7, 0x62, 0x51, 0xe6, 0x17, 0xa0, 0x69, 0x63, 0x36, 0x53, 0xb5, 0xf7, 0x32, 0x30, 0xa6, 0xe8, 0xb1, 0x24], [0x0d, 0x17, 0x6d, 0x7d, 0x56, 0xc4, 0x0b, 0xd9, 0x63, 0x50, 0x13, 0xc0, 0xf3, 0x15, 0x18, 0x5c, 0x97, 0x9c, 0x6b, 0xd8, 0x17, 0x11, 0x87, 0x18, 0x09, 0x8f, 0xde, 0xf2, 0x06, 0x4b, 0x04, 0x01]],
+    [[0x28, 0x3a, 0x4f, 0x00, 0xfb, 0xf8, 0xbf, 0x4a, 0x2a, 0x67, 0xbc, 0x02, 0x9a, 0xcd, 0x5e, 0x10, 0x0d, 0x91, 0x83, 0xd1, 0x55, 0xba, 0xa1, 0xf7, 0x76, 0x2d, 0xf9, 0xc1, 0x39, 0xb7, 0xf1, 0xaf], [0x2f, 0xbd, 0x82, 0x31, 0x2a, 0x92, 0x54, 0x26, 0x25, 0x58, 0x82, 0xdd, 0xdf, 0x48, 0x53, 0xb6, 0x4b, 0x22, 0x17, 0x52, 0xfc, 0x23, 0x90, 0xfc, 0x87, 0x86, 0x89, 0x3e, 0xca, 0xbb, 0xc3, 0x53]],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The proof isn't 256 bytes long.
+    InvalidProofLength,
+    /// The inputs aren't `32 * NUM_INPUTS` bytes long.
+    InvalidInputLength,
+    /// A coordinate which isn't an element of the base field, or a point
+    /// which isn't on the curve.
+    InvalidPoint,
+    /// An input which isn't an element of the scalar field.
+    InvalidInput,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Proof {
+    pub a: G1,
+    pub b: G2,
+    pub c: G1,
+}
+
+impl Proof {
+    /// Reads a proof from its 256 bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Proof, Error> {
+        if bytes.len() != 256 {
+            return Err(Error::InvalidProofLength);
+        }
+        let mut words = [[0u8; 32]; 8];
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks(32)) {
+            word.copy_from_slice(chunk);
+        }
+        Ok(Proof {
+            a: g1(&[words[0], words[1]])?,
+            b: g2(&[words[2], words[3], words[4], words[5]])?,
+            c: g1(&[words[6], words[7]])?,
+        })
+    }
+}
+
+fn fq(bytes: &[u8; 32]) -> Result<Fq, Error> {
+    Fq::from_slice(bytes).map_err(|_| Error::InvalidPoint)
+}
+
+fn g1(p: &[[u8; 32]; 2]) -> Result<G1, Error> {
+    let (x, y) = (fq(&p[0])?, fq(&p[1])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1::zero());
+    }
+    AffineG1::new(x, y).map(G1::from).map_err(|_| Error::InvalidPoint)
+}
+
+fn g2(p: &[[u8; 32]; 4]) -> Result<G2, Error> {
+    let x = Fq2::new(fq(&p[1])?, fq(&p[0])?);
+    let y = Fq2::new(fq(&p[3])?, fq(&p[2])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2::zero());
+    }
+    AffineG2::new(x, y).map(G2::from).map_err(|_| Error::InvalidPoint)
+}
+
+/// Reads public inputs from their concatenated 32-byte encodings.
+pub fn inputs_from_bytes(bytes: &[u8]) -> Result<[Fr; NUM_INPUTS], Error> {
+    if bytes.len() != 32 * NUM_INPUTS {
+        return Err(Error::InvalidInputLength);
+    }
+    let mut inputs = [Fr::zero(); NUM_INPUTS];
+    for (input, chunk) in inputs.iter_mut().zip(bytes.chunks(32)) {
+        *input = Fr::from_slice(chunk).map_err(|_| Error::InvalidInput)?;
+    }
+    Ok(inputs)
+}
+
+/// Checks the proof against the baked in verifying key and the public
+/// inputs.
+pub fn verify(proof: &Proof, inputs: &[Fr; NUM_INPUTS]) -> bool {
+    let key = "the verifying key is valid";
+    let mut acc = g1(&IC[0]).expect(key);
+    for (input, ic) in inputs.iter().zip(IC[1..].iter()) {
+        acc = acc + g1(ic).expect(key) * *input;
+    }
+
+    pairing_batch(&[
+        (-proof.a, proof.b),
+        (g1(&ALPHA_G1).expect(key), g2(&BETA_G2).expect(key)),
+        (acc, g2(&GAMMA_G2).expect(key)),
+        (proof.c, g2(&DELTA_G2).expect(key)),
+    ]) == Gt::one()
+}
+
+/// Reads a proof and its public inputs and checks them.
+pub fn verify_bytes(proof: &[u8], inputs: &[u8]) -> Result<bool, Error> {
+    Ok(verify(&Proof::from_bytes(proof)?, &inputs_from_bytes(inputs)?))
+}
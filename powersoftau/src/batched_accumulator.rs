@@ -12,7 +12,8 @@ use std::io::{self, Read, Write};
 use std::sync::{Arc, Mutex};
 use typenum::consts::U64;
 
-use super::keypair::{PrivateKey, PublicKey};
+use super::checkpoint::{Checkpoint, CheckpointError};
+use super::keypair::{key_fingerprint, PrivateKey, PublicKey};
 use super::parameters::{
     CeremonyParams, CheckForCorrectness, DeserializationError, ElementType, UseCompression,
 };
@@ -273,7 +274,7 @@ pub fn verify_transform<E: Engine>(
 
 impl<'a, E: Engine> BatchedAccumulator<'a, E> {
     /// Verifies a transformation of the `Accumulator` with the `PublicKey`, given a 64-byte transcript `digest`.
-    #[allow(clippy::too_many_arguments, clippy::cognitive_complexity)]
+    #[allow(clippy::too_many_arguments)]
     pub fn verify_transformation(
         input_map: &Mmap,
         output_map: &Mmap,
@@ -285,7 +286,44 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
         check_output_for_correctness: CheckForCorrectness,
         parameters: &'a CeremonyParams<E>,
     ) -> bool {
-        use itertools::MinMaxResult::MinMax;
+        Self::verify_transformation_head(
+            input_map,
+            output_map,
+            key,
+            digest,
+            input_is_compressed,
+            output_is_compressed,
+            check_input_for_correctness,
+            check_output_for_correctness,
+            parameters,
+        ) && (0..Self::transform_chunk_count(parameters)).all(|index| {
+            Self::verify_transformation_chunk(
+                input_map,
+                output_map,
+                input_is_compressed,
+                output_is_compressed,
+                check_input_for_correctness,
+                check_output_for_correctness,
+                index,
+                parameters,
+            )
+        })
+    }
+
+    /// The checks of `verify_transformation` which don't depend on the chunks: the proofs
+    /// of knowledge of the contribution and its first elements.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_transformation_head(
+        input_map: &Mmap,
+        output_map: &Mmap,
+        key: &PublicKey<E>,
+        digest: &[u8],
+        input_is_compressed: UseCompression,
+        output_is_compressed: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        check_output_for_correctness: CheckForCorrectness,
+        parameters: &'a CeremonyParams<E>,
+    ) -> bool {
         assert_eq!(digest.len(), 64);
 
         let tau_g2_s = compute_g2_s::<E>(&digest, &key.tau_g1.0, &key.tau_g1.1, 0);
@@ -317,24 +355,26 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
 
         {
             let chunk_size = 2;
-            before
-                .read_chunk(
-                    0,
-                    chunk_size,
-                    input_is_compressed,
-                    check_input_for_correctness,
-                    &input_map,
-                )
-                .expect("must read a first chunk from `challenge`");
-            after
-                .read_chunk(
-                    0,
-                    chunk_size,
-                    output_is_compressed,
-                    check_output_for_correctness,
-                    &output_map,
-                )
-                .expect("must read a first chunk from `response`");
+            if let Err(e) = before.read_chunk(
+                0,
+                chunk_size,
+                input_is_compressed,
+                check_input_for_correctness,
+                input_map,
+            ) {
+                error!("Unable to read a first chunk from `challenge`: {}", e);
+                return false;
+            }
+            if let Err(e) = after.read_chunk(
+                0,
+                chunk_size,
+                output_is_compressed,
+                check_output_for_correctness,
+                output_map,
+            ) {
+                error!("Unable to read a first chunk from `response`: {}", e);
+                return false;
+            }
 
             // Check the correctness of the generators for tau powers
             if after.tau_powers_g1[0] != E::G1Affine::one() {
@@ -381,165 +421,252 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
             }
         }
 
+        true
+    }
+
+    /// Indices of the first and the last element of the chunk `index`, as processed by
+    /// `transform` and `verify_transformation_chunk`. Chunks never cross `powers_length`.
+    pub fn chunk_bounds(index: usize, parameters: &CeremonyParams<E>) -> (usize, usize) {
+        let first_part =
+            (parameters.powers_length + parameters.batch_size - 1) / parameters.batch_size;
+        let (start, length) = if index < first_part {
+            (index * parameters.batch_size, parameters.powers_length)
+        } else {
+            (
+                parameters.powers_length + (index - first_part) * parameters.batch_size,
+                parameters.powers_g1_length,
+            )
+        };
+        assert!(start < length, "chunk {} is out of range", index);
+
+        (start, std::cmp::min(start + parameters.batch_size, length) - 1)
+    }
+
+    /// Checks that the chunk `index` of the response, together with the first element of
+    /// the next chunk, holds consecutive powers of tau. Chunks can be checked in any order,
+    /// in different threads or processes: the transformation is valid if
+    /// `verify_transformation_head` and the check of every chunk succeed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_transformation_chunk(
+        input_map: &Mmap,
+        output_map: &Mmap,
+        input_is_compressed: UseCompression,
+        output_is_compressed: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        check_output_for_correctness: CheckForCorrectness,
+        index: usize,
+        parameters: &'a CeremonyParams<E>,
+    ) -> bool {
+        let (start, end) = Self::chunk_bounds(index, parameters);
+
+        let mut before = Self::empty(parameters);
+        let mut after = Self::empty(parameters);
+
+        // The powers are checked against the first two elements of the response
+        if let Err(e) = after.read_chunk(
+            0,
+            2,
+            output_is_compressed,
+            check_output_for_correctness,
+            output_map,
+        ) {
+            error!("Unable to read a first chunk from `response`: {}", e);
+            return false;
+        }
         let tau_powers_g2_0 = after.tau_powers_g2[0];
         let tau_powers_g2_1 = after.tau_powers_g2[1];
         let tau_powers_g1_0 = after.tau_powers_g1[0];
         let tau_powers_g1_1 = after.tau_powers_g1[1];
 
-        // Read by parts and just verify same ratios. Cause of two fixed variables above with tau_powers_g2_1 = tau_powers_g2_0 ^ s
-        // one does not need to care about some overlapping
+        let is_extra_tau_g1 = start >= parameters.powers_length;
+        let length = if is_extra_tau_g1 {
+            parameters.powers_g1_length
+        } else {
+            parameters.powers_length
+        };
 
-        let mut tau_powers_last_first_chunks = vec![E::G1Affine::zero(); 2];
-        let tau_powers_length = parameters.powers_length;
-        for chunk in &(0..tau_powers_length).chunks(parameters.batch_size) {
-            if let MinMax(start, end) = chunk.minmax() {
-                // extra 1 to ensure intersection between chunks and ensure we don't overflow
-                let size = end - start + 1 + if end == tau_powers_length - 1 { 0 } else { 1 };
-                before
-                    .read_chunk(
-                        start,
-                        size,
-                        input_is_compressed,
-                        check_input_for_correctness,
-                        &input_map,
-                    )
-                    .unwrap_or_else(|_| {
-                        panic!(format!(
-                            "must read a chunk from {} to {} from `challenge`",
-                            start, end
-                        ))
-                    });
-                after
-                    .read_chunk(
-                        start,
-                        size,
-                        output_is_compressed,
-                        check_output_for_correctness,
-                        &output_map,
-                    )
-                    .unwrap_or_else(|_| {
-                        panic!(format!(
-                            "must read a chunk from {} to {} from `response`",
-                            start, end
-                        ))
-                    });
+        // extra 1 to ensure intersection between chunks and ensure we don't overflow
+        let size = end - start + 1 + if end == length - 1 { 0 } else { 1 };
+        if let Err(e) = before.read_chunk(
+            start,
+            size,
+            input_is_compressed,
+            check_input_for_correctness,
+            input_map,
+        ) {
+            error!(
+                "Unable to read a chunk from {} to {} from `challenge`: {}",
+                start, end, e
+            );
+            return false;
+        }
+        if let Err(e) = after.read_chunk(
+            start,
+            size,
+            output_is_compressed,
+            check_output_for_correctness,
+            output_map,
+        ) {
+            error!(
+                "Unable to read a chunk from {} to {} from `response`: {}",
+                start, end, e
+            );
+            return false;
+        }
 
-                // Are the powers of tau correct?
-                if !same_ratio(
-                    power_pairs(&after.tau_powers_g1),
-                    (tau_powers_g2_0, tau_powers_g2_1),
-                ) {
-                    error!("Invalid ratio power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)");
-                    return false;
-                }
-                if !same_ratio(
-                    power_pairs(&after.tau_powers_g2),
-                    (tau_powers_g1_0, tau_powers_g1_1),
-                ) {
-                    error!("Invalid ratio power_pairs(&after.tau_powers_g2), (tau_powers_g1_0, tau_powers_g1_1)");
-                    return false;
-                }
-                if !same_ratio(
-                    power_pairs(&after.alpha_tau_powers_g1),
-                    (tau_powers_g2_0, tau_powers_g2_1),
-                ) {
-                    error!("Invalid ratio power_pairs(&after.alpha_tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)");
-                    return false;
-                }
-                if !same_ratio(
-                    power_pairs(&after.beta_tau_powers_g1),
-                    (tau_powers_g2_0, tau_powers_g2_1),
-                ) {
-                    error!("Invalid ratio power_pairs(&after.beta_tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1)");
-                    return false;
-                }
-                if end == tau_powers_length - 1 {
-                    tau_powers_last_first_chunks[0] = after.tau_powers_g1[size - 1];
-                }
-                info!("Done processing {} powers of tau", end);
-            } else {
-                panic!("Chunk does not have a min and max");
-            }
+        // A single last element was already checked with the previous chunk
+        if size < 2 {
+            return true;
         }
 
-        for chunk in &(tau_powers_length..parameters.powers_g1_length).chunks(parameters.batch_size)
-        {
-            if let MinMax(start, end) = chunk.minmax() {
-                // extra 1 to ensure intersection between chunks and ensure we don't overflow
-                let size = end - start
-                    + 1
-                    + if end == parameters.powers_g1_length - 1 {
-                        0
-                    } else {
-                        1
-                    };
-                before
-                    .read_chunk(
-                        start,
-                        size,
-                        input_is_compressed,
-                        check_input_for_correctness,
-                        &input_map,
-                    )
-                    .unwrap_or_else(|_| {
-                        panic!(format!(
-                            "must read a chunk from {} to {} from `challenge`",
-                            start, end
-                        ))
-                    });
-                after
-                    .read_chunk(
-                        start,
-                        size,
-                        output_is_compressed,
-                        check_output_for_correctness,
-                        &output_map,
-                    )
-                    .unwrap_or_else(|_| {
-                        panic!(format!(
-                            "must read a chunk from {} to {} from `response`",
-                            start, end
-                        ))
-                    });
+        if !is_extra_tau_g1 {
+            // Are the powers of tau correct?
+            if !same_ratio(
+                power_pairs(&after.tau_powers_g1),
+                (tau_powers_g2_0, tau_powers_g2_1),
+            ) {
+                error!("Invalid ratio power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1) in chunk from {} to {}", start, end);
+                return false;
+            }
+            if !same_ratio(
+                power_pairs(&after.tau_powers_g2),
+                (tau_powers_g1_0, tau_powers_g1_1),
+            ) {
+                error!("Invalid ratio power_pairs(&after.tau_powers_g2), (tau_powers_g1_0, tau_powers_g1_1) in chunk from {} to {}", start, end);
+                return false;
+            }
+            if !same_ratio(
+                power_pairs(&after.alpha_tau_powers_g1),
+                (tau_powers_g2_0, tau_powers_g2_1),
+            ) {
+                error!("Invalid ratio power_pairs(&after.alpha_tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1) in chunk from {} to {}", start, end);
+                return false;
+            }
+            if !same_ratio(
+                power_pairs(&after.beta_tau_powers_g1),
+                (tau_powers_g2_0, tau_powers_g2_1),
+            ) {
+                error!("Invalid ratio power_pairs(&after.beta_tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1) in chunk from {} to {}", start, end);
+                return false;
+            }
+        } else {
+            assert_eq!(
+                after.tau_powers_g2.len(),
+                0,
+                "during rest of tau g1 generation tau g2 must be empty"
+            );
+
+            // Are the powers of tau correct?
+            if !same_ratio(
+                power_pairs(&after.tau_powers_g1),
+                (tau_powers_g2_0, tau_powers_g2_1),
+            ) {
+                error!("Invalid ratio power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1) in extra TauG1 contribution from {} to {}", start, end);
+                return false;
+            }
+        }
 
-                assert_eq!(
-                    before.tau_powers_g2.len(),
-                    0,
-                    "during rest of tau g1 generation tau g2 must be empty"
-                );
-                assert_eq!(
-                    after.tau_powers_g2.len(),
-                    0,
-                    "during rest of tau g1 generation tau g2 must be empty"
+        // The last power of the first part and the first extra one are in different chunks
+        if start == parameters.powers_length {
+            let mut last = Self::empty(parameters);
+            if let Err(e) = last.read_chunk(
+                start - 1,
+                1,
+                output_is_compressed,
+                check_output_for_correctness,
+                output_map,
+            ) {
+                error!(
+                    "Unable to read element {} from `response`: {}",
+                    start - 1,
+                    e
                 );
-
-                // Are the powers of tau correct?
-                if !same_ratio(
-                    power_pairs(&after.tau_powers_g1),
-                    (tau_powers_g2_0, tau_powers_g2_1),
-                ) {
-                    error!("Invalid ratio power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1) in extra TauG1 contribution");
-                    return false;
-                }
-                if start == parameters.powers_length {
-                    tau_powers_last_first_chunks[1] = after.tau_powers_g1[0];
-                }
-                info!("Done processing {} powers of tau", end);
-            } else {
-                panic!("Chunk does not have a min and max");
+                return false;
+            }
+            if !same_ratio(
+                power_pairs(&[last.tau_powers_g1[0], after.tau_powers_g1[0]]),
+                (tau_powers_g2_0, tau_powers_g2_1),
+            ) {
+                error!("Invalid ratio power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1) in TauG1 contribution intersection");
+                return false;
             }
         }
 
-        if !same_ratio(
-            power_pairs(&tau_powers_last_first_chunks),
-            (tau_powers_g2_0, tau_powers_g2_1),
-        ) {
-            error!("Invalid ratio power_pairs(&after.tau_powers_g1), (tau_powers_g2_0, tau_powers_g2_1) in TauG1 contribution intersection");
-            return false;
-        }
+        info!("Done processing {} powers of tau", end);
         true
     }
 
+    /// Checks the `chunks` of the response on `threads` threads, stopping at the first
+    /// invalid one. Chunks recorded in `checkpoint` are skipped and the valid ones are
+    /// recorded, so that an interrupted verification can be resumed and several processes
+    /// sharing the checkpoint file can split the chunks between them. Returns `Ok(false)`
+    /// if a chunk is invalid.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_transformation_chunks(
+        input_map: &Mmap,
+        output_map: &Mmap,
+        input_is_compressed: UseCompression,
+        output_is_compressed: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        check_output_for_correctness: CheckForCorrectness,
+        chunks: &[usize],
+        threads: usize,
+        checkpoint: Option<&mut Checkpoint>,
+        parameters: &'a CeremonyParams<E>,
+    ) -> io::Result<bool>
+    where
+        E: Sync,
+    {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        let pending: Vec<usize> = chunks
+            .iter()
+            .cloned()
+            .filter(|&i| !checkpoint.as_ref().map_or(false, |c| c.is_done(i)))
+            .collect();
+        let checkpoint = checkpoint.map(Mutex::new);
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+
+        crossbeam::scope(|scope| {
+            let workers: Vec<_> = (0..threads.max(1))
+                .map(|_| {
+                    scope.spawn(|_| -> io::Result<()> {
+                        while !failed.load(Ordering::SeqCst) {
+                            let index = match pending.get(next.fetch_add(1, Ordering::SeqCst)) {
+                                Some(&index) => index,
+                                None => break,
+                            };
+                            if !Self::verify_transformation_chunk(
+                                input_map,
+                                output_map,
+                                input_is_compressed,
+                                output_is_compressed,
+                                check_input_for_correctness,
+                                check_output_for_correctness,
+                                index,
+                                parameters,
+                            ) {
+                                failed.store(true, Ordering::SeqCst);
+                            } else if let Some(checkpoint) = checkpoint.as_ref() {
+                                // Only the verdict is recorded, both maps are read-only
+                                // so there is nothing to flush before it
+                                checkpoint.lock().unwrap().mark_done(index)?;
+                            }
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .try_for_each(|worker| worker.join().unwrap())
+        }).unwrap()?;
+
+        Ok(!failed.load(Ordering::SeqCst))
+    }
+
     pub fn decompress(
         input_map: &Mmap,
         output_map: &mut MmapMut,
@@ -1110,6 +1237,48 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
         Ok(())
     }
 
+    /// Flushes the points `write_chunk` wrote for the chunk at `chunk_start` to disk, so
+    /// that a chunk recorded as done in a checkpoint survives a crash of the machine.
+    fn flush_chunk(
+        &self,
+        chunk_start: usize,
+        compression: UseCompression,
+        output_map: &MmapMut,
+    ) -> io::Result<()> {
+        let flush = |element_type: ElementType, len: usize, end: usize| {
+            let len = len.min(end - chunk_start);
+            if len == 0 {
+                return Ok(());
+            }
+            let position = self.calculate_mmap_position(chunk_start, element_type, compression);
+            output_map.flush_range(position, len * self.get_size(element_type, compression))
+        };
+
+        let powers_g1_length = self.parameters.powers_g1_length;
+        let powers_length = self.parameters.powers_length;
+        flush(
+            ElementType::TauG1,
+            self.tau_powers_g1.len(),
+            powers_g1_length,
+        )?;
+        if chunk_start < powers_length {
+            flush(ElementType::TauG2, self.tau_powers_g2.len(), powers_length)?;
+            flush(
+                ElementType::AlphaG1,
+                self.alpha_tau_powers_g1.len(),
+                powers_length,
+            )?;
+            flush(
+                ElementType::BetaG1,
+                self.beta_tau_powers_g1.len(),
+                powers_length,
+            )?;
+            flush(ElementType::BetaG2, 1, powers_length)?;
+        }
+
+        Ok(())
+    }
+
     /// Transforms the accumulator with a private key.
     /// Due to large amount of data in a previous accumulator even in the compressed form
     /// this function can now work on compressed input. Output can be made in any form
@@ -1124,6 +1293,80 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
         check_input_for_correctness: CheckForCorrectness,
         key: &PrivateKey<E>,
         parameters: &'a CeremonyParams<E>,
+    ) -> io::Result<()> {
+        Self::transform_chunks(
+            input_map,
+            output_map,
+            input_is_compressed,
+            compress_the_output,
+            check_input_for_correctness,
+            key,
+            None,
+            parameters,
+        )
+    }
+
+    /// Same as `transform`, but records every chunk written to `output_map` in `checkpoint`
+    /// and skips the chunks it already records, so that an interrupted transformation can
+    /// be resumed with the same key and output map. The checkpoint identifies the key by
+    /// `key_fingerprint`, resuming with any other key is refused.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transform_with_checkpoint(
+        input_map: &Mmap,
+        output_map: &mut MmapMut,
+        input_is_compressed: UseCompression,
+        compress_the_output: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        key: &PrivateKey<E>,
+        checkpoint: &mut Checkpoint,
+        parameters: &'a CeremonyParams<E>,
+    ) -> Result<(), CheckpointError> {
+        if checkpoint.id() != key_fingerprint(key).as_slice() {
+            return Err(CheckpointError::KeyMismatch {
+                path: checkpoint.path().to_path_buf(),
+            });
+        }
+        let expected = Self::transform_chunk_count(parameters);
+        if checkpoint.len() != expected {
+            return Err(CheckpointError::ChunkCountMismatch {
+                path: checkpoint.path().to_path_buf(),
+                expected,
+                actual: checkpoint.len(),
+            });
+        }
+
+        Self::transform_chunks(
+            input_map,
+            output_map,
+            input_is_compressed,
+            compress_the_output,
+            check_input_for_correctness,
+            key,
+            Some(checkpoint),
+            parameters,
+        )?;
+
+        Ok(())
+    }
+
+    /// Number of chunks `transform` and `verify_transformation_chunk` process, which a
+    /// checkpoint has to track.
+    pub fn transform_chunk_count(parameters: &CeremonyParams<E>) -> usize {
+        let chunks = |len: usize| (len + parameters.batch_size - 1) / parameters.batch_size;
+        chunks(parameters.powers_length)
+            + chunks(parameters.powers_g1_length - parameters.powers_length)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn transform_chunks(
+        input_map: &Mmap,
+        output_map: &mut MmapMut,
+        input_is_compressed: UseCompression,
+        compress_the_output: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        key: &PrivateKey<E>,
+        mut checkpoint: Option<&mut Checkpoint>,
+        parameters: &'a CeremonyParams<E>,
     ) -> io::Result<()> {
         /// Exponentiate a large number of points, with an optional coefficient to be applied to the
         /// exponent.
@@ -1184,8 +1427,24 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
 
         use itertools::MinMaxResult::MinMax;
 
+        let is_done = |checkpoint: &Option<&mut Checkpoint>, index| {
+            checkpoint.as_ref().map_or(false, |c| c.is_done(index))
+        };
+        let read_error = |e: DeserializationError| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unable to read a chunk: {}", e),
+            )
+        };
+        let mut chunk_index = 0;
+
         for chunk in &(0..parameters.powers_length).chunks(parameters.batch_size) {
             if let MinMax(start, end) = chunk.minmax() {
+                chunk_index += 1;
+                if is_done(&checkpoint, chunk_index - 1) {
+                    info!("Skipping {} powers of tau done before", end);
+                    continue;
+                }
                 let size = end - start + 1;
                 accumulator
                     .read_chunk(
@@ -1195,7 +1454,7 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
                         check_input_for_correctness,
                         &input_map,
                     )
-                    .expect("must read a first chunk");
+                    .map_err(read_error)?;
 
                 // Construct the powers of tau
                 let mut taupowers = vec![E::Fr::zero(); size];
@@ -1233,6 +1492,10 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
                     "your contribution happened to produce a point at infinity, please re-run"
                 );
                 accumulator.write_chunk(start, compress_the_output, output_map)?;
+                if let Some(checkpoint) = checkpoint.as_mut() {
+                    accumulator.flush_chunk(start, compress_the_output, output_map)?;
+                    checkpoint.mark_done(chunk_index - 1)?;
+                }
                 info!("Done processing {} powers of tau", end);
             } else {
                 panic!("Chunk does not have a min and max");
@@ -1243,6 +1506,11 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
             &(parameters.powers_length..parameters.powers_g1_length).chunks(parameters.batch_size)
         {
             if let MinMax(start, end) = chunk.minmax() {
+                chunk_index += 1;
+                if is_done(&checkpoint, chunk_index - 1) {
+                    info!("Skipping {} powers of tau done before", end);
+                    continue;
+                }
                 let size = end - start + 1;
                 accumulator
                     .read_chunk(
@@ -1252,7 +1520,7 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
                         check_input_for_correctness,
                         &input_map,
                     )
-                    .expect("must read a first chunk");
+                    .map_err(read_error)?;
                 assert_eq!(
                     accumulator.tau_powers_g2.len(),
                     0,
@@ -1281,6 +1549,10 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
                 //accumulator.beta_g2 = accumulator.beta_g2.mul(key.beta).into_affine();
                 //assert!(!accumulator.beta_g2.is_zero(), "your contribution happened to produce a point at infinity, please re-run");
                 accumulator.write_chunk(start, compress_the_output, output_map)?;
+                if let Some(checkpoint) = checkpoint.as_mut() {
+                    accumulator.flush_chunk(start, compress_the_output, output_map)?;
+                    checkpoint.mark_done(chunk_index - 1)?;
+                }
 
                 info!("Done processing {} powers of tau", end);
             } else {
@@ -1346,3 +1618,112 @@ impl<'a, E: Engine> BatchedAccumulator<'a, E> {
         Ok(())
     }
 }
+
+/// An initial challenge of `parameters` in an anonymous memory map.
+#[cfg(test)]
+pub(crate) fn initial_challenge(
+    parameters: &CeremonyParams<bellman_ce::pairing::bn256::Bn256>,
+) -> Mmap {
+    let mut initial = MmapMut::map_anon(parameters.accumulator_size).unwrap();
+    BatchedAccumulator::generate_initial(&mut initial, UseCompression::No, parameters).unwrap();
+    initial.make_read_only().unwrap()
+}
+
+#[cfg(test)]
+mod bn256_tests {
+    use super::*;
+    use crate::keypair::keypair;
+    use crate::utils::temp_path;
+    use bellman_ce::pairing::bn256::Bn256;
+    use rand::thread_rng;
+    use std::fs;
+
+    #[test]
+    fn test_verify_transformation_chunks_bn256() {
+        let parameters = CeremonyParams::<Bn256>::new(6, 32);
+        let input = initial_challenge(&parameters);
+
+        let rng = &mut thread_rng();
+        let digest = [0; 64];
+        let (pubkey, key) = keypair::<_, Bn256>(rng, &digest);
+
+        let mut response = MmapMut::map_anon(parameters.accumulator_size).unwrap();
+        BatchedAccumulator::transform(
+            &input,
+            &mut response,
+            UseCompression::No,
+            UseCompression::No,
+            CheckForCorrectness::Yes,
+            &key,
+            &parameters,
+        )
+        .unwrap();
+        let response = response.make_read_only().unwrap();
+
+        assert!(BatchedAccumulator::verify_transformation(
+            &input,
+            &response,
+            &pubkey,
+            &digest,
+            UseCompression::No,
+            UseCompression::No,
+            CheckForCorrectness::Yes,
+            CheckForCorrectness::Yes,
+            &parameters,
+        ));
+
+        let verify_chunks = |response: &Mmap, chunks: &[usize], checkpoint| {
+            BatchedAccumulator::verify_transformation_chunks(
+                &input,
+                response,
+                UseCompression::No,
+                UseCompression::No,
+                CheckForCorrectness::Yes,
+                CheckForCorrectness::Yes,
+                chunks,
+                2,
+                checkpoint,
+                &parameters,
+            )
+            .unwrap()
+        };
+
+        // Workers sharing a checkpoint split the chunks between them
+        let path = temp_path("ptau_verify_checkpoint");
+        let mut first = Checkpoint::open_or_create(&path, &[1; 64], 4).unwrap();
+        let mut second = Checkpoint::open_or_create(&path, &[1; 64], 4).unwrap();
+        assert!(verify_chunks(&response, &[0, 2], Some(&mut first)));
+        assert!(verify_chunks(&response, &[1, 3], Some(&mut second)));
+        assert_eq!(Checkpoint::open(&path).unwrap().completed(), 4);
+        match Checkpoint::open_or_create(&path, &[2; 64], 4) {
+            Err(CheckpointError::IdMismatch { .. }) => {}
+            _ => panic!("expected an id mismatch"),
+        }
+        fs::remove_file(&path).unwrap();
+
+        // Multiply tau^65 by tau, which only breaks the chunk from 64 to 95
+        let mut tampered = MmapMut::map_anon(parameters.accumulator_size).unwrap();
+        tampered.copy_from_slice(&response);
+        let mut accumulator = BatchedAccumulator::empty(&parameters);
+        accumulator
+            .read_chunk(
+                64,
+                3,
+                UseCompression::No,
+                CheckForCorrectness::Yes,
+                &response,
+            )
+            .unwrap();
+        accumulator.tau_powers_g1[1] = accumulator.tau_powers_g1[2];
+        accumulator
+            .write_chunk(64, UseCompression::No, &mut tampered)
+            .unwrap();
+        let tampered = tampered.make_read_only().unwrap();
+
+        assert!(verify_chunks(&tampered, &[0, 1, 3], None));
+        assert!(!verify_chunks(&tampered, &[2], None));
+        assert!(!verify_chunks(&tampered, &[0, 1, 2, 3], None));
+        assert_eq!(BatchedAccumulator::chunk_bounds(2, &parameters), (64, 95));
+        assert_eq!(BatchedAccumulator::chunk_bounds(3, &parameters), (96, 126));
+    }
+}
@@ -3,7 +3,7 @@ use fawkes_crypto_powersoftau::{
     batched_accumulator::BatchedAccumulator,
     keypair::keypair,
     parameters::{CeremonyParams, CheckForCorrectness, UseCompression},
-    utils::calculate_hash,
+    utils::{beacon_rng, calculate_hash, iterate_beacon_hash, BEACON_HASH_LENGTH},
 };
 
 use bellman_ce::pairing::bn256::Bn256;
@@ -49,51 +49,35 @@ fn main() {
 
     // Create an RNG based on the outcome of the random beacon
     let mut rng = {
-        use byteorder::{BigEndian, ReadBytesExt};
-        use crypto::digest::Digest;
-        use crypto::sha2::Sha256;
-        use rand::chacha::ChaChaRng;
-        use rand::SeedableRng;
-
-        let mut cur_hash = hex::decode(beacon_hash).unwrap();
-
-        // Performs 2^n hash iterations over it
-        let n: usize = *num_iterations_exp;
-
-        for i in 0..(1u64 << n) {
-            // Print 1024 of the interstitial states
-            // so that verification can be
-            // parallelized
-
-            if i % (1u64 << (n - 10)) == 0 {
-                print!("{}: ", i);
-                for b in cur_hash.iter() {
-                    print!("{:02x}", b);
-                }
-                println!();
+        let beacon_hash = match hex::decode(beacon_hash) {
+            Ok(beacon_hash) => beacon_hash,
+            Err(_) => {
+                println!("Beacon hash should be in hexadecimal format");
+                std::process::exit(exitcode::DATAERR);
             }
-
-            let mut h = Sha256::new();
-            h.input(&cur_hash);
-            h.result(&mut cur_hash);
+        };
+        if beacon_hash.len() != BEACON_HASH_LENGTH {
+            println!("Beacon hash should be {} bytes long", BEACON_HASH_LENGTH);
+            std::process::exit(exitcode::DATAERR);
         }
 
+        // Performs 2^n hash iterations over it, printing 1024 of the
+        // interstitial states so that verification can be parallelized
+        let cur_hash = iterate_beacon_hash(&beacon_hash, *num_iterations_exp, |i, cur_hash| {
+            print!("{}: ", i);
+            for b in cur_hash.iter() {
+                print!("{:02x}", b);
+            }
+            println!();
+        });
+
         print!("Final result of beacon: ");
         for b in cur_hash.iter() {
             print!("{:02x}", b);
         }
         println!();
 
-        let mut digest = &cur_hash[..];
-
-        let mut seed = [0u32; 8];
-        for s in &mut seed {
-            *s = digest
-                .read_u32::<BigEndian>()
-                .expect("digest is large enough for this to work");
-        }
-
-        ChaChaRng::from_seed(&seed)
+        beacon_rng(&cur_hash)
     };
 
     println!("Done creating a beacon RNG");
@@ -1,6 +1,7 @@
 use fawkes_crypto_powersoftau::{
     batched_accumulator::BatchedAccumulator,
-    keypair::keypair,
+    checkpoint::{Checkpoint, CheckpointError},
+    keypair::{key_fingerprint, keypair},
     parameters::{CeremonyParams, CheckForCorrectness, UseCompression},
     utils::calculate_hash,
 };
@@ -8,23 +9,43 @@ use fawkes_crypto_powersoftau::{
 use bellman_ce::pairing::bn256::Bn256;
 use memmap::*;
 use std::fs::OpenOptions;
+use std::path::Path;
 
 use std::io::{Read, Write};
 
 const INPUT_IS_COMPRESSED: UseCompression = UseCompression::No;
 const COMPRESS_THE_OUTPUT: UseCompression = UseCompression::Yes;
 const CHECK_INPUT_CORRECTNESS: CheckForCorrectness = CheckForCorrectness::No;
+/// How many times an interrupted contribution is resumed before giving up.
+const MAX_RESUME_ATTEMPTS: usize = 10;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() != 5 {
-        println!("Usage: \n<challenge_file> <response_file> <circuit_power> <batch_size>");
+    if args.len() != 5 && args.len() != 6 {
+        println!("Usage: \n<challenge_file> <response_file> <circuit_power> <batch_size> [<checkpoint_file>]");
+        println!("With a checkpoint file, a contribution interrupted by an error can be resumed while this program is running.");
         std::process::exit(exitcode::USAGE);
     }
     let challenge_filename = &args[1];
     let response_filename = &args[2];
     let circuit_power = args[3].parse().expect("could not parse circuit power");
     let batch_size = args[4].parse().expect("could not parse batch size");
+    let checkpoint_filename = args.get(5);
+
+    if let Some(checkpoint_filename) = checkpoint_filename {
+        if Path::new(checkpoint_filename).exists() {
+            // The private key of the interrupted contribution was only kept in memory
+            println!(
+                "{} belongs to an interrupted contribution which can't be resumed, as its private key is gone.",
+                checkpoint_filename
+            );
+            println!(
+                "Remove {} and {} to contribute again.",
+                checkpoint_filename, response_filename
+            );
+            std::process::exit(exitcode::DATAERR);
+        }
+    }
 
     let parameters = CeremonyParams::<Bn256>::new(circuit_power, batch_size);
 
@@ -190,16 +211,77 @@ fn main() {
     println!("Computing and writing your contribution, this could take a while...");
 
     // this computes a transformation and writes it
-    BatchedAccumulator::transform(
-        &readable_map,
-        &mut writable_map,
-        INPUT_IS_COMPRESSED,
-        COMPRESS_THE_OUTPUT,
-        CHECK_INPUT_CORRECTNESS,
-        &privkey,
-        &parameters,
-    )
-    .expect("must transform with the key");
+    match checkpoint_filename {
+        None => BatchedAccumulator::transform(
+            &readable_map,
+            &mut writable_map,
+            INPUT_IS_COMPRESSED,
+            COMPRESS_THE_OUTPUT,
+            CHECK_INPUT_CORRECTNESS,
+            &privkey,
+            &parameters,
+        )
+        .expect("must transform with the key"),
+        Some(checkpoint_filename) => {
+            let mut checkpoint = Checkpoint::create(
+                checkpoint_filename,
+                key_fingerprint(&privkey).as_slice(),
+                BatchedAccumulator::transform_chunk_count(&parameters),
+            )
+            .expect("unable to create checkpoint file");
+
+            let mut attempts = 0;
+            loop {
+                attempts += 1;
+                match BatchedAccumulator::transform_with_checkpoint(
+                    &readable_map,
+                    &mut writable_map,
+                    INPUT_IS_COMPRESSED,
+                    COMPRESS_THE_OUTPUT,
+                    CHECK_INPUT_CORRECTNESS,
+                    &privkey,
+                    &mut checkpoint,
+                    &parameters,
+                ) {
+                    Ok(()) => break,
+                    Err(CheckpointError::IoError(e)) if attempts == MAX_RESUME_ATTEMPTS => {
+                        println!(
+                            "Contribution interrupted after {} of {} chunks, giving up after {} attempts: {}",
+                            checkpoint.completed(),
+                            checkpoint.len(),
+                            attempts,
+                            e
+                        );
+                        std::process::exit(exitcode::IOERR);
+                    }
+                    Err(CheckpointError::IoError(e)) => {
+                        println!(
+                            "Contribution interrupted after {} of {} chunks: {}",
+                            checkpoint.completed(),
+                            checkpoint.len(),
+                            e
+                        );
+                        println!(
+                            "Your private key is only kept in memory, so don't close this program."
+                        );
+                        println!(
+                            "Fix the problem and press [ENTER] to resume ({} of {} attempts left)...",
+                            MAX_RESUME_ATTEMPTS - attempts,
+                            MAX_RESUME_ATTEMPTS
+                        );
+                        std::io::stdin()
+                            .read_line(&mut String::new())
+                            .expect("expected to read a line from the user");
+                    }
+                    Err(e) => panic!("must transform with the key: {}", e),
+                }
+            }
+
+            checkpoint
+                .remove()
+                .expect("unable to remove checkpoint file");
+        }
+    }
 
     println!("Finishing writing your contribution to response file...");
 
@@ -0,0 +1,58 @@
+use bellman_ce::pairing::bn256::Bn256;
+use fawkes_crypto_powersoftau::{
+    batched_accumulator::BatchedAccumulator,
+    parameters::{CeremonyParams, CheckForCorrectness, UseCompression},
+};
+
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+
+use memmap::MmapOptions;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 5 {
+        println!("Usage: \n<response_filename> <circuit_power> <batch_size> <out_powers.ptau>");
+        std::process::exit(exitcode::USAGE);
+    }
+    let response_filename = &args[1];
+    let circuit_power = args[2].parse().expect("could not parse circuit power");
+    let batch_size = args[3].parse().expect("could not parse batch size");
+    let ptau_filename = &args[4];
+
+    let parameters = CeremonyParams::<Bn256>::new(circuit_power, batch_size);
+
+    // Try to load response file from disk.
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(response_filename)
+        .expect("unable open response file in this directory");
+    let response_readable_map = unsafe {
+        MmapOptions::new()
+            .map(&reader)
+            .expect("unable to create a memory map for input")
+    };
+
+    let current_accumulator = BatchedAccumulator::deserialize(
+        &response_readable_map,
+        CheckForCorrectness::Yes,
+        UseCompression::Yes,
+        &parameters,
+    )
+    .expect("unable to read uncompressed accumulator");
+
+    let writer = OpenOptions::new()
+        .read(false)
+        .write(true)
+        .create_new(true)
+        .open(ptau_filename)
+        .expect("unable to create the .ptau file in this directory");
+    let mut writer = BufWriter::new(writer);
+
+    current_accumulator
+        .write_ptau(&mut writer)
+        .expect("unable to write the .ptau file");
+    writer.flush().expect("unable to write the .ptau file");
+
+    println!("Wrote the accumulator to `{}`", ptau_filename);
+}
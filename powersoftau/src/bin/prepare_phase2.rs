@@ -11,7 +11,7 @@ use bellman_ce::domain::{EvaluationDomain, Point};
 use bellman_ce::multicore::Worker;
 
 use std::fs::OpenOptions;
-use std::io::{BufWriter, Write};
+use std::io::{BufReader, BufWriter, Write};
 
 use memmap::*;
 
@@ -27,7 +27,7 @@ fn log_2(x: u64) -> u32 {
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() != 4 {
-        println!("Usage: \n<response_filename> <circuit_power> <batch_size>");
+        println!("Usage: \n<response_filename|powers.ptau> <circuit_power> <batch_size>");
         std::process::exit(exitcode::USAGE);
     }
     let response_filename = &args[1];
@@ -41,19 +41,30 @@ fn main() {
         .read(true)
         .open(response_filename)
         .expect("unable open response file in this directory");
-    let response_readable_map = unsafe {
-        MmapOptions::new()
-            .map(&reader)
-            .expect("unable to create a memory map for input")
-    };
 
-    let current_accumulator = BatchedAccumulator::deserialize(
-        &response_readable_map,
-        CheckForCorrectness::Yes,
-        UseCompression::Yes,
-        &parameters,
-    )
-    .expect("unable to read uncompressed accumulator");
+    let current_accumulator = if response_filename.ends_with(".ptau") {
+        // Transcripts of snarkjs ceremonies are truncated to `circuit_power`
+        BatchedAccumulator::read_ptau(
+            BufReader::new(reader),
+            CheckForCorrectness::Yes,
+            &parameters,
+        )
+        .expect("unable to read .ptau file")
+    } else {
+        let response_readable_map = unsafe {
+            MmapOptions::new()
+                .map(&reader)
+                .expect("unable to create a memory map for input")
+        };
+
+        BatchedAccumulator::deserialize(
+            &response_readable_map,
+            CheckForCorrectness::Yes,
+            UseCompression::Yes,
+            &parameters,
+        )
+        .expect("unable to read uncompressed accumulator")
+    };
 
     let worker = &Worker::new();
 
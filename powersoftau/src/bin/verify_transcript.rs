@@ -0,0 +1,85 @@
+use fawkes_crypto_powersoftau::{
+    parameters::CeremonyParams,
+    transcript::{verify_transcript, Beacon},
+};
+
+use bellman_ce::pairing::bn256::Bn256;
+use std::fs;
+
+fn print_hash(hash: &[u8]) {
+    for line in hash.chunks(16) {
+        print!("\t");
+        for section in line.chunks(4) {
+            for b in section {
+                print!("{:02x}", b);
+            }
+            print!(" ");
+        }
+        println!();
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 4 && args.len() != 6 {
+        println!("Usage: \n<circuit_power> <batch_size> <manifest_file> [<beacon_hash> <num_iterations_exp>]");
+        println!(
+            "The manifest file lists the response files in order of contribution, one per line."
+        );
+        std::process::exit(exitcode::USAGE);
+    }
+    let circuit_power = args[1].parse().expect("could not parse circuit power");
+    let batch_size = args[2].parse().expect("could not parse batch size");
+    let manifest_filename = &args[3];
+    let beacon = if args.len() == 6 {
+        Some(Beacon {
+            hash: hex::decode(&args[4]).expect("could not parse beacon hash"),
+            num_iterations_exp: args[5]
+                .parse()
+                .expect("could not parse number of iterations"),
+        })
+    } else {
+        None
+    };
+
+    let parameters = CeremonyParams::<Bn256>::new(circuit_power, batch_size);
+
+    let manifest = fs::read_to_string(manifest_filename).expect("unable to read manifest file");
+    let responses: Vec<&str> = manifest
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    println!(
+        "Will verify {} contributions to accumulator for 2^{} powers of tau",
+        responses.len(),
+        parameters.size,
+    );
+
+    let reports = match verify_transcript(&responses, beacon.as_ref(), &parameters) {
+        Ok(reports) => reports,
+        Err(e) => {
+            println!("Verification failed: {}", e);
+            std::process::exit(exitcode::DATAERR);
+        }
+    };
+
+    for (i, report) in reports.iter().enumerate() {
+        if report.is_beacon {
+            println!(
+                "Random beacon contribution {}, {}:",
+                i + 1,
+                report.path.display()
+            );
+        } else {
+            println!("Contribution {}, {}:", i + 1, report.path.display());
+        }
+        println!("  made on top of the challenge with hash:");
+        print_hash(report.challenge_hash.as_slice());
+        println!("  BLAKE2b hash of the response:");
+        print_hash(report.response_hash.as_slice());
+    }
+
+    println!("Transcript is valid!");
+}
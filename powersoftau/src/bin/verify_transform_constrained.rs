@@ -1,5 +1,6 @@
 use fawkes_crypto_powersoftau::{
     batched_accumulator::BatchedAccumulator,
+    checkpoint::Checkpoint,
     keypair::PublicKey,
     parameters::{CeremonyParams, CheckForCorrectness, UseCompression},
     utils::calculate_hash,
@@ -17,8 +18,11 @@ const COMPRESS_NEW_CHALLENGE: UseCompression = UseCompression::No;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() != 6 {
-        println!("Usage: \n<challenge_file> <response_file> <new_challenge_file> <circuit_power> <batch_size>");
+    if args.len() != 6 && args.len() != 7 && args.len() != 9 {
+        println!("Usage: \n<challenge_file> <response_file> <new_challenge_file> <circuit_power> <batch_size> [<checkpoint_file> [<worker_index> <worker_count>]]");
+        println!("With a checkpoint file, the verified chunks are recorded in it and an interrupted verification resumes from it.");
+        println!("Workers sharing a checkpoint file verify every <worker_count>-th chunk starting from <worker_index>,");
+        println!("run again without them once they are all done to get the verdict and the new challenge file.");
         std::process::exit(exitcode::USAGE);
     }
     let challenge_filename = &args[1];
@@ -26,6 +30,15 @@ fn main() {
     let new_challenge_filename = &args[3];
     let circuit_power = args[4].parse().expect("could not parse circuit power");
     let batch_size = args[5].parse().expect("could not parse batch size");
+    let checkpoint_filename = args.get(6);
+    let worker = if args.len() == 9 {
+        let index: usize = args[7].parse().expect("could not parse worker index");
+        let count: usize = args[8].parse().expect("could not parse worker count");
+        assert!(index < count, "worker index must be less than worker count");
+        Some((index, count))
+    } else {
+        None
+    };
 
     let parameters = CeremonyParams::<Bn256>::new(circuit_power, batch_size);
 
@@ -165,25 +178,97 @@ fn main() {
         "Verifying a contribution to contain proper powers and correspond to the public key..."
     );
 
-    let valid = BatchedAccumulator::verify_transformation(
-        &challenge_readable_map,
-        &response_readable_map,
-        &public_key,
-        current_accumulator_hash.as_slice(),
-        PREVIOUS_CHALLENGE_IS_COMPRESSED,
-        CONTRIBUTION_IS_COMPRESSED,
-        CheckForCorrectness::No,
-        CheckForCorrectness::Yes,
-        &parameters,
-    );
+    let valid = match checkpoint_filename {
+        None => BatchedAccumulator::verify_transformation(
+            &challenge_readable_map,
+            &response_readable_map,
+            &public_key,
+            current_accumulator_hash.as_slice(),
+            PREVIOUS_CHALLENGE_IS_COMPRESSED,
+            CONTRIBUTION_IS_COMPRESSED,
+            CheckForCorrectness::No,
+            CheckForCorrectness::Yes,
+            &parameters,
+        ),
+        Some(checkpoint_filename) => {
+            let chunk_count = BatchedAccumulator::transform_chunk_count(&parameters);
+
+            // The checkpoint belongs to this pair of challenge and response
+            let id = {
+                use blake2::{Blake2b, Digest};
+                let mut h = Blake2b::default();
+                h.input(current_accumulator_hash.as_slice());
+                h.input(response_hash.as_slice());
+                h.result()
+            };
+            let mut checkpoint =
+                Checkpoint::open_or_create(checkpoint_filename, id.as_slice(), chunk_count)
+                    .unwrap_or_else(|e| panic!("unable to open checkpoint file: {}", e));
+            let chunks: Vec<usize> = match worker {
+                Some((index, count)) => (index..chunk_count).step_by(count).collect(),
+                None => (0..chunk_count).collect(),
+            };
+            println!(
+                "{} of {} chunks are already verified according to {}",
+                checkpoint.completed(),
+                chunk_count,
+                checkpoint_filename
+            );
+
+            BatchedAccumulator::verify_transformation_head(
+                &challenge_readable_map,
+                &response_readable_map,
+                &public_key,
+                current_accumulator_hash.as_slice(),
+                PREVIOUS_CHALLENGE_IS_COMPRESSED,
+                CONTRIBUTION_IS_COMPRESSED,
+                CheckForCorrectness::No,
+                CheckForCorrectness::Yes,
+                &parameters,
+            ) && BatchedAccumulator::verify_transformation_chunks(
+                &challenge_readable_map,
+                &response_readable_map,
+                PREVIOUS_CHALLENGE_IS_COMPRESSED,
+                CONTRIBUTION_IS_COMPRESSED,
+                CheckForCorrectness::No,
+                CheckForCorrectness::Yes,
+                &chunks,
+                num_cpus::get(),
+                Some(&mut checkpoint),
+                &parameters,
+            )
+            .expect("unable to record verified chunks")
+        }
+    };
 
     if !valid {
         println!("Verification failed, contribution was invalid somehow.");
         panic!("INVALID CONTRIBUTION!!!");
-    } else {
-        println!("Verification succeeded!");
     }
 
+    if let Some(checkpoint_filename) = checkpoint_filename {
+        // Other workers may have recorded their chunks in the meantime
+        let checkpoint = Checkpoint::open(checkpoint_filename)
+            .unwrap_or_else(|e| panic!("unable to open checkpoint file: {}", e));
+        if checkpoint.completed() != checkpoint.len() {
+            println!(
+                "Verified chunks are valid, {} of {} chunks are verified so far.",
+                checkpoint.completed(),
+                checkpoint.len()
+            );
+            return;
+        }
+        if worker.is_some() {
+            println!("All chunks are verified, run again without a worker index to write the new challenge.");
+            return;
+        }
+        checkpoint
+            .remove()
+            .expect("unable to remove checkpoint file");
+    }
+
+    println!("Verification succeeded!");
+
     if COMPRESS_NEW_CHALLENGE == UseCompression::Yes {
         println!(
             "Don't need to recompress the contribution, please copy response file as new challenge"
@@ -0,0 +1,359 @@
+//! Progress of a long running computation over chunks of a memory map.
+//!
+//! A checkpoint file starts with a magic, the 64 byte identifier of the computation
+//! it belongs to and the number of chunks, followed by one byte per chunk which is
+//! set once the chunk has been written and flushed. The identifier of a contribution
+//! is derived from its private key (see `BatchedAccumulator::transform_with_checkpoint`)
+//! and the key itself is never written, so a contribution can only be resumed by the
+//! process still holding the key in memory.
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const MAGIC: &[u8; 8] = b"ptauckpt";
+const HEADER_SIZE: u64 = 8 + 64 + 8;
+
+/// Errors that might occur while creating, opening or resuming from a checkpoint.
+#[derive(Debug)]
+pub enum CheckpointError {
+    IoError(io::Error),
+    /// The file isn't a checkpoint or is truncated.
+    InvalidCheckpoint {
+        path: PathBuf,
+    },
+    /// The checkpoint was made with another private key, which is gone.
+    KeyMismatch {
+        path: PathBuf,
+    },
+    /// The checkpoint belongs to another computation.
+    IdMismatch {
+        path: PathBuf,
+    },
+    /// The checkpoint was made for different ceremony parameters.
+    ChunkCountMismatch {
+        path: PathBuf,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CheckpointError::IoError(ref e) => write!(f, "Disk IO error: {}", e),
+            CheckpointError::InvalidCheckpoint { ref path } => {
+                write!(f, "{} is not a valid checkpoint", path.display())
+            }
+            CheckpointError::KeyMismatch { ref path } => write!(
+                f,
+                "{} was made with a private key which is no longer available, the contribution must be started over",
+                path.display()
+            ),
+            CheckpointError::IdMismatch { ref path } => {
+                write!(f, "{} belongs to another computation", path.display())
+            }
+            CheckpointError::ChunkCountMismatch {
+                ref path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} tracks {} chunks, but {} are expected",
+                path.display(),
+                actual,
+                expected
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for CheckpointError {
+    fn from(e: io::Error) -> CheckpointError {
+        CheckpointError::IoError(e)
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+/// Tracks which chunks of a computation are complete in a file on disk.
+pub struct Checkpoint {
+    path: PathBuf,
+    file: File,
+    id: [u8; 64],
+    done: Vec<bool>,
+}
+
+impl Checkpoint {
+    /// Creates a new checkpoint at `path` with no complete chunks. Fails if `path` exists.
+    ///
+    /// The checkpoint is written to a temporary file next to `path` and then linked to
+    /// `path`, so other processes never see it half written.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        id: &[u8],
+        chunks: usize,
+    ) -> Result<Checkpoint, CheckpointError> {
+        assert_eq!(id.len(), 64);
+        let tmp_path = Self::tmp_path(path.as_ref());
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)?;
+
+        let written = (|| {
+            file.write_all(MAGIC)?;
+            file.write_all(id)?;
+            file.write_u64::<BigEndian>(chunks as u64)?;
+            file.write_all(&vec![0u8; chunks])?;
+            file.sync_all()?;
+            // Unlike a rename, linking fails if another process created `path` meanwhile
+            fs::hard_link(&tmp_path, path.as_ref())
+        })();
+        fs::remove_file(&tmp_path)?;
+        written?;
+
+        let mut checkpoint_id = [0u8; 64];
+        checkpoint_id.copy_from_slice(id);
+
+        Ok(Checkpoint {
+            path: path.as_ref().to_path_buf(),
+            file,
+            id: checkpoint_id,
+            done: vec![false; chunks],
+        })
+    }
+
+    /// A path next to `path` that no other process or thread uses.
+    fn tmp_path(path: &Path) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(
+            ".{}.{}.tmp",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        path.with_file_name(name)
+    }
+
+    /// Opens an existing checkpoint.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Checkpoint, CheckpointError> {
+        let path = path.as_ref().to_path_buf();
+        let invalid = || CheckpointError::InvalidCheckpoint { path: path.clone() };
+        let mut file = OpenOptions::new().read(true).write(true).open(&path)?;
+
+        let mut magic = [0u8; 8];
+        let mut id = [0u8; 64];
+        if file.read_exact(&mut magic).is_err() || &magic != MAGIC {
+            return Err(invalid());
+        }
+        file.read_exact(&mut id).map_err(|_| invalid())?;
+        let chunks = file.read_u64::<BigEndian>().map_err(|_| invalid())? as usize;
+        if file.metadata()?.len() != HEADER_SIZE + chunks as u64 {
+            return Err(invalid());
+        }
+
+        let mut done = vec![0u8; chunks];
+        file.read_exact(&mut done)?;
+
+        Ok(Checkpoint {
+            path,
+            file,
+            id,
+            done: done.iter().map(|&b| b != 0).collect(),
+        })
+    }
+
+    /// Opens the checkpoint at `path` if it exists, checking that it was created with
+    /// the same `id` and number of chunks, and creates it otherwise. Several processes
+    /// can share a checkpoint, as long as they work on different chunks.
+    pub fn open_or_create<P: AsRef<Path>>(
+        path: P,
+        id: &[u8],
+        chunks: usize,
+    ) -> Result<Checkpoint, CheckpointError> {
+        let checkpoint = match Checkpoint::create(path.as_ref(), id, chunks) {
+            Err(CheckpointError::IoError(ref e)) if e.kind() == io::ErrorKind::AlreadyExists => {
+                Checkpoint::open(path.as_ref())?
+            }
+            result => return result,
+        };
+
+        if checkpoint.id() != id {
+            return Err(CheckpointError::IdMismatch {
+                path: checkpoint.path,
+            });
+        }
+        if checkpoint.len() != chunks {
+            return Err(CheckpointError::ChunkCountMismatch {
+                path: checkpoint.path,
+                expected: chunks,
+                actual: checkpoint.done.len(),
+            });
+        }
+
+        Ok(checkpoint)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Identifier of the computation this checkpoint belongs to.
+    pub fn id(&self) -> &[u8] {
+        &self.id[..]
+    }
+
+    /// Total number of chunks.
+    pub fn len(&self) -> usize {
+        self.done.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.done.is_empty()
+    }
+
+    /// Number of complete chunks.
+    pub fn completed(&self) -> usize {
+        self.done.iter().filter(|&&done| done).count()
+    }
+
+    pub fn is_done(&self, chunk: usize) -> bool {
+        self.done[chunk]
+    }
+
+    /// Records that `chunk` is complete. Its output must have been flushed beforehand.
+    pub fn mark_done(&mut self, chunk: usize) -> io::Result<()> {
+        self.file
+            .seek(SeekFrom::Start(HEADER_SIZE + chunk as u64))?;
+        self.file.write_all(&[1])?;
+        self.file.sync_data()?;
+        self.done[chunk] = true;
+
+        Ok(())
+    }
+
+    /// Deletes the checkpoint file, once the computation is over.
+    pub fn remove(self) -> io::Result<()> {
+        fs::remove_file(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod bn256_tests {
+    use super::*;
+    use crate::batched_accumulator::{initial_challenge, BatchedAccumulator};
+    use crate::keypair::{key_fingerprint, keypair};
+    use crate::parameters::{CeremonyParams, CheckForCorrectness, UseCompression};
+    use crate::utils::temp_path;
+    use bellman_ce::pairing::bn256::Bn256;
+    use memmap::MmapMut;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_transform_with_checkpoint_bn256() {
+        let parameters = CeremonyParams::<Bn256>::new(6, 32);
+        let input = initial_challenge(&parameters);
+
+        let rng = &mut thread_rng();
+        let (_, key) = keypair::<_, Bn256>(rng, &[0; 64]);
+        let (_, other_key) = keypair::<_, Bn256>(rng, &[0; 64]);
+
+        let mut expected = MmapMut::map_anon(parameters.accumulator_size).unwrap();
+        BatchedAccumulator::transform(
+            &input,
+            &mut expected,
+            UseCompression::No,
+            UseCompression::No,
+            CheckForCorrectness::Yes,
+            &key,
+            &parameters,
+        )
+        .unwrap();
+
+        let path = temp_path("ptau_transform_checkpoint");
+        let chunks = BatchedAccumulator::transform_chunk_count(&parameters);
+        assert_eq!(chunks, 4);
+
+        let transform = |output: &mut MmapMut, key, checkpoint: &mut Checkpoint| {
+            BatchedAccumulator::transform_with_checkpoint(
+                &input,
+                output,
+                UseCompression::No,
+                UseCompression::No,
+                CheckForCorrectness::Yes,
+                key,
+                checkpoint,
+                &parameters,
+            )
+        };
+
+        // Chunks recorded by the checkpoint are not computed again
+        let mut output = MmapMut::map_anon(parameters.accumulator_size).unwrap();
+        let mut checkpoint = Checkpoint::create(&path, &key_fingerprint(&key), chunks).unwrap();
+        checkpoint.mark_done(1).unwrap();
+        checkpoint.mark_done(3).unwrap();
+        transform(&mut output, &key, &mut checkpoint).unwrap();
+        assert_eq!(checkpoint.completed(), chunks);
+        assert!(output[..] != expected[..]);
+
+        let checkpoint = Checkpoint::open(&path).unwrap();
+        assert_eq!(checkpoint.completed(), chunks);
+        fs::remove_file(&path).unwrap();
+
+        let mut checkpoint = Checkpoint::create(&path, &key_fingerprint(&key), chunks).unwrap();
+        transform(&mut output, &key, &mut checkpoint).unwrap();
+        assert!(output[..] == expected[..]);
+
+        // Resuming with another key is refused
+        match transform(&mut output, &other_key, &mut checkpoint) {
+            Err(CheckpointError::KeyMismatch { .. }) => {}
+            _ => panic!("expected a key mismatch"),
+        }
+        checkpoint.remove().unwrap();
+
+        let mut checkpoint = Checkpoint::create(&path, &key_fingerprint(&key), chunks + 1).unwrap();
+        match transform(&mut output, &key, &mut checkpoint) {
+            Err(CheckpointError::ChunkCountMismatch { .. }) => {}
+            _ => panic!("expected a chunk count mismatch"),
+        }
+        checkpoint.remove().unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_open_or_create() {
+        let path = temp_path("ptau_concurrent_checkpoint");
+
+        // Workers starting together all see a complete checkpoint, whoever creates it
+        let workers = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || Checkpoint::open_or_create(&path, &[1; 64], 1000))
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            let checkpoint = worker.join().unwrap().unwrap();
+            assert_eq!(checkpoint.len(), 1000);
+        }
+
+        let leftovers = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy()
+                    .starts_with(path.file_name().unwrap().to_str().unwrap())
+            })
+            .count();
+        assert_eq!(
+            leftovers, 1,
+            "temporary files are left next to the checkpoint"
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -102,6 +102,22 @@ pub fn keypair<R: Rng, E: Engine>(rng: &mut R, digest: &[u8]) -> (PublicKey<E>,
     )
 }
 
+/// Identifies a private key without revealing it, as BLAKE2b of τ, α and β in G1.
+/// Used to tie a checkpoint to the key of the contribution in progress.
+pub fn key_fingerprint<E: Engine>(key: &PrivateKey<E>) -> generic_array::GenericArray<u8, U64> {
+    let mut h = Blake2b::default();
+    for x in &[key.tau, key.alpha, key.beta] {
+        h.input(
+            E::G1Affine::one()
+                .mul(*x)
+                .into_affine()
+                .into_uncompressed()
+                .as_ref(),
+        );
+    }
+    h.result()
+}
+
 impl<E: Engine> PublicKey<E> {
     /// Serialize the public key. Points are always in uncompressed form.
     pub fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
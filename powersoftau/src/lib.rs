@@ -1,4 +1,9 @@
 pub mod batched_accumulator;
+pub mod checkpoint;
 pub mod keypair;
 pub mod parameters;
+pub mod ptau;
+#[cfg(feature = "sonic")]
+pub mod sonic_srs;
+pub mod transcript;
 pub mod utils;
@@ -144,6 +144,32 @@ pub enum DeserializationError {
     IoError(io::Error),
     DecodingError(GroupDecodingError),
     PointAtInfinity,
+    /// A `.ptau` file doesn't start with the expected magic bytes.
+    InvalidMagic,
+    /// A `.ptau` file has a format version we don't support.
+    UnsupportedVersion(u32),
+    /// A `.ptau` file lacks a required section.
+    MissingSection(u32),
+    /// A `.ptau` file is defined over a different base field.
+    FieldMismatch,
+    /// A `.ptau` file has fewer powers than the ceremony parameters need.
+    NotEnoughPowers {
+        available: usize,
+        required: usize,
+    },
+    /// A section of a `.ptau` file doesn't hold as many points as the
+    /// power of the file implies.
+    InvalidSectionSize {
+        section: u32,
+        point_size: u64,
+        size: u64,
+    },
+    /// Fewer points than required are in a section of a `.ptau` file.
+    NotEnoughPoints {
+        section: u32,
+        available: u64,
+        required: usize,
+    },
 }
 
 impl fmt::Display for DeserializationError {
@@ -152,6 +178,50 @@ impl fmt::Display for DeserializationError {
             DeserializationError::IoError(ref e) => write!(f, "Disk IO error: {}", e),
             DeserializationError::DecodingError(ref e) => write!(f, "Decoding error: {}", e),
             DeserializationError::PointAtInfinity => write!(f, "Point at infinity found"),
+            DeserializationError::InvalidMagic => write!(f, "Not a .ptau file"),
+            DeserializationError::UnsupportedVersion(v) => {
+                write!(f, "Unsupported .ptau version {}", v)
+            }
+            DeserializationError::MissingSection(t) => write!(f, "Missing section of type {}", t),
+            DeserializationError::FieldMismatch => {
+                write!(f, "File is defined over a different field")
+            }
+            DeserializationError::NotEnoughPowers {
+                available,
+                required,
+            } => write!(
+                f,
+                "File has powers up to 2^{}, while 2^{} are required",
+                available, required
+            ),
+            DeserializationError::InvalidSectionSize {
+                section,
+                point_size,
+                size,
+            } => write!(
+                f,
+                "Section of type {} has {} bytes, which isn't the expected number of {}-byte points",
+                section, size, point_size
+            ),
+            DeserializationError::NotEnoughPoints {
+                section,
+                available,
+                required,
+            } => write!(
+                f,
+                "Section of type {} has {} points, while {} are required",
+                section, available, required
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeserializationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            DeserializationError::IoError(ref e) => Some(e),
+            DeserializationError::DecodingError(ref e) => Some(e),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,492 @@
+//! Conversion between the accumulator and the `.ptau` files snarkjs and the
+//! Perpetual Powers of Tau ceremony publish their transcripts in
+use bellman_ce::pairing::ff::{PrimeField, PrimeFieldRepr};
+use bellman_ce::pairing::*;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use std::collections::BTreeMap;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex};
+
+use super::batched_accumulator::BatchedAccumulator;
+use super::parameters::{CeremonyParams, CheckForCorrectness, DeserializationError};
+use super::utils::blank_hash;
+
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+const PTAU_VERSION: u32 = 1;
+
+const HEADER_SECTION: u32 = 1;
+pub const TAU_G1_SECTION: u32 = 2;
+pub const TAU_G2_SECTION: u32 = 3;
+pub const ALPHA_TAU_G1_SECTION: u32 = 4;
+pub const BETA_TAU_G1_SECTION: u32 = 5;
+pub const BETA_G2_SECTION: u32 = 6;
+const CONTRIBUTIONS_SECTION: u32 = 7;
+
+/// Reads the sections of a `.ptau` file one at a time, for callers which
+/// only need some of the powers, e.g. the ones of a single domain size.
+pub struct PtauReader<R> {
+    reader: R,
+    sections: BTreeMap<u32, (u64, u64)>,
+    power: usize,
+}
+
+impl<R: Read + Seek> PtauReader<R> {
+    /// Reads the header of the file, checking that it's defined over the
+    /// base field of `E`.
+    pub fn new<E: Engine>(mut reader: R) -> Result<PtauReader<R>, DeserializationError> {
+        let sections = read_sections(&mut reader)?;
+
+        seek_section(&mut reader, &sections, HEADER_SECTION)?;
+        let field_size = reader.read_u32::<LittleEndian>()? as usize;
+        let mut modulus = <E::Fq as PrimeField>::Repr::default();
+        if field_size != modulus.as_ref().len() * 8 {
+            return Err(DeserializationError::FieldMismatch);
+        }
+        modulus.read_le(&mut reader)?;
+        if modulus != E::Fq::char() {
+            return Err(DeserializationError::FieldMismatch);
+        }
+        let power = reader.read_u32::<LittleEndian>()? as usize;
+
+        Ok(PtauReader {
+            reader,
+            sections,
+            power,
+        })
+    }
+
+    /// The file has powers for domains of size up to `2^power`.
+    pub fn power(&self) -> usize {
+        self.power
+    }
+
+    /// The number of points in a section of powers, for the power of the
+    /// file.
+    fn section_length(&self, section_type: u32) -> Option<u64> {
+        let domain_size = 1u64.checked_shl(self.power as u32)?;
+        match section_type {
+            TAU_G1_SECTION => domain_size.checked_mul(2).map(|n| n - 1),
+            TAU_G2_SECTION | ALPHA_TAU_G1_SECTION | BETA_TAU_G1_SECTION => Some(domain_size),
+            BETA_G2_SECTION => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Reads the first `count` points of a section, which must not be at
+    /// infinity. The section must hold as many points as the power of the
+    /// file implies, or a whole number of points if it isn't a section of
+    /// powers.
+    pub fn read_section<G: RawEncodable>(
+        &mut self,
+        section_type: u32,
+        count: usize,
+        checked: CheckForCorrectness,
+    ) -> Result<Vec<G>, DeserializationError> {
+        let size = seek_section(&mut self.reader, &self.sections, section_type)?;
+        let point_size = G::Uncompressed::size() as u64;
+        let length = self
+            .section_length(section_type)
+            .unwrap_or(size / point_size);
+        if length.checked_mul(point_size) != Some(size) {
+            return Err(DeserializationError::InvalidSectionSize {
+                section: section_type,
+                point_size,
+                size,
+            });
+        }
+        if count as u64 > length {
+            return Err(DeserializationError::NotEnoughPoints {
+                section: section_type,
+                available: length,
+                required: count,
+            });
+        }
+
+        let mut reader = BufReader::with_capacity(1024 * 1024, &mut self.reader);
+        read_points(&mut reader, count, checked)
+    }
+}
+
+impl<'a, E: Engine> BatchedAccumulator<'a, E> {
+    /// Reads the powers of a `.ptau` file, keeping only as many of them as
+    /// `parameters` asks for, so a transcript of a larger ceremony can be
+    /// used for smaller circuits.
+    ///
+    /// File expected structure
+    /// "ptau", version and the number of sections, then sections made of
+    /// their type, their length and their data:
+    /// 1: field element size, Fq modulus, power, ceremony power
+    /// 2: 2^{power+1} - 1 tau powers in G1
+    /// 3: 2^power tau powers in G2
+    /// 4: 2^power alpha * tau powers in G1
+    /// 5: 2^power beta * tau powers in G1
+    /// 6: beta in G2
+    /// Points have little-endian Montgomery form coordinates.
+    pub fn read_ptau<R: Read + Seek>(
+        reader: R,
+        check_input_for_correctness: CheckForCorrectness,
+        parameters: &'a CeremonyParams<E>,
+    ) -> Result<BatchedAccumulator<'a, E>, DeserializationError> {
+        let mut reader = PtauReader::new::<E>(reader)?;
+        if reader.power() < parameters.size {
+            return Err(DeserializationError::NotEnoughPowers {
+                available: reader.power(),
+                required: parameters.size,
+            });
+        }
+
+        let checked = check_input_for_correctness;
+
+        let tau_powers_g1 =
+            reader.read_section(TAU_G1_SECTION, parameters.powers_g1_length, checked)?;
+        let tau_powers_g2 =
+            reader.read_section(TAU_G2_SECTION, parameters.powers_length, checked)?;
+        let alpha_tau_powers_g1 =
+            reader.read_section(ALPHA_TAU_G1_SECTION, parameters.powers_length, checked)?;
+        let beta_tau_powers_g1 =
+            reader.read_section(BETA_TAU_G1_SECTION, parameters.powers_length, checked)?;
+        let beta_g2 = reader.read_section(BETA_G2_SECTION, 1, checked)?;
+
+        Ok(BatchedAccumulator {
+            tau_powers_g1,
+            tau_powers_g2,
+            alpha_tau_powers_g1,
+            beta_tau_powers_g1,
+            beta_g2: beta_g2[0],
+            hash: blank_hash(),
+            parameters,
+        })
+    }
+
+    /// Writes the accumulator as a `.ptau` file of `parameters.size` powers
+    /// without any contributions, in the layout described in `read_ptau`.
+    pub fn write_ptau<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let parameters = self.parameters;
+        let g1_size = parameters.curve.g1 as u64;
+        let g2_size = parameters.curve.g2 as u64;
+        let modulus = E::Fq::char();
+        let field_size = modulus.as_ref().len() * 8;
+
+        writer.write_all(PTAU_MAGIC)?;
+        writer.write_u32::<LittleEndian>(PTAU_VERSION)?;
+        writer.write_u32::<LittleEndian>(7)?;
+
+        write_section_header(&mut writer, HEADER_SECTION, 4 + field_size as u64 + 4 + 4)?;
+        writer.write_u32::<LittleEndian>(field_size as u32)?;
+        modulus.write_le(&mut writer)?;
+        writer.write_u32::<LittleEndian>(parameters.size as u32)?;
+        writer.write_u32::<LittleEndian>(parameters.size as u32)?;
+
+        write_section_header(
+            &mut writer,
+            TAU_G1_SECTION,
+            parameters.powers_g1_length as u64 * g1_size,
+        )?;
+        write_points(
+            &mut writer,
+            &self.tau_powers_g1[..parameters.powers_g1_length],
+        )?;
+
+        write_section_header(
+            &mut writer,
+            TAU_G2_SECTION,
+            parameters.powers_length as u64 * g2_size,
+        )?;
+        write_points(&mut writer, &self.tau_powers_g2[..parameters.powers_length])?;
+
+        write_section_header(
+            &mut writer,
+            ALPHA_TAU_G1_SECTION,
+            parameters.powers_length as u64 * g1_size,
+        )?;
+        write_points(
+            &mut writer,
+            &self.alpha_tau_powers_g1[..parameters.powers_length],
+        )?;
+
+        write_section_header(
+            &mut writer,
+            BETA_TAU_G1_SECTION,
+            parameters.powers_length as u64 * g1_size,
+        )?;
+        write_points(
+            &mut writer,
+            &self.beta_tau_powers_g1[..parameters.powers_length],
+        )?;
+
+        write_section_header(&mut writer, BETA_G2_SECTION, g2_size)?;
+        write_points(&mut writer, &[self.beta_g2])?;
+
+        // The contributions of this project's ceremonies don't map onto the
+        // ones of snarkjs, so none are recorded
+        write_section_header(&mut writer, CONTRIBUTIONS_SECTION, 4)?;
+        writer.write_u32::<LittleEndian>(0)?;
+
+        Ok(())
+    }
+}
+
+/// Reads the header of the file and returns the offset of every section
+fn read_sections<R: Read + Seek>(
+    reader: &mut R,
+) -> Result<BTreeMap<u32, (u64, u64)>, DeserializationError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != PTAU_MAGIC {
+        return Err(DeserializationError::InvalidMagic);
+    }
+    let version = reader.read_u32::<LittleEndian>()?;
+    if version != PTAU_VERSION {
+        return Err(DeserializationError::UnsupportedVersion(version));
+    }
+
+    let num_sections = reader.read_u32::<LittleEndian>()?;
+    let mut sections = BTreeMap::new();
+    for _ in 0..num_sections {
+        let section_type = reader.read_u32::<LittleEndian>()?;
+        let size = reader.read_u64::<LittleEndian>()?;
+        let offset = reader.stream_position()?;
+        sections.entry(section_type).or_insert((offset, size));
+        reader.seek(SeekFrom::Current(size as i64))?;
+    }
+
+    Ok(sections)
+}
+
+/// Seeks to the start of a section and returns its size.
+fn seek_section<R: Seek>(
+    reader: &mut R,
+    sections: &BTreeMap<u32, (u64, u64)>,
+    section_type: u32,
+) -> Result<u64, DeserializationError> {
+    let (offset, size) = *sections
+        .get(&section_type)
+        .ok_or(DeserializationError::MissingSection(section_type))?;
+    reader.seek(SeekFrom::Start(offset))?;
+    Ok(size)
+}
+
+fn write_section_header<W: Write>(writer: &mut W, section_type: u32, size: u64) -> io::Result<()> {
+    writer.write_u32::<LittleEndian>(section_type)?;
+    writer.write_u64::<LittleEndian>(size)
+}
+
+fn write_points<W: Write, G: RawEncodable>(writer: &mut W, points: &[G]) -> io::Result<()> {
+    for point in points {
+        writer.write_all(point.into_raw_uncompressed_le().as_ref())?;
+    }
+    Ok(())
+}
+
+fn read_points<R: Read, G: RawEncodable>(
+    reader: &mut R,
+    count: usize,
+    checked: CheckForCorrectness,
+) -> Result<Vec<G>, DeserializationError> {
+    let mut encoded = vec![G::Uncompressed::empty(); count];
+    for point in encoded.iter_mut() {
+        reader.read_exact(point.as_mut())?;
+    }
+
+    let mut decoded = vec![G::zero(); count];
+
+    let mut chunk_size = count / num_cpus::get();
+    if chunk_size == 0 {
+        chunk_size = 1;
+    }
+
+    // If any of our threads encounter a deserialization error, catch
+    // it with this.
+    let decoding_error = Arc::new(Mutex::new(None));
+
+    crossbeam::scope(|scope| {
+        for (source, target) in encoded
+            .chunks(chunk_size)
+            .zip(decoded.chunks_mut(chunk_size))
+        {
+            let decoding_error = decoding_error.clone();
+
+            scope.spawn(move |_| {
+                for (source, target) in source.iter().zip(target.iter_mut()) {
+                    let point = match checked {
+                        CheckForCorrectness::Yes => G::from_raw_uncompressed_le(source, false),
+                        CheckForCorrectness::No => {
+                            G::from_raw_uncompressed_le_unchecked(source, false)
+                        }
+                    };
+                    match point {
+                        Ok(point) => *target = point,
+                        Err(e) => *decoding_error.lock().unwrap() = Some(e.into()),
+                    }
+                }
+            });
+        }
+    })
+    .unwrap();
+
+    if let Some(e) = Arc::try_unwrap(decoding_error)
+        .unwrap()
+        .into_inner()
+        .unwrap()
+    {
+        return Err(e);
+    }
+
+    // Points at infinity are never expected in the accumulator
+    if decoded.iter().any(|point| point.is_zero()) {
+        return Err(DeserializationError::PointAtInfinity);
+    }
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod bn256_tests {
+    use super::*;
+    use bellman_ce::pairing::bn256::{Bn256, Fr, G1Affine, G2Affine};
+    use rand::{thread_rng, Rand};
+    use std::io::Cursor;
+
+    fn random_accumulator(parameters: &CeremonyParams<Bn256>) -> BatchedAccumulator<'_, Bn256> {
+        let rng = &mut thread_rng();
+        let mut g1 = |n| -> Vec<G1Affine> {
+            (0..n)
+                .map(|_| G1Affine::one().mul(Fr::rand(rng)).into_affine())
+                .collect()
+        };
+        let mut accumulator = BatchedAccumulator::empty(parameters);
+        accumulator.tau_powers_g1 = g1(parameters.powers_g1_length);
+        accumulator.alpha_tau_powers_g1 = g1(parameters.powers_length);
+        accumulator.beta_tau_powers_g1 = g1(parameters.powers_length);
+        accumulator.tau_powers_g2 = (0..parameters.powers_length)
+            .map(|_| G2Affine::one().mul(Fr::rand(rng)).into_affine())
+            .collect();
+        accumulator.beta_g2 = G2Affine::one().mul(Fr::rand(rng)).into_affine();
+        accumulator
+    }
+
+    #[test]
+    fn test_ptau_roundtrip_bn256() {
+        let parameters = CeremonyParams::<Bn256>::new(3, 4);
+        let accumulator = random_accumulator(&parameters);
+
+        let mut ptau = vec![];
+        accumulator.write_ptau(&mut ptau).unwrap();
+
+        let read = BatchedAccumulator::read_ptau(
+            Cursor::new(&ptau),
+            CheckForCorrectness::Yes,
+            &parameters,
+        )
+        .unwrap();
+        assert!(read.tau_powers_g1 == accumulator.tau_powers_g1);
+        assert!(read.tau_powers_g2 == accumulator.tau_powers_g2);
+        assert!(read.alpha_tau_powers_g1 == accumulator.alpha_tau_powers_g1);
+        assert!(read.beta_tau_powers_g1 == accumulator.beta_tau_powers_g1);
+        assert!(read.beta_g2 == accumulator.beta_g2);
+
+        let smaller = CeremonyParams::<Bn256>::new(2, 4);
+        let read =
+            BatchedAccumulator::read_ptau(Cursor::new(&ptau), CheckForCorrectness::Yes, &smaller)
+                .unwrap();
+        assert!(read.tau_powers_g1[..] == accumulator.tau_powers_g1[..smaller.powers_g1_length]);
+        assert!(read.tau_powers_g2[..] == accumulator.tau_powers_g2[..smaller.powers_length]);
+
+        let larger = CeremonyParams::<Bn256>::new(4, 4);
+        assert!(BatchedAccumulator::read_ptau(
+            Cursor::new(&ptau),
+            CheckForCorrectness::Yes,
+            &larger
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_ptau_section_sizes_bn256() {
+        let parameters = CeremonyParams::<Bn256>::new(3, 4);
+        let accumulator = random_accumulator(&parameters);
+        let mut ptau = vec![];
+        accumulator.write_ptau(&mut ptau).unwrap();
+
+        let mut reader = PtauReader::new::<Bn256>(Cursor::new(&ptau)).unwrap();
+        match reader.read_section::<G2Affine>(TAU_G2_SECTION, 9, CheckForCorrectness::Yes) {
+            Err(DeserializationError::NotEnoughPoints {
+                section: TAU_G2_SECTION,
+                available: 8,
+                required: 9,
+            }) => {}
+            _ => panic!("points past the end of the section read"),
+        }
+
+        // The power in the header, right after the field element size and
+        // the modulus, no longer matches the sections
+        let power_offset = 12 + 12 + 4 + 32;
+        ptau[power_offset] = 2;
+        let smaller = CeremonyParams::<Bn256>::new(2, 4);
+        match BatchedAccumulator::read_ptau(Cursor::new(&ptau), CheckForCorrectness::Yes, &smaller)
+        {
+            Err(DeserializationError::InvalidSectionSize {
+                section: TAU_G1_SECTION,
+                point_size: 64,
+                size,
+            }) if size == 15 * 64 => {}
+            _ => panic!("section of the wrong size accepted"),
+        }
+    }
+
+    #[test]
+    #[ignore = "needs testdata/snarkjs_power2.ptau, which CI writes with testdata/snarkjs_fixtures.sh"]
+    fn test_read_snarkjs_ptau_bn256() {
+        use super::super::utils::{power_pairs, same_ratio};
+        use std::fs::File;
+
+        // A ceremony of power 2 with one contribution, made by snarkjs 0.7.4
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/snarkjs_power2.ptau");
+        assert_eq!(
+            PtauReader::new::<Bn256>(BufReader::new(File::open(path).unwrap()))
+                .unwrap()
+                .power(),
+            2
+        );
+
+        let parameters = CeremonyParams::<Bn256>::new(2, 4);
+        let read = BatchedAccumulator::read_ptau(
+            BufReader::new(File::open(path).unwrap()),
+            CheckForCorrectness::Yes,
+            &parameters,
+        )
+        .unwrap();
+
+        // tau is unknown, so the powers are only checked to be consistent
+        let tau_g2 = (read.tau_powers_g2[0], read.tau_powers_g2[1]);
+        assert!(read.tau_powers_g1[0] == G1Affine::one());
+        assert!(read.tau_powers_g2[0] == G2Affine::one());
+        assert!(same_ratio(power_pairs(&read.tau_powers_g1), tau_g2));
+        assert!(same_ratio(
+            power_pairs(&read.tau_powers_g2),
+            (read.tau_powers_g1[0], read.tau_powers_g1[1])
+        ));
+        assert!(same_ratio(power_pairs(&read.alpha_tau_powers_g1), tau_g2));
+        assert!(same_ratio(power_pairs(&read.beta_tau_powers_g1), tau_g2));
+        assert!(same_ratio(
+            (G1Affine::one(), read.beta_tau_powers_g1[0]),
+            (G2Affine::one(), read.beta_g2)
+        ));
+
+        // and the writer lays the powers out the way the reader expects
+        let mut written = vec![];
+        read.write_ptau(&mut written).unwrap();
+        let reread = BatchedAccumulator::read_ptau(
+            Cursor::new(&written),
+            CheckForCorrectness::Yes,
+            &parameters,
+        )
+        .unwrap();
+        assert!(reread.tau_powers_g1 == read.tau_powers_g1);
+        assert!(reread.tau_powers_g2 == read.tau_powers_g2);
+        assert!(reread.alpha_tau_powers_g1 == read.alpha_tau_powers_g1);
+        assert!(reread.beta_tau_powers_g1 == read.beta_tau_powers_g1);
+        assert!(reread.beta_g2 == read.beta_g2);
+    }
+}
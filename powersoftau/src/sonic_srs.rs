@@ -0,0 +1,568 @@
+//! Derivation of a Sonic structured reference string from a powers of tau accumulator.
+//!
+//! A Sonic SRS of degree d holds x<sup>i</sup> for i in [-d, d] in G1 and G2, αx<sup>i</sup>
+//! for i ≠ 0 in G1 and αx<sup>i</sup> for i in [-d, d] in G2. The accumulator only has
+//! nonnegative powers of τ, and α only in G1, which leaves two gaps:
+//!
+//! * Negative powers of τ over the fixed generators can't be obtained from the accumulator,
+//!   and no later contribution can produce them since that needs τ<sup>-1</sup>. Instead, the
+//!   SRS is taken over the generators g = τ<sup>d</sup>G and h = τ<sup>d</sup>H with x = τ, so
+//!   that x<sup>i</sup> is τ<sup>d+i</sup> over the fixed generators for every i in [-d, d].
+//!   The Sonic verifiers, helped and succinct, take `g_positive_x[0]` and `h_positive_x[0]`
+//!   from the SRS or from a verifying key made on it as the generators, so nothing changes
+//!   for them. The powers in G2 go up to τ<sup>2d</sup>, so d can be at most
+//!   (`powers_length` - 1) / 2. Note that the accumulator publishes more powers of x, without
+//!   α, than an SRS of degree d holds.
+//! * ατ<sup>i</sup> in G2 can't be computed from the accumulator at all, since its α is only
+//!   published in G1. Sonic's α is therefore a fresh secret, built by an extension ceremony:
+//!   every participant multiplies all α terms by a secret of their own with `contribute`,
+//!   and each contribution is checked with `verify_contribution`. The α and β of the
+//!   accumulator aren't used.
+//!
+//! `srs_from_accumulator` returns an `UncontributedSrs`, which has α = 1 and can't be used
+//! as an `SRS` until it has been contributed to. It must not be trusted before at least one
+//! honest contribution. A verifier recomputes it from the verified accumulator and checks
+//! every contribution on top of it in order.
+
+use bellman_ce::pairing::ff::PrimeField;
+use bellman_ce::pairing::*;
+use bellman_ce::sonic::srs::SRS;
+use blake2::{Blake2b, Digest};
+use generic_array::GenericArray;
+use rand::{Rand, Rng};
+use typenum::consts::U64;
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use super::batched_accumulator::BatchedAccumulator;
+use super::parameters::{DeserializationError, UseCompression};
+use super::utils::{compute_g2_s, merge_pairs, same_ratio, write_point};
+
+/// Personalization of the proof of knowledge, after the ones of τ, α and β in the accumulator.
+const ALPHA_PERSONALIZATION: u8 = 3;
+
+/// Errors that might occur while deriving a Sonic SRS or verifying a contribution to it.
+#[derive(Debug)]
+pub enum SonicSrsError {
+    /// The SRS needs a degree of at least 1.
+    ZeroDegree,
+    /// The accumulator doesn't have enough powers of τ in G2 for the requested degree.
+    NotEnoughPowers { degree: usize, max_degree: usize },
+    /// The SRS has vectors of unexpected lengths for its degree.
+    MalformedSrs,
+    /// The contribution changed the degree or the powers of x, which only the accumulator
+    /// determines.
+    PowersChanged,
+    /// The public key doesn't prove knowledge of the contributed secret.
+    InvalidProofOfKnowledge,
+    /// α in G2 wasn't multiplied by the secret of the public key.
+    AlphaNotUpdated,
+    /// The α terms in the given group aren't the powers of x times the same α.
+    InconsistentAlphaPowers(&'static str),
+}
+
+impl fmt::Display for SonicSrsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SonicSrsError::ZeroDegree => write!(f, "The SRS degree must be at least 1"),
+            SonicSrsError::NotEnoughPowers { degree, max_degree } => write!(
+                f,
+                "The accumulator supports SRS degrees up to {}, but {} was requested",
+                max_degree, degree
+            ),
+            SonicSrsError::MalformedSrs => {
+                write!(
+                    f,
+                    "The SRS vectors don't have the lengths its degree implies"
+                )
+            }
+            SonicSrsError::PowersChanged => {
+                write!(f, "The contribution changed the degree or the powers of x")
+            }
+            SonicSrsError::InvalidProofOfKnowledge => {
+                write!(f, "The public key doesn't prove knowledge of the secret")
+            }
+            SonicSrsError::AlphaNotUpdated => {
+                write!(f, "Alpha wasn't updated with the secret of the public key")
+            }
+            SonicSrsError::InconsistentAlphaPowers(group) => {
+                write!(f, "The alpha powers in {} are inconsistent", group)
+            }
+        }
+    }
+}
+
+/// Contains (s, s<sup>a</sup>, H(s, s<sup>a</sup>)<sub>2</sub><sup>a</sup>) for the secret a a
+/// participant multiplied α by and some s chosen randomly by its creator, the same as the
+/// public key of the accumulator does for τ, α and β. H is keyed by the hash of the SRS the
+/// contribution was made on, which ties the key to it.
+pub struct SonicPublicKey<E: Engine> {
+    pub alpha_g1: (E::G1Affine, E::G1Affine),
+    pub alpha_g2: E::G2Affine,
+}
+
+impl<E: Engine> PartialEq for SonicPublicKey<E> {
+    fn eq(&self, other: &SonicPublicKey<E>) -> bool {
+        self.alpha_g1.0 == other.alpha_g1.0
+            && self.alpha_g1.1 == other.alpha_g1.1
+            && self.alpha_g2 == other.alpha_g2
+    }
+}
+
+impl<E: Engine> SonicPublicKey<E> {
+    /// Serialize the public key. Points are always in uncompressed form.
+    pub fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_point(writer, &self.alpha_g1.0, UseCompression::No)?;
+        write_point(writer, &self.alpha_g1.1, UseCompression::No)?;
+        write_point(writer, &self.alpha_g2, UseCompression::No)?;
+
+        Ok(())
+    }
+
+    /// Deserialize the public key. Points are always checked and none of them may be
+    /// the point at infinity.
+    pub fn deserialize<R: Read>(reader: &mut R) -> Result<SonicPublicKey<E>, DeserializationError> {
+        fn read_uncompressed<EE: Engine, C: CurveAffine<Engine = EE, Scalar = EE::Fr>, R: Read>(
+            reader: &mut R,
+        ) -> Result<C, DeserializationError> {
+            let mut repr = C::Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
+            let v = repr.into_affine()?;
+
+            if v.is_zero() {
+                Err(DeserializationError::PointAtInfinity)
+            } else {
+                Ok(v)
+            }
+        }
+
+        let alpha_g1_s = read_uncompressed::<E, _, _>(reader)?;
+        let alpha_g1_s_alpha = read_uncompressed::<E, _, _>(reader)?;
+        let alpha_g2 = read_uncompressed::<E, _, _>(reader)?;
+
+        Ok(SonicPublicKey {
+            alpha_g1: (alpha_g1_s, alpha_g1_s_alpha),
+            alpha_g2,
+        })
+    }
+}
+
+/// The SRS derived from an accumulator before any contribution. Its α is 1, so it is only
+/// handed out as an `SRS` by `contribute`.
+pub struct UncontributedSrs<E: Engine> {
+    srs: SRS<E>,
+}
+
+impl<E: Engine> UncontributedSrs<E> {
+    /// The degree of the SRS.
+    pub fn degree(&self) -> usize {
+        self.srs.d
+    }
+
+    /// Makes the first contribution, see `contribute`.
+    pub fn contribute<R: Rng>(self, rng: &mut R) -> (SRS<E>, SonicPublicKey<E>) {
+        let mut srs = self.srs;
+        let key = contribute(&mut srs, rng);
+
+        (srs, key)
+    }
+
+    /// Verifies the first contribution, see `verify_contribution`.
+    pub fn verify_contribution(
+        &self,
+        after: &SRS<E>,
+        key: &SonicPublicKey<E>,
+    ) -> Result<(), SonicSrsError> {
+        verify_contribution(&self.srs, after, key)
+    }
+}
+
+/// Builds the Sonic SRS of degree `d` over the generators τ<sup>d</sup>G and τ<sup>d</sup>H
+/// from a verified accumulator, with α = 1.
+pub fn srs_from_accumulator<E: Engine>(
+    accumulator: &BatchedAccumulator<E>,
+    d: usize,
+) -> Result<UncontributedSrs<E>, SonicSrsError> {
+    if d == 0 {
+        return Err(SonicSrsError::ZeroDegree);
+    }
+    let max_degree = accumulator
+        .tau_powers_g2
+        .len()
+        .min(accumulator.tau_powers_g1.len())
+        .saturating_sub(1)
+        / 2;
+    if d > max_degree {
+        return Err(SonicSrsError::NotEnoughPowers {
+            degree: d,
+            max_degree,
+        });
+    }
+
+    // x^{-i} is tau^{d-i} and x^i is tau^{d+i}
+    let g_negative_x: Vec<_> = accumulator.tau_powers_g1[0..=d]
+        .iter()
+        .rev()
+        .cloned()
+        .collect();
+    let g_positive_x = accumulator.tau_powers_g1[d..=2 * d].to_vec();
+    let h_negative_x: Vec<_> = accumulator.tau_powers_g2[0..=d]
+        .iter()
+        .rev()
+        .cloned()
+        .collect();
+    let h_positive_x = accumulator.tau_powers_g2[d..=2 * d].to_vec();
+
+    Ok(UncontributedSrs {
+        srs: SRS {
+            d,
+            g_negative_x_alpha: g_negative_x[1..].to_vec(),
+            g_positive_x_alpha: g_positive_x[1..].to_vec(),
+            h_negative_x_alpha: h_negative_x.clone(),
+            h_positive_x_alpha: h_positive_x.clone(),
+            g_negative_x,
+            g_positive_x,
+            h_negative_x,
+            h_positive_x,
+        },
+    })
+}
+
+/// Multiplies α of the SRS by a random secret, which is dropped afterwards, and returns the
+/// public key proving knowledge of it.
+pub fn contribute<R: Rng, E: Engine>(srs: &mut SRS<E>, rng: &mut R) -> SonicPublicKey<E> {
+    let digest = srs_hash(srs);
+
+    let alpha = E::Fr::rand(rng);
+    let g1_s = E::G1::rand(rng).into_affine();
+    let g1_s_alpha = g1_s.mul(alpha).into_affine();
+    let g2_s = compute_g2_s::<E>(&digest, &g1_s, &g1_s_alpha, ALPHA_PERSONALIZATION);
+    let g2_s_alpha = g2_s.mul(alpha).into_affine();
+
+    batch_mul(&mut srs.g_negative_x_alpha, alpha);
+    batch_mul(&mut srs.g_positive_x_alpha, alpha);
+    batch_mul(&mut srs.h_negative_x_alpha, alpha);
+    batch_mul(&mut srs.h_positive_x_alpha, alpha);
+
+    SonicPublicKey {
+        alpha_g1: (g1_s, g1_s_alpha),
+        alpha_g2: g2_s_alpha,
+    }
+}
+
+/// Verifies that `after` is `before` with α multiplied by the secret `key` proves knowledge of.
+pub fn verify_contribution<E: Engine>(
+    before: &SRS<E>,
+    after: &SRS<E>,
+    key: &SonicPublicKey<E>,
+) -> Result<(), SonicSrsError> {
+    if !has_valid_lengths(before) || !has_valid_lengths(after) {
+        return Err(SonicSrsError::MalformedSrs);
+    }
+    if after.d != before.d
+        || after.g_negative_x != before.g_negative_x
+        || after.g_positive_x != before.g_positive_x
+        || after.h_negative_x != before.h_negative_x
+        || after.h_positive_x != before.h_positive_x
+    {
+        return Err(SonicSrsError::PowersChanged);
+    }
+
+    let digest = srs_hash(before);
+    let g2_s = compute_g2_s::<E>(
+        &digest,
+        &key.alpha_g1.0,
+        &key.alpha_g1.1,
+        ALPHA_PERSONALIZATION,
+    );
+    if !same_ratio(key.alpha_g1, (g2_s, key.alpha_g2)) {
+        return Err(SonicSrsError::InvalidProofOfKnowledge);
+    }
+    if !same_ratio(
+        key.alpha_g1,
+        (before.h_positive_x_alpha[0], after.h_positive_x_alpha[0]),
+    ) {
+        return Err(SonicSrsError::AlphaNotUpdated);
+    }
+
+    // All the other alpha terms must be the powers of x times the alpha of h^alpha
+    let x_g1 = [&after.g_negative_x[1..], &after.g_positive_x[1..]].concat();
+    let alpha_x_g1 = [&after.g_negative_x_alpha[..], &after.g_positive_x_alpha[..]].concat();
+    if !same_ratio(
+        merge_pairs::<E, _>(&x_g1, &alpha_x_g1),
+        (after.h_positive_x[0], after.h_positive_x_alpha[0]),
+    ) {
+        return Err(SonicSrsError::InconsistentAlphaPowers("G1"));
+    }
+
+    let x_g2 = [&after.h_negative_x[..], &after.h_positive_x[..]].concat();
+    let alpha_x_g2 = [&after.h_negative_x_alpha[..], &after.h_positive_x_alpha[..]].concat();
+    if !same_ratio(
+        (after.g_positive_x[1], after.g_positive_x_alpha[0]),
+        merge_pairs::<E, _>(&x_g2, &alpha_x_g2),
+    ) {
+        return Err(SonicSrsError::InconsistentAlphaPowers("G2"));
+    }
+
+    Ok(())
+}
+
+fn has_valid_lengths<E: Engine>(srs: &SRS<E>) -> bool {
+    srs.d > 0
+        && srs.g_negative_x.len() == srs.d + 1
+        && srs.g_positive_x.len() == srs.d + 1
+        && srs.h_negative_x.len() == srs.d + 1
+        && srs.h_positive_x.len() == srs.d + 1
+        && srs.g_negative_x_alpha.len() == srs.d
+        && srs.g_positive_x_alpha.len() == srs.d
+        && srs.h_negative_x_alpha.len() == srs.d + 1
+        && srs.h_positive_x_alpha.len() == srs.d + 1
+}
+
+/// BLAKE2b of the serialized SRS, which public keys of contributions to it are tied to.
+fn srs_hash<E: Engine>(srs: &SRS<E>) -> GenericArray<u8, U64> {
+    let mut hasher = Blake2b::default();
+    srs.write(&mut hasher).expect("must hash the SRS");
+    hasher.result()
+}
+
+fn batch_mul<C: CurveAffine>(bases: &mut [C], coeff: C::Scalar) {
+    let mut projective = vec![C::Projective::zero(); bases.len()];
+    let chunk_size = bases.len() / num_cpus::get() + 1;
+    let coeff = coeff.into_repr();
+
+    crossbeam::scope(|scope| {
+        for (bases, projective) in bases
+            .chunks(chunk_size)
+            .zip(projective.chunks_mut(chunk_size))
+        {
+            scope.spawn(move |_| {
+                for (base, projective) in bases.iter().zip(projective.iter_mut()) {
+                    *projective = base.mul(coeff);
+                }
+                C::Projective::batch_normalization(projective);
+            });
+        }
+    })
+    .unwrap();
+
+    for (projective, affine) in projective.iter().zip(bases.iter_mut()) {
+        *affine = projective.into_affine();
+        assert!(
+            !affine.is_zero(),
+            "your contribution happened to produce a point at infinity, please re-run"
+        );
+    }
+}
+
+#[cfg(test)]
+mod bn256_tests {
+    use super::*;
+    use crate::parameters::CeremonyParams;
+    use bellman_ce::pairing::bn256::{Bn256, Fr, G1Affine, G2Affine};
+    use bellman_ce::pairing::ff::Field;
+    use bellman_ce::sonic::helped::{create_proof, generate_parameters_on_srs, verify_proofs};
+    use bellman_ce::{Circuit, ConstraintSystem, SynthesisError};
+    use rand::thread_rng;
+
+    fn accumulator(parameters: &CeremonyParams<Bn256>, tau: Fr) -> BatchedAccumulator<'_, Bn256> {
+        let mut powers = vec![];
+        let mut acc = Fr::one();
+        for _ in 0..parameters.powers_g1_length {
+            powers.push(acc);
+            acc.mul_assign(&tau);
+        }
+
+        let mut accumulator = BatchedAccumulator::empty(parameters);
+        accumulator.tau_powers_g1 = powers
+            .iter()
+            .map(|p| G1Affine::one().mul(*p).into_affine())
+            .collect();
+        accumulator.tau_powers_g2 = powers[0..parameters.powers_length]
+            .iter()
+            .map(|p| G2Affine::one().mul(*p).into_affine())
+            .collect();
+        accumulator
+    }
+
+    #[test]
+    fn test_srs_from_accumulator_bn256() {
+        let rng = &mut thread_rng();
+        let parameters = CeremonyParams::<Bn256>::new(5, 32);
+        let tau = Fr::rand(rng);
+        let accumulator = accumulator(&parameters, tau);
+
+        assert!(matches!(
+            srs_from_accumulator(&accumulator, 0),
+            Err(SonicSrsError::ZeroDegree)
+        ));
+        assert!(matches!(
+            srs_from_accumulator(&accumulator, 16),
+            Err(SonicSrsError::NotEnoughPowers {
+                degree: 16,
+                max_degree: 15
+            })
+        ));
+
+        let initial = srs_from_accumulator(&accumulator, 15).unwrap();
+        assert_eq!(initial.degree(), 15);
+        assert_eq!(initial.srs.g_negative_x[15], G1Affine::one());
+        assert_eq!(initial.srs.g_positive_x[0], accumulator.tau_powers_g1[15]);
+        assert_eq!(initial.srs.h_positive_x[15], accumulator.tau_powers_g2[30]);
+
+        let (first, first_key) = srs_from_accumulator(&accumulator, 15)
+            .unwrap()
+            .contribute(rng);
+        initial.verify_contribution(&first, &first_key).unwrap();
+
+        let mut second = first.clone();
+        let second_key = contribute(&mut second, rng);
+        verify_contribution(&first, &second, &second_key).unwrap();
+
+        // A key only proves knowledge for the SRS it was made on
+        assert!(matches!(
+            initial.verify_contribution(&first, &second_key),
+            Err(SonicSrsError::InvalidProofOfKnowledge)
+        ));
+        assert!(matches!(
+            verify_contribution(&first, &first, &second_key),
+            Err(SonicSrsError::AlphaNotUpdated)
+        ));
+
+        let mut tampered = second.clone();
+        tampered.g_positive_x[3] = tampered.g_positive_x[4];
+        assert!(matches!(
+            verify_contribution(&first, &tampered, &second_key),
+            Err(SonicSrsError::PowersChanged)
+        ));
+
+        let mut tampered = second.clone();
+        tampered.g_negative_x_alpha[3] = tampered.g_negative_x_alpha[4];
+        assert!(matches!(
+            verify_contribution(&first, &tampered, &second_key),
+            Err(SonicSrsError::InconsistentAlphaPowers("G1"))
+        ));
+
+        let mut tampered = second.clone();
+        tampered.h_negative_x_alpha[2] = tampered.h_positive_x_alpha[2];
+        assert!(matches!(
+            verify_contribution(&first, &tampered, &second_key),
+            Err(SonicSrsError::InconsistentAlphaPowers("G2"))
+        ));
+
+        let mut v = vec![];
+        second_key.serialize(&mut v).unwrap();
+        let deserialized = SonicPublicKey::<Bn256>::deserialize(&mut &v[..]).unwrap();
+        assert!(deserialized == second_key);
+    }
+
+    #[derive(Clone)]
+    struct MulCircuit {
+        a: Option<Fr>,
+        b: Option<Fr>,
+    }
+
+    impl Circuit<Bn256> for MulCircuit {
+        fn synthesize<CS: ConstraintSystem<Bn256>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+            let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(
+                || "c",
+                || {
+                    let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+                    a.mul_assign(&self.b.ok_or(SynthesisError::AssignmentMissing)?);
+                    Ok(a)
+                },
+            )?;
+
+            cs.enforce(|| "a*b=c", |lc| lc + a, |lc| lc + b, |lc| lc + c);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sonic_proof_on_derived_srs_bn256() {
+        let rng = &mut thread_rng();
+        let parameters = CeremonyParams::<Bn256>::new(6, 64);
+        let accumulator = accumulator(&parameters, Fr::rand(rng));
+
+        let (srs, _) = srs_from_accumulator(&accumulator, 31)
+            .unwrap()
+            .contribute(rng);
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let mut c = a;
+        c.mul_assign(&b);
+        let circuit = MulCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+
+        let params = generate_parameters_on_srs(circuit.clone(), &srs).unwrap();
+        let proofs = vec![create_proof(circuit.clone(), &params).unwrap()];
+
+        assert!(
+            verify_proofs(&proofs, &[vec![c]], circuit.clone(), thread_rng(), &params).unwrap()
+        );
+        assert!(!verify_proofs(&proofs, &[vec![a]], circuit, thread_rng(), &params).unwrap());
+    }
+    #[test]
+    fn test_succinct_sonic_proof_on_derived_srs_bn256() {
+        use bellman_ce::sonic::helped::prover::{
+            create_advice_on_information_and_srs, create_proof_on_srs,
+        };
+        use bellman_ce::sonic::sonic::{AdaptorCircuit, Permutation3};
+        use bellman_ce::sonic::unhelped::{
+            create_aggregate_on_srs_using_key, SuccinctMultiVerifier, SuccinctProvingKey,
+        };
+
+        let rng = &mut thread_rng();
+        let parameters = CeremonyParams::<Bn256>::new(6, 64);
+        let accumulator = accumulator(&parameters, Fr::rand(rng));
+
+        let (srs, _) = srs_from_accumulator(&accumulator, 31)
+            .unwrap()
+            .contribute(rng);
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let mut c = a;
+        c.mul_assign(&b);
+        let circuit = AdaptorCircuit(MulCircuit {
+            a: Some(a),
+            b: Some(b),
+        });
+
+        let pk = SuccinctProvingKey::new::<_, Permutation3>(&circuit, &srs).unwrap();
+        let proof = create_proof_on_srs::<Bn256, _, Permutation3>(&circuit, &srs).unwrap();
+        let advice = create_advice_on_information_and_srs::<Bn256, _, Permutation3>(
+            &circuit, &proof, &srs, pk.vk.n,
+        )
+        .unwrap();
+        let proofs = vec![(proof, advice)];
+        let aggregate = create_aggregate_on_srs_using_key::<Bn256, _, Permutation3>(
+            &circuit, &proofs, &srs, &pk,
+        );
+
+        for (inputs, valid) in vec![(vec![c], true), (vec![a], false)] {
+            let mut verifier = SuccinctMultiVerifier::<Bn256, _, Permutation3, _>::new_from_key(
+                circuit.clone(),
+                pk.vk.clone(),
+                &srs,
+                thread_rng(),
+            )
+            .unwrap();
+            for (proof, advice) in &proofs {
+                verifier.add_proof_with_advice(proof, &inputs, advice);
+            }
+            verifier.add_aggregate(&proofs, &aggregate, &srs);
+            assert_eq!(verifier.check_all(), valid);
+        }
+    }
+}
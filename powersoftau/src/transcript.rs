@@ -0,0 +1,468 @@
+//! Verification of a whole powers of tau ceremony.
+//!
+//! Starting from the initial accumulator every response is checked to be built on top
+//! of the challenge preceding it and to be a valid transformation of that challenge.
+//! Challenges are reconstructed from the responses, so only the response files are
+//! needed. Optionally the last response is checked to be the random beacon contribution.
+
+use bellman_ce::pairing::Engine;
+use generic_array::GenericArray;
+use memmap::{Mmap, MmapMut, MmapOptions};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use typenum::consts::U64;
+
+use crate::batched_accumulator::BatchedAccumulator;
+use crate::keypair::{keypair, PublicKey};
+use crate::parameters::{
+    CeremonyParams, CheckForCorrectness, DeserializationError, UseCompression,
+};
+use crate::utils::{
+    beacon_rng, blank_hash, calculate_hash, iterate_beacon_hash, BEACON_HASH_LENGTH,
+};
+
+/// The random beacon which is expected to be the last contribution of the ceremony.
+pub struct Beacon {
+    /// The value of the beacon, e.g. a block hash.
+    pub hash: Vec<u8>,
+    /// The beacon value is hashed `2^num_iterations_exp` times.
+    pub num_iterations_exp: usize,
+}
+
+/// The outcome of verifying a single contribution.
+pub struct ContributionReport {
+    /// The response file of the contribution.
+    pub path: PathBuf,
+    /// The hash of the challenge the contribution was made on top of.
+    pub challenge_hash: GenericArray<u8, U64>,
+    /// The hash of the response file, which the participant can compare
+    /// with the one reported to them.
+    pub response_hash: GenericArray<u8, U64>,
+    /// Whether this is the random beacon contribution.
+    pub is_beacon: bool,
+}
+
+/// Errors that might occur during transcript verification.
+#[derive(Debug)]
+pub enum TranscriptError {
+    IoError(io::Error),
+    /// No response files were given.
+    EmptyTranscript,
+    /// The response file has an unexpected size.
+    InvalidResponseSize {
+        path: PathBuf,
+        expected: usize,
+        actual: u64,
+    },
+    /// The response file isn't built on top of the preceding challenge.
+    HashChainBroken {
+        path: PathBuf,
+    },
+    /// The public key of the response file can't be read.
+    InvalidPublicKey {
+        path: PathBuf,
+        error: DeserializationError,
+    },
+    /// The response file isn't a valid transformation of the preceding challenge.
+    InvalidTransformation {
+        path: PathBuf,
+    },
+    /// The beacon is hashed a number of times outside the supported range.
+    UnsupportedBeaconIterations(usize),
+    /// The beacon value isn't `BEACON_HASH_LENGTH` bytes long.
+    InvalidBeaconHashLength(usize),
+    /// The last contribution wasn't made with the given random beacon.
+    InvalidBeacon {
+        path: PathBuf,
+    },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TranscriptError::IoError(ref e) => write!(f, "Disk IO error: {}", e),
+            TranscriptError::EmptyTranscript => write!(f, "No contributions to verify"),
+            TranscriptError::InvalidResponseSize {
+                ref path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "The size of {} should be {}, but it's {}",
+                path.display(),
+                expected,
+                actual
+            ),
+            TranscriptError::HashChainBroken { ref path } => write!(
+                f,
+                "{} isn't based on the previous contribution",
+                path.display()
+            ),
+            TranscriptError::InvalidPublicKey {
+                ref path,
+                ref error,
+            } => write!(
+                f,
+                "Unable to read the public key of {}: {}",
+                path.display(),
+                error
+            ),
+            TranscriptError::InvalidTransformation { ref path } => {
+                write!(f, "{} is not a valid transformation", path.display())
+            }
+            TranscriptError::UnsupportedBeaconIterations(n) => write!(
+                f,
+                "Beacon iterations exponent should be in [10, 63] range, got {}",
+                n
+            ),
+            TranscriptError::InvalidBeaconHashLength(n) => write!(
+                f,
+                "Beacon hash should be {} bytes long, got {}",
+                BEACON_HASH_LENGTH, n
+            ),
+            TranscriptError::InvalidBeacon { ref path } => write!(
+                f,
+                "{} wasn't made with the given random beacon",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for TranscriptError {
+    fn from(err: io::Error) -> TranscriptError {
+        TranscriptError::IoError(err)
+    }
+}
+
+/// Verifies the ceremony made of the given response files, in order of contribution.
+/// If `beacon` is given, the last response has to be the beacon contribution.
+///
+/// Each intermediate challenge is kept in an anonymous memory map of
+/// `parameters.accumulator_size` bytes, two of them at a time.
+pub fn verify_transcript<E: Engine, P: AsRef<Path>>(
+    responses: &[P],
+    beacon: Option<&Beacon>,
+    parameters: &CeremonyParams<E>,
+) -> Result<Vec<ContributionReport>, TranscriptError> {
+    if responses.is_empty() {
+        return Err(TranscriptError::EmptyTranscript);
+    }
+
+    let beacon_digest = match beacon {
+        Some(beacon) => {
+            if beacon.num_iterations_exp < 10 || beacon.num_iterations_exp > 63 {
+                return Err(TranscriptError::UnsupportedBeaconIterations(
+                    beacon.num_iterations_exp,
+                ));
+            }
+            if beacon.hash.len() != BEACON_HASH_LENGTH {
+                return Err(TranscriptError::InvalidBeaconHashLength(beacon.hash.len()));
+            }
+            Some(iterate_beacon_hash(
+                &beacon.hash,
+                beacon.num_iterations_exp,
+                |_, _| {},
+            ))
+        }
+        None => None,
+    };
+
+    let mut challenge = {
+        let mut writable_map = MmapMut::map_anon(parameters.accumulator_size)?;
+        (&mut writable_map[0..]).write_all(blank_hash().as_slice())?;
+        BatchedAccumulator::generate_initial(&mut writable_map, UseCompression::No, parameters)?;
+        writable_map.make_read_only()?
+    };
+
+    let mut reports = Vec::with_capacity(responses.len());
+    for (i, path) in responses.iter().enumerate() {
+        let path = path.as_ref().to_path_buf();
+        let response = open_response(&path, parameters)?;
+
+        let challenge_hash = calculate_hash(&challenge);
+        if response[0..parameters.hash_size] != challenge_hash[..] {
+            return Err(TranscriptError::HashChainBroken { path });
+        }
+
+        let public_key = match PublicKey::<E>::read(&response, UseCompression::Yes, parameters) {
+            Ok(public_key) => public_key,
+            Err(error) => return Err(TranscriptError::InvalidPublicKey { path, error }),
+        };
+
+        if !BatchedAccumulator::verify_transformation(
+            &challenge,
+            &response,
+            &public_key,
+            challenge_hash.as_slice(),
+            UseCompression::No,
+            UseCompression::Yes,
+            CheckForCorrectness::No,
+            CheckForCorrectness::Yes,
+            parameters,
+        ) {
+            return Err(TranscriptError::InvalidTransformation { path });
+        }
+
+        let is_beacon = match beacon_digest {
+            Some(ref digest) if i == responses.len() - 1 => {
+                let mut rng = beacon_rng(digest);
+                let (beacon_key, _) = keypair::<_, E>(&mut rng, challenge_hash.as_slice());
+                if beacon_key != public_key {
+                    return Err(TranscriptError::InvalidBeacon { path });
+                }
+                true
+            }
+            _ => false,
+        };
+
+        let response_hash = calculate_hash(&response);
+
+        // The next challenge is the decompressed response prefixed by its hash
+        if i != responses.len() - 1 {
+            let mut writable_map = MmapMut::map_anon(parameters.accumulator_size)?;
+            (&mut writable_map[0..]).write_all(response_hash.as_slice())?;
+            BatchedAccumulator::decompress(
+                &response,
+                &mut writable_map,
+                CheckForCorrectness::No,
+                parameters,
+            )?;
+            challenge = writable_map.make_read_only()?;
+        }
+
+        reports.push(ContributionReport {
+            path,
+            challenge_hash,
+            response_hash,
+            is_beacon,
+        });
+    }
+
+    Ok(reports)
+}
+
+fn open_response<E: Engine>(
+    path: &Path,
+    parameters: &CeremonyParams<E>,
+) -> Result<Mmap, TranscriptError> {
+    let reader = OpenOptions::new().read(true).open(path)?;
+    let actual = reader.metadata()?.len();
+    if actual != parameters.contribution_size as u64 {
+        return Err(TranscriptError::InvalidResponseSize {
+            path: path.to_path_buf(),
+            expected: parameters.contribution_size,
+            actual,
+        });
+    }
+
+    let map = unsafe { MmapOptions::new().map(&reader)? };
+    Ok(map)
+}
+
+#[cfg(test)]
+mod bn256_tests {
+    use super::*;
+    use crate::keypair::PrivateKey;
+    use crate::utils::temp_path;
+    use bellman_ce::pairing::bn256::Bn256;
+    use rand::thread_rng;
+    use std::fs;
+
+    const BEACON_HASH: [u8; BEACON_HASH_LENGTH] = [7; BEACON_HASH_LENGTH];
+
+    fn initial_challenge(parameters: &CeremonyParams<Bn256>) -> Mmap {
+        let mut challenge = MmapMut::map_anon(parameters.accumulator_size).unwrap();
+        (&mut challenge[0..])
+            .write_all(blank_hash().as_slice())
+            .unwrap();
+        BatchedAccumulator::generate_initial(&mut challenge, UseCompression::No, parameters)
+            .unwrap();
+        challenge.make_read_only().unwrap()
+    }
+
+    /// Writes the response to `challenge` made with `key`, but carrying `public_key`,
+    /// to `path` and returns the challenge made from it.
+    fn contribute(
+        challenge: &Mmap,
+        public_key: &PublicKey<Bn256>,
+        key: &PrivateKey<Bn256>,
+        path: &Path,
+        parameters: &CeremonyParams<Bn256>,
+    ) -> Mmap {
+        let mut response = MmapMut::map_anon(parameters.contribution_size).unwrap();
+        (&mut response[0..])
+            .write_all(calculate_hash(challenge).as_slice())
+            .unwrap();
+        BatchedAccumulator::transform(
+            challenge,
+            &mut response,
+            UseCompression::No,
+            UseCompression::Yes,
+            CheckForCorrectness::No,
+            key,
+            parameters,
+        )
+        .unwrap();
+        public_key
+            .write(&mut response, UseCompression::Yes, parameters)
+            .unwrap();
+        let response = response.make_read_only().unwrap();
+        fs::write(path, &response[..]).unwrap();
+        next_challenge(&response, parameters)
+    }
+
+    fn next_challenge(response: &Mmap, parameters: &CeremonyParams<Bn256>) -> Mmap {
+        let mut challenge = MmapMut::map_anon(parameters.accumulator_size).unwrap();
+        (&mut challenge[0..])
+            .write_all(calculate_hash(response).as_slice())
+            .unwrap();
+        BatchedAccumulator::decompress(
+            response,
+            &mut challenge,
+            CheckForCorrectness::No,
+            parameters,
+        )
+        .unwrap();
+        challenge.make_read_only().unwrap()
+    }
+
+    /// How the second contribution of a test ceremony is made invalid.
+    enum Tamper {
+        Nothing,
+        /// A G1 power of tau is replaced by the next one.
+        ShiftPower,
+        /// The response is made with another key than the one it carries.
+        WrongKey,
+    }
+
+    /// Writes a ceremony of two random contributions followed by the beacon one,
+    /// each built on top of the response preceding it.
+    fn write_transcript(
+        name: &str,
+        tamper: Tamper,
+        parameters: &CeremonyParams<Bn256>,
+    ) -> Vec<PathBuf> {
+        let rng = &mut thread_rng();
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|i| temp_path(&format!("{}_response{}", name, i)))
+            .collect();
+
+        let mut challenge = initial_challenge(parameters);
+        for (i, path) in paths.iter().enumerate() {
+            let challenge_hash = calculate_hash(&challenge);
+            let (public_key, key) = if i == 2 {
+                let digest = iterate_beacon_hash(&BEACON_HASH, 10, |_, _| {});
+                keypair::<_, Bn256>(&mut beacon_rng(&digest), &challenge_hash)
+            } else {
+                keypair::<_, Bn256>(rng, &challenge_hash)
+            };
+            challenge = match (i, &tamper) {
+                (1, Tamper::WrongKey) => {
+                    let (_, other_key) = keypair::<_, Bn256>(rng, &challenge_hash);
+                    contribute(&challenge, &public_key, &other_key, path, parameters)
+                }
+                (1, Tamper::ShiftPower) => {
+                    contribute(&challenge, &public_key, &key, path, parameters);
+                    let mut response = fs::read(path).unwrap();
+                    let g1 = parameters.curve.g1_compressed;
+                    let position = parameters.hash_size + g1;
+                    let next = response[position + g1..position + 2 * g1].to_vec();
+                    response[position..position + g1].copy_from_slice(&next);
+                    fs::write(path, response).unwrap();
+                    next_challenge(&open_response(path, parameters).unwrap(), parameters)
+                }
+                _ => contribute(&challenge, &public_key, &key, path, parameters),
+            };
+        }
+
+        paths
+    }
+
+    fn remove(paths: &[PathBuf]) {
+        for path in paths {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    fn beacon(hash: Vec<u8>, num_iterations_exp: usize) -> Beacon {
+        Beacon {
+            hash,
+            num_iterations_exp,
+        }
+    }
+
+    #[test]
+    fn test_verify_transcript_bn256() {
+        let parameters = CeremonyParams::<Bn256>::new(3, 4);
+        let paths = write_transcript("transcript_valid", Tamper::Nothing, &parameters);
+
+        let reports =
+            verify_transcript(&paths, Some(&beacon(BEACON_HASH.to_vec(), 10)), &parameters)
+                .unwrap();
+        assert_eq!(reports.len(), 3);
+        for (i, report) in reports.iter().enumerate() {
+            let response = open_response(&paths[i], &parameters).unwrap();
+            assert_eq!(report.path, paths[i]);
+            assert_eq!(report.challenge_hash.as_slice(), &response[0..64]);
+            assert_eq!(report.response_hash, calculate_hash(&response));
+            assert_eq!(report.is_beacon, i == 2);
+        }
+
+        // Without a beacon the last contribution is checked as any other
+        let reports = verify_transcript(&paths, None, &parameters).unwrap();
+        assert!(reports.iter().all(|report| !report.is_beacon));
+
+        let mut hash = BEACON_HASH.to_vec();
+        hash[0] ^= 1;
+        for beacon in &[beacon(hash, 10), beacon(BEACON_HASH.to_vec(), 11)] {
+            match verify_transcript(&paths, Some(beacon), &parameters) {
+                Err(TranscriptError::InvalidBeacon { path }) => assert_eq!(path, paths[2]),
+                _ => panic!("transcript verified with a wrong beacon"),
+            }
+        }
+
+        // The responses have to be given in order of contribution
+        match verify_transcript(&paths[1..], None, &parameters) {
+            Err(TranscriptError::HashChainBroken { path }) => assert_eq!(path, paths[1]),
+            _ => panic!("transcript verified without its first contribution"),
+        }
+
+        remove(&paths);
+    }
+
+    #[test]
+    fn test_invalid_transformation_bn256() {
+        let parameters = CeremonyParams::<Bn256>::new(3, 4);
+        for (name, tamper) in [
+            ("transcript_shifted", Tamper::ShiftPower),
+            ("transcript_wrong_key", Tamper::WrongKey),
+        ] {
+            let paths = write_transcript(name, tamper, &parameters);
+            match verify_transcript(&paths, None, &parameters) {
+                Err(TranscriptError::InvalidTransformation { path }) => {
+                    assert_eq!(path, paths[1])
+                }
+                _ => panic!("{}: invalid transformation accepted", name),
+            }
+            remove(&paths);
+        }
+    }
+
+    #[test]
+    fn test_beacon_hash_length() {
+        let parameters = CeremonyParams::<Bn256>::new(2, 4);
+        for length in &[0, 31, 33] {
+            let beacon = Beacon {
+                hash: vec![0; *length],
+                num_iterations_exp: 10,
+            };
+            match verify_transcript(&["missing_response"], Some(&beacon), &parameters) {
+                Err(TranscriptError::InvalidBeaconHashLength(n)) => assert_eq!(n, *length),
+                _ => panic!("beacon hash of {} bytes accepted", length),
+            }
+        }
+    }
+}
@@ -2,6 +2,8 @@ use bellman_ce::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
 use bellman_ce::pairing::*;
 use blake2::{Blake2b, Digest};
 use byteorder::{BigEndian, ReadBytesExt};
+use crypto::digest::Digest as Sha256Digest;
+use crypto::sha2::Sha256;
 use generic_array::GenericArray;
 use rand::chacha::ChaChaRng;
 use rand::{Rand, Rng, SeedableRng};
@@ -44,6 +46,58 @@ pub fn hash_to_g2<E: Engine>(mut digest: &[u8]) -> E::G2 {
     ChaChaRng::from_seed(&seed).gen()
 }
 
+/// The length of the random beacon value, which is hashed with SHA256 in place.
+pub const BEACON_HASH_LENGTH: usize = 32;
+
+/// Performs `2^num_iterations_exp` SHA256 iterations over the random beacon value.
+/// `report` is called with 1024 of the interstitial states so that verification
+/// of the beacon can be parallelized. Requires `num_iterations_exp >= 10` and
+/// `beacon_hash` to be `BEACON_HASH_LENGTH` bytes long.
+pub fn iterate_beacon_hash<F: FnMut(u64, &[u8])>(
+    beacon_hash: &[u8],
+    num_iterations_exp: usize,
+    mut report: F,
+) -> Vec<u8> {
+    assert!(num_iterations_exp >= 10);
+    assert_eq!(beacon_hash.len(), BEACON_HASH_LENGTH);
+
+    let mut cur_hash = beacon_hash.to_vec();
+    for i in 0..(1u64 << num_iterations_exp) {
+        if i % (1u64 << (num_iterations_exp - 10)) == 0 {
+            report(i, &cur_hash);
+        }
+
+        let mut h = Sha256::new();
+        Sha256Digest::input(&mut h, &cur_hash);
+        Sha256Digest::result(&mut h, &mut cur_hash);
+    }
+    cur_hash
+}
+
+/// Creates the RNG used for the random beacon contribution from the final
+/// result of `iterate_beacon_hash`. Panics if `digest` is less than 32 bytes.
+pub fn beacon_rng(mut digest: &[u8]) -> ChaChaRng {
+    assert!(digest.len() >= 32);
+
+    let mut seed = [0u32; 8];
+    for s in &mut seed {
+        *s = digest
+            .read_u32::<BigEndian>()
+            .expect("assertion above guarantees this to work");
+    }
+
+    ChaChaRng::from_seed(&seed)
+}
+
+/// A path named after `name` and the process in the temporary directory,
+/// removing any file left there by an earlier run.
+#[cfg(test)]
+pub(crate) fn temp_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
 #[cfg(test)]
 mod bn256_tests {
     use super::*;
@@ -109,7 +163,9 @@ mod bn256_tests {
     }
 }
 
-fn merge_pairs<E: Engine, G: CurveAffine<Engine = E, Scalar = E::Fr>>(
+/// Construct a single pair (s, s^x) for two vectors of the form
+/// [a, b, c, ...] and [a^x, b^x, c^x, ...].
+pub(crate) fn merge_pairs<E: Engine, G: CurveAffine<Engine = E, Scalar = E::Fr>>(
     v1: &[G],
     v2: &[G],
 ) -> (G, G) {
@@ -266,7 +322,8 @@ fn dense_multiexp_inner<G: CurveAffine>(
                     (*guard).add_assign(&acc);
                 });
             }
-        }).unwrap();
+        })
+        .unwrap();
 
         let this_region = Arc::try_unwrap(arc).unwrap();
 
@@ -5,6 +5,7 @@ rm response*
 rm transcript
 rm phase1radix*
 rm tmp_*
+rm manifest
 
 set -e
 
@@ -24,4 +25,7 @@ cargo run --release --bin verify_transform_constrained challenge3 response3 chal
 cargo run --release --bin beacon_constrained challenge4 response4 $SIZE $BATCH 0000000000000000000a558a61ddc8ee4e488d647a747fe4dcc362fe2026c620 10
 cargo run --release --bin verify_transform_constrained challenge4 response4 challenge5 $SIZE $BATCH
 
+printf 'response1\nresponse2\nresponse3\nresponse4\n' > manifest
+cargo run --release --bin verify_transcript $SIZE $BATCH manifest 0000000000000000000a558a61ddc8ee4e488d647a747fe4dcc362fe2026c620 10
+
 cargo run --release --bin prepare_phase2 response4 $SIZE $BATCH
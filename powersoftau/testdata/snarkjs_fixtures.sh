@@ -0,0 +1,14 @@
+#!/bin/sh
+# Writes snarkjs_power2.ptau, a powers of tau ceremony of power 2 over BN254
+# with one contribution, using the snarkjs CLI. Run it from this directory
+# and check the output in.
+
+set -e
+
+SNARKJS="npx -y snarkjs@0.7.4"
+
+$SNARKJS powersoftau new bn128 2 tmp_power2_0000.ptau
+$SNARKJS powersoftau contribute tmp_power2_0000.ptau snarkjs_power2.ptau --name=fixture -e=fixture
+$SNARKJS powersoftau verify snarkjs_power2.ptau
+
+rm tmp_power2_0000.ptau